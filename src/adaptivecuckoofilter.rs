@@ -0,0 +1,404 @@
+use crate::utils;
+
+use rand_chacha::ChaCha8Rng;
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::cuckoofilter::{self, CuckooTable, Relocate, BUCKET_SIZE, MAX_KICKS};
+use crate::filter::{self, Filter, FilterStats};
+use crate::serialize::{ensure, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
+
+/*
+Adaptive cuckoo filter (Mitzenmacher, Pontarelli and Reviriego, 2018).
+A cuckoo filter that can be told a positive was false, and makes sure the same key is not a false positive again.
+Every slot has a 2 bit selector choosing one of four hash functions for its fingerprint. Alongside the filter, as the
+remote representation, is a store of the key in each slot. When a query for a key that is not stored matches a slot,
+the slot's selector moves on to the next hash function and its fingerprint is worked out again from the stored key,
+after which the query almost certainly no longer matches.
+The store is the caller's, a KeyStore passed to insert, delete and report_false_positive. The filter says which slot
+each key is in, and only reads the store on those calls, never on a query, so the keys can be kept wherever suits the
+caller, such as on disk next to the data the filter guards. The filter itself holds only fingerprints and selectors,
+and the key of its victim.
+Since a fingerprint changes when its selector does, the other bucket cannot come from the fingerprint as in the cuckoo
+filter. Both buckets come from the key instead, and a kicked fingerprint finds its other bucket from its key in the
+store. Otherwise the table is the cuckoo filter's CuckooTable, sized from the capacity and false positive rate, with the
+selector packed above each fingerprint. The store also makes the filter a set: inserting a key already there does
+nothing, and a delete removes exactly the key asked for.
+ */
+
+// the number of fingerprint hash functions a selector chooses from.
+const SELECTORS: usize = 4;
+const SELECTOR_BITS: u32 = 2;
+
+// the remote representation of an adaptive cuckoo filter: the key in each of its slots, from 0 to slots(). a slot the
+// filter has not put a key in may hold anything. a Vec of slots() keys will do.
+pub trait KeyStore {
+    fn key(&self, slot: usize) -> u64;
+
+    fn set_key(&mut self, slot: usize, key: u64);
+}
+
+impl KeyStore for Vec<u64> {
+    fn key(&self, slot: usize) -> u64 {
+        return self[slot];
+    }
+
+    fn set_key(&mut self, slot: usize, key: u64) {
+        self[slot] = key;
+    }
+}
+
+// both buckets from the top l bits of two hashes of the key.
+fn buckets<H: KeyHasher>(hash_functions: &[H], l: u32, key: u64) -> (usize, usize) {
+    let i_1 = hash_functions[0].hash(key).checked_shr(64 - l).unwrap_or(0) as usize;
+    let i_2 = hash_functions[1].hash(key).checked_shr(64 - l).unwrap_or(0) as usize;
+    return (i_1, i_2);
+}
+
+// the entry being moved by the table, with its key, which goes into the store wherever the entry does.
+struct Moving<'a, H> {
+    hash_functions: &'a [H],
+    l: u32,
+    store: &'a mut dyn KeyStore,
+    key: u64,
+}
+
+impl<H: KeyHasher> Relocate for Moving<'_, H> {
+    fn alternate(&self, bucket: usize, _f: u64) -> usize {
+        let (i_1, i_2) = buckets(self.hash_functions, self.l, self.key);
+        return if bucket == i_1 { i_2 } else { i_1 };
+    }
+
+    fn placed(&mut self, slot: usize) {
+        let kicked = self.store.key(slot);
+        self.store.set_key(slot, self.key);
+        self.key = kicked;
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdaptiveCuckooFilter<H = MultiplyShift> {
+    // cells of a fingerprint with its selector above it.
+    pub(crate) table: CuckooTable,
+    l: u32,
+    // the two bucket hash functions, then one fingerprint hash function per selector.
+    hash_functions: Vec<H>,
+    seed: u64,
+    // the key of the table's victim, which has no slot in the store. meaningless when there is no victim.
+    victim_key: u64,
+}
+
+impl AdaptiveCuckooFilter {
+    pub fn new(capacity: usize, false_positive_rate: f64) -> AdaptiveCuckooFilter {
+        return Self::with_hasher(capacity, false_positive_rate);
+    }
+}
+
+impl<H: KeyHasher> AdaptiveCuckooFilter<H> {
+    // same as new, but draws the hash functions from H instead of multiply-shift.
+    pub fn with_hasher(capacity: usize, false_positive_rate: f64) -> Self {
+        return Self::with_seed(capacity, false_positive_rate, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    pub fn with_seed(capacity: usize, false_positive_rate: f64, seed: u64) -> Self {
+        let (bucket_count, fingerprint_bits) = cuckoofilter::sized_for(capacity, false_positive_rate);
+        let mut rng = utils::seeded_rng(seed);
+        let hash_functions = Self::generate_hash_functions(2 + SELECTORS, &mut rng);
+        let table = CuckooTable::new(bucket_count, BUCKET_SIZE, fingerprint_bits, SELECTOR_BITS, false, MAX_KICKS, rng);
+        AdaptiveCuckooFilter {
+            l: table.bucket_count.trailing_zeros(),
+            table,
+            hash_functions,
+            seed,
+            victim_key: 0,
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    pub fn fingerprint_bits(&self) -> u32 {
+        return self.table.fingerprint_bits;
+    }
+
+    // whether an insert ran out of kicks, so that the victim stash is in use.
+    pub fn is_full(&self) -> bool {
+        return self.table.victim.is_some();
+    }
+
+    // returns false if the key is new, both of its buckets are full and the victim stash is in use already.
+    pub fn insert(&mut self, key: u64, store: &mut dyn KeyStore) -> bool {
+        let (i_1, i_2) = self.buckets(key);
+        if self.find(i_1, i_2, key, store).is_some() || self.victim_is(i_1, i_2, key) {
+            return true;
+        }
+        let (cell, had_victim) = (self.cell(key, 0), self.table.victim.is_some());
+        let mut moving = Moving { hash_functions: &self.hash_functions, l: self.l, store, key };
+        if !self.table.insert(i_1, i_2, cell, &mut moving) {
+            return false;
+        }
+        if !had_victim && self.table.victim.is_some() {
+            self.victim_key = moving.key;
+        }
+        return true;
+    }
+
+    // returns false if the key is not in the store.
+    pub fn delete(&mut self, key: u64, store: &mut dyn KeyStore) -> bool {
+        let (i_1, i_2) = self.buckets(key);
+        if self.victim_is(i_1, i_2, key) {
+            self.table.victim = None;
+            return true;
+        }
+        match self.find(i_1, i_2, key, store) {
+            Some(slot) => {
+                self.table.clear(slot);
+                // a delete makes room, so the victim, if there is one, gets another go at finding a slot.
+                let mut moving = Moving { hash_functions: &self.hash_functions, l: self.l, store, key: self.victim_key };
+                self.table.reinsert_victim(&mut moving);
+                if self.table.victim.is_some() {
+                    self.victim_key = moving.key;
+                }
+                return true;
+            }
+            None => return false,
+        }
+    }
+
+    // tell the filter that member(key) was true for a key that was never inserted. every slot that matched it gets a
+    // new fingerprint, so the key is no longer a false positive, unless a new fingerprint happens to match it too.
+    // returns false, and changes nothing, if the key is in the filter after all or did not match.
+    pub fn report_false_positive(&mut self, key: u64, store: &dyn KeyStore) -> bool {
+        let (i_1, i_2) = self.buckets(key);
+        if self.find(i_1, i_2, key, store).is_some() || self.victim_is(i_1, i_2, key) {
+            return false;
+        }
+        let mut adapted = false;
+        for slot in self.table.slots_of(i_1, i_2) {
+            let cell = self.table.get_cell(slot);
+            if self.matches(cell, key) {
+                let selector = (self.selector(cell) + 1) % SELECTORS;
+                self.table.set_cell(slot, self.cell(store.key(slot), selector));
+                adapted = true;
+            }
+        }
+        if let Some(cell) = self.table.victim_in(i_1, i_2) {
+            if self.matches(cell, key) {
+                let selector = (self.selector(cell) + 1) % SELECTORS;
+                self.table.victim.as_mut().unwrap().1 = self.cell(self.victim_key, selector);
+                adapted = true;
+            }
+        }
+        return adapted;
+    }
+
+    fn generate_hash_functions(n: usize, rng: &mut ChaCha8Rng) -> Vec<H> {
+        let mut hash_functions = Vec::new();
+        for _ in 0..n {
+            hash_functions.push(H::from_rng(rng));
+        }
+        return hash_functions;
+    }
+
+    fn buckets(&self, key: u64) -> (usize, usize) {
+        return buckets(&self.hash_functions, self.l, key);
+    }
+
+    // the key's fingerprint under a selector, never zero.
+    fn fingerprint(&self, key: u64, selector: usize) -> u64 {
+        let bits = self.table.fingerprint_bits;
+        let hash = self.hash_functions[2 + selector].hash(key) >> (64 - bits);
+        return hash % ((1 << bits) - 1) + 1;
+    }
+
+    fn cell(&self, key: u64, selector: usize) -> u64 {
+        return self.fingerprint(key, selector) | (selector as u64) << self.table.fingerprint_bits;
+    }
+
+    fn selector(&self, cell: u64) -> usize {
+        return (cell >> self.table.fingerprint_bits) as usize;
+    }
+
+    // whether the cell holds a fingerprint, and it is the key's under the cell's selector.
+    fn matches(&self, cell: u64, key: u64) -> bool {
+        let f = self.table.fingerprint(cell);
+        return f != 0 && f == self.fingerprint(key, self.selector(cell));
+    }
+
+    // the slot holding the key itself. the store is only read where the fingerprint matches.
+    fn find(&self, i_1: usize, i_2: usize, key: u64, store: &dyn KeyStore) -> Option<usize> {
+        return self.table.slots_of(i_1, i_2)
+            .find(|slot| self.matches(self.table.get_cell(*slot), key) && store.key(*slot) == key);
+    }
+
+    fn victim_is(&self, i_1: usize, i_2: usize, key: u64) -> bool {
+        return self.table.victim_in(i_1, i_2).is_some() && self.victim_key == key;
+    }
+}
+
+impl<H: KeyHasher> Filter for AdaptiveCuckooFilter<H> {
+    // only reads the fingerprints and selectors, never the store.
+    fn member(&self, key: u64) -> bool {
+        let (i_1, i_2) = self.buckets(key);
+        if self.table.slots_of(i_1, i_2).any(|slot| self.matches(self.table.get_cell(slot), key)) {
+            return true;
+        }
+        return matches!(self.table.victim_in(i_1, i_2), Some(cell) if self.matches(cell, key));
+    }
+}
+
+impl<H: KeyHasher> FilterStats for AdaptiveCuckooFilter<H> {
+    // every key in the table, and the victim.
+    fn len(&self) -> u64 {
+        return (self.table.occupied + self.table.victim.is_some() as usize) as u64;
+    }
+
+    fn capacity(&self) -> u64 {
+        return self.slots();
+    }
+
+    fn slots(&self) -> u64 {
+        return self.table.slots() as u64;
+    }
+
+    fn occupied(&self) -> u64 {
+        return self.table.occupied as u64;
+    }
+
+    // the fingerprints and selectors. the keys are the caller's.
+    fn size_in_bits(&self) -> usize {
+        return self.table.words.len() * 64;
+    }
+
+    // before any false positives are reported. reporting them only brings it down for the keys reported.
+    fn false_positive_rate(&self) -> f64 {
+        let compared = 2.0 * self.table.bucket_size as f64 * self.fill_ratio();
+        return filter::fingerprint_false_positive_rate(compared, self.table.fingerprint_bits);
+    }
+}
+
+impl<H: SerializableHasher> SerializableFilter for AdaptiveCuckooFilter<H> {
+    const KIND: FilterKind = FilterKind::AdaptiveCuckoo;
+    const HASHER: HasherKind = H::KIND;
+
+    // the store is the caller's, and is saved, if at all, by the caller.
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_u64(self.table.bucket_count as u64);
+        enc.put_u64(self.table.bucket_size as u64);
+        enc.put_u64(self.table.fingerprint_bits as u64);
+        enc.put_u64(self.table.max_kicks as u64);
+        enc.put_u64(self.table.victim.is_some() as u64);
+        let (bucket, cell) = self.table.victim.unwrap_or((0, 0));
+        enc.put_u64(bucket as u64);
+        enc.put_u64(if self.table.victim.is_some() { self.victim_key } else { 0 });
+        enc.put_u64(self.selector(cell) as u64);
+        self.table.encode_rng(enc);
+        enc.put_hashers(&self.hash_functions);
+        enc.put_u64s(&self.table.words);
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let bucket_count = dec.get_usize()?;
+        let bucket_size = dec.get_usize()?;
+        let fingerprint_bits = dec.get_u32()?;
+        let max_kicks = dec.get_usize()?;
+        let has_victim = dec.get_bool()?;
+        let victim_bucket = dec.get_usize()?;
+        let victim_key = dec.get_u64()?;
+        let victim_selector = dec.get_usize()?;
+        let rng = CuckooTable::decode_rng(dec)?;
+        let hash_functions = dec.get_hashers(Some(2 + SELECTORS))?;
+        let words = dec.get_u64s()?;
+        // the victim's cell comes from its key, once the hash functions are in place.
+        let table = CuckooTable {
+            words,
+            bucket_count,
+            bucket_size,
+            fingerprint_bits,
+            extra_bits: SELECTOR_BITS,
+            semi_sorted: false,
+            max_kicks,
+            rng,
+            victim: None,
+            occupied: 0,
+        }.checked()?;
+        let mut filter = AdaptiveCuckooFilter {
+            l: bucket_count.trailing_zeros(),
+            table,
+            hash_functions,
+            seed,
+            victim_key,
+        };
+        if has_victim {
+            let (i_1, i_2) = filter.buckets(victim_key);
+            ensure(victim_selector < SELECTORS && (victim_bucket == i_1 || victim_bucket == i_2), "victim out of range")?;
+            filter.table.victim = Some((victim_bucket, filter.cell(victim_key, victim_selector)));
+        }
+        return Ok(filter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a full filter over keys 1 to n, and its store.
+    fn full_filter(seed: u64) -> (AdaptiveCuckooFilter<MultiplyShift>, Vec<u64>, Vec<u64>) {
+        let mut filter = AdaptiveCuckooFilter::<MultiplyShift>::with_seed(64, 0.01, seed);
+        let mut store = vec![0; filter.slots() as usize];
+        let mut keys = Vec::new();
+        let mut key = 1;
+        while !filter.is_full() {
+            filter.insert(key, &mut store);
+            keys.push(key);
+            key += 1;
+        }
+        return (filter, store, keys);
+    }
+
+    // deleting a key from the victim's bucket makes room for the victim right there. it must take the free slot, and
+    // not kick the empty slot's zero key into the table as if it were stored.
+    #[test]
+    fn delete_with_victim_keeps_absent_keys_out() {
+        for seed in 0..100 {
+            let (mut filter, mut store, _) = full_filter(seed);
+            let zero_was_member = filter.member(0);
+            let (bucket, _) = filter.table.victim.unwrap();
+            let len = filter.len();
+            assert!(filter.delete(store[bucket * filter.table.bucket_size], &mut store));
+            assert!(filter.table.victim.is_none());
+            assert_eq!(filter.len(), len - 1);
+            assert!(zero_was_member || !filter.member(0));
+            assert!(!filter.delete(0, &mut store));
+            let copy = AdaptiveCuckooFilter::<MultiplyShift>::from_bytes(&filter.to_bytes()).unwrap();
+            assert_eq!(copy.len(), filter.len());
+        }
+    }
+
+    // the keys move around the store as they are kicked, and every one is still found, deleted and told apart from a
+    // reported false positive, before and after a round trip through bytes.
+    #[test]
+    fn reported_false_positives_stop_and_keys_stay() {
+        for seed in 0..20 {
+            let (filter, mut store, keys) = full_filter(seed);
+            let mut filter = AdaptiveCuckooFilter::<MultiplyShift>::from_bytes(&filter.to_bytes()).unwrap();
+            let mut reported = Vec::new();
+            // scattered, since multiply-shift can leave a run of sequential keys with no false positives at all.
+            for key in (1_000_000..1_010_000u64).map(|key| key.wrapping_mul(0x9e37_79b9_7f4a_7c15)) {
+                if filter.member(key) {
+                    assert!(filter.report_false_positive(key, &store));
+                    reported.push(key);
+                }
+            }
+            assert!(!reported.is_empty());
+            assert!(reported.iter().filter(|key| filter.member(**key)).count() * 10 < reported.len());
+            assert!(keys.iter().all(|key| filter.member(*key) && !filter.report_false_positive(*key, &store)));
+            for key in &keys {
+                assert!(filter.delete(*key, &mut store));
+            }
+            assert_eq!(filter.len(), 0);
+        }
+    }
+}
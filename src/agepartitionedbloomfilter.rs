@@ -0,0 +1,270 @@
+use chrono::{DateTime, Utc};
+use rand_chacha::ChaCha8Rng;
+
+use crate::bitvector;
+
+use crate::utils;
+
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::filter::{DynamicFilter, Filter, FilterStats};
+use crate::serialize::{ensure, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
+use crate::window::{Clock, Window};
+
+/*
+Age-partitioned Bloom filter (Shtul, Baquero and Almeida, 2020).
+A filter over a sliding window of a stream: keys are remembered for the window after they were inserted, then forgotten.
+The bits are split into k + l slices that form a ring, each with a hash function of its own. A key sets one bit in
+each of the k youngest slices. The window is cut into l generations, and at the end of each one the oldest slice is
+cleared and becomes the youngest, so every slice ages by one. A query asks whether k consecutive slices all have the
+key's bit set: a key keeps k consecutive slices for l generations after the one it was inserted in, so it is never a
+false negative in the window, and once its youngest slice has been cleared it is forgotten.
+Each slice holds k generations of keys, and is sized to be half full when it is cleared.
+ */
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AgePartitionedBloomFilter<H = MultiplyShift> {
+    bit_array: bitvector::BitVector,
+    // one per slice, by where the slice is in the bit array rather than by its age.
+    hash_functions: Vec<H>,
+    seed: u64,
+    // k, the slices a key sets, and the bits in each slice, a multiple of 8 so that each slice starts on a byte.
+    num_hashes: usize,
+    slice_size: u64,
+    // the youngest slice. the slice of age i is (head + i) % (k + l).
+    head: usize,
+    // keys inserted in the generation each slice was youngest for.
+    generation_inserts: Vec<u64>,
+    expected_inserts: u64,
+    clock: Clock,
+}
+
+impl AgePartitionedBloomFilter {
+    pub fn new(window: Window, false_positive_rate: f64) -> AgePartitionedBloomFilter {
+        return Self::with_hasher(window, false_positive_rate);
+    }
+}
+
+impl<H: KeyHasher> AgePartitionedBloomFilter<H> {
+    // same as new, but draws the hash functions from H instead of multiply-shift.
+    pub fn with_hasher(window: Window, false_positive_rate: f64) -> Self {
+        return Self::with_seed(window, false_positive_rate, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    pub fn with_seed(window: Window, false_positive_rate: f64, seed: u64) -> Self {
+        assert!(false_positive_rate > 0.0 && false_positive_rate < 1.0, "false positive rate must be between 0 and 1");
+        let mut rng = utils::seeded_rng(seed);
+        // a query has l + 1 runs of k slices that could match, each with a rate of about 2^-k once full, so
+        // k = log2((l + 1) / fpr). taking l = k as well is the paper's balance between memory and how promptly keys
+        // are forgotten.
+        let mut num_hashes = ((-false_positive_rate.log2()).ceil() as usize).max(1);
+        loop {
+            let next = ((num_hashes as f64 + 1.0) / false_positive_rate).log2().ceil() as usize;
+            if next <= num_hashes {
+                break;
+            }
+            num_hashes = next;
+        }
+        let generations = num_hashes;
+        let expected_inserts = window.expected_inserts().max(1);
+        let generation_size = expected_inserts.div_ceil(generations as u64);
+        let slice_size = ((num_hashes as u64 * generation_size) as f64 / std::f64::consts::LN_2).ceil() as u64;
+        let slice_size = slice_size.div_ceil(8).max(1) * 8;
+        let num_slices = num_hashes + generations;
+        AgePartitionedBloomFilter {
+            bit_array: bitvector::BitVector::new(slice_size * num_slices as u64),
+            hash_functions: Self::generate_hash_functions(num_slices, &mut rng),
+            seed,
+            num_hashes,
+            slice_size,
+            head: 0,
+            generation_inserts: vec![0; num_slices],
+            expected_inserts,
+            clock: Clock::new(window, generations as u64),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    // the generations a key is remembered for after the one it was inserted in.
+    pub fn generations(&self) -> usize {
+        return self.hash_functions.len() - self.num_hashes;
+    }
+
+    // as insert, at the time now rather than the time on the clock. a filter with a window of inserts ignores now.
+    // times that go backwards are taken to be the latest time seen.
+    pub fn insert_at(&mut self, key: u64, now: DateTime<Utc>) -> bool {
+        let generations = self.clock.insert(now);
+        self.shift(generations);
+        for age in 0..self.num_hashes {
+            let slice = self.slice(age);
+            let index = self.index(key, slice);
+            self.bit_array.insert(index);
+        }
+        self.generation_inserts[self.head] += 1;
+        return true;
+    }
+
+    // forget the keys that have left a timed window. inserts do this anyway, so it is only needed when the stream
+    // goes quiet.
+    pub fn expire(&mut self) {
+        self.expire_at(Utc::now());
+    }
+
+    pub fn expire_at(&mut self, now: DateTime<Utc>) {
+        let generations = self.clock.advance(now);
+        self.shift(generations);
+    }
+
+    fn generate_hash_functions(n: usize, rng: &mut ChaCha8Rng) -> Vec<H> {
+        let mut hash_functions = Vec::new();
+        for _ in 0..n {
+            hash_functions.push(H::from_rng(rng));
+        }
+        return hash_functions;
+    }
+
+    fn slice(&self, age: usize) -> usize {
+        return (self.head + age) % self.hash_functions.len();
+    }
+
+    // the bit the key sets in the slice. as in the partitioned Bloom filter, the 64 bit hash is scaled to the slice in
+    // 128 bits.
+    fn index(&self, key: u64, slice: usize) -> u64 {
+        let hash = self.hash_functions[slice].hash(key) as u128;
+        return slice as u64 * self.slice_size + ((hash * self.slice_size as u128) >> 64) as u64;
+    }
+
+    fn slice_bytes(&self, slice: usize) -> std::ops::Range<usize> {
+        let bytes = (self.slice_size / 8) as usize;
+        return slice * bytes..(slice + 1) * bytes;
+    }
+
+    fn slice_fill(&self, slice: usize) -> f64 {
+        let ones: u32 = self.bit_array.array[self.slice_bytes(slice)].iter().map(|byte| byte.count_ones()).sum();
+        return ones as f64 / self.slice_size as f64;
+    }
+
+    // end the current generation, generations times. the oldest slice is cleared and becomes the youngest.
+    fn shift(&mut self, generations: u64) {
+        let num_slices = self.hash_functions.len();
+        for _ in 0..generations.min(num_slices as u64) {
+            self.head = (self.head + num_slices - 1) % num_slices;
+            let bytes = self.slice_bytes(self.head);
+            self.bit_array.array[bytes].fill(0);
+            self.generation_inserts[self.head] = 0;
+        }
+    }
+}
+
+impl<H: KeyHasher> DynamicFilter for AgePartitionedBloomFilter<H> {
+    fn insert(&mut self, key: u64) -> bool {
+        return self.insert_at(key, Utc::now());
+    }
+}
+
+impl<H: KeyHasher> Filter for AgePartitionedBloomFilter<H> {
+    // looks for k consecutive slices with the key's bit set, from the youngest.
+    fn member(&self, key: u64) -> bool {
+        let num_slices = self.hash_functions.len();
+        let mut run = 0;
+        for age in 0..num_slices {
+            if run + num_slices - age < self.num_hashes {
+                return false;
+            }
+            let slice = self.slice(age);
+            if self.bit_array.member(self.index(key, slice)) {
+                run += 1;
+                if run == self.num_hashes {
+                    return true;
+                }
+            } else {
+                run = 0;
+            }
+        }
+        return false;
+    }
+}
+
+impl<H: KeyHasher> FilterStats for AgePartitionedBloomFilter<H> {
+    // keys inserted in the generations that are still remembered.
+    fn len(&self) -> u64 {
+        return (0..=self.generations()).map(|age| self.generation_inserts[self.slice(age)]).sum();
+    }
+
+    fn capacity(&self) -> u64 {
+        return self.expected_inserts;
+    }
+
+    fn slots(&self) -> u64 {
+        return self.slice_size * self.hash_functions.len() as u64;
+    }
+
+    fn occupied(&self) -> u64 {
+        return self.bit_array.count_ones();
+    }
+
+    fn size_in_bits(&self) -> usize {
+        return self.bit_array.array.len() * 8;
+    }
+
+    // a key not in the filter is a false positive if any of the l + 1 runs of k slices has all of its bits set.
+    fn false_positive_rate(&self) -> f64 {
+        let fills: Vec<f64> = (0..self.hash_functions.len()).map(|age| self.slice_fill(self.slice(age))).collect();
+        let mut none = 1.0;
+        for run in fills.windows(self.num_hashes) {
+            none *= 1.0 - run.iter().product::<f64>();
+        }
+        return 1.0 - none;
+    }
+}
+
+impl<H: SerializableHasher> SerializableFilter for AgePartitionedBloomFilter<H> {
+    const KIND: FilterKind = FilterKind::AgePartitionedBloom;
+    const HASHER: HasherKind = H::KIND;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_u64(self.num_hashes as u64);
+        enc.put_u64(self.slice_size);
+        enc.put_u64(self.head as u64);
+        enc.put_u64(self.expected_inserts);
+        self.clock.encode(enc);
+        enc.put_hashers(&self.hash_functions);
+        enc.put_u64s(&self.generation_inserts);
+        enc.put_u8s(&self.bit_array.array);
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let num_hashes = dec.get_usize()?;
+        let slice_size = dec.get_u64()?;
+        let head = dec.get_usize()?;
+        let expected_inserts = dec.get_u64()?;
+        let clock = Clock::decode(dec)?;
+        let hash_functions = dec.get_hashers(None)?;
+        let generation_inserts = dec.get_u64s()?;
+        let array = dec.get_u8s()?;
+        let num_slices = hash_functions.len();
+        ensure(num_hashes > 0 && num_hashes < num_slices, "slice count out of range")?;
+        ensure(slice_size > 0 && slice_size <= u32::MAX as u64 && slice_size.is_multiple_of(8), "slice size out of range")?;
+        ensure(head < num_slices, "youngest slice out of range")?;
+        ensure(generation_inserts.len() == num_slices, "insert counts do not match the slices")?;
+        ensure(slice_size.checked_mul(num_slices as u64).map(|bits| bits / 8) == Some(array.len() as u64),
+               "bit array does not match the slices")?;
+        return Ok(AgePartitionedBloomFilter {
+            bit_array: bitvector::BitVector { array },
+            hash_functions,
+            seed,
+            num_hashes,
+            slice_size,
+            head,
+            generation_inserts,
+            expected_inserts,
+            clock,
+        });
+    }
+}
@@ -0,0 +1,72 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use diss::cuckoo::{AdaptiveCuckooFilter, CuckooFilter};
+use diss::filter::{Filter, FilterStats};
+use diss::keygenerator::{KeyGenerator, SAMPLE_SIZE};
+
+mod harness;
+
+// lookups in the skewed stream, how many different keys they are drawn from, and how skewed it is.
+const QUERIES: usize = 10_000_000;
+const DISTINCT: usize = 1_000_000;
+const EXPONENT: f64 = 1.0;
+
+fn plain(keys: &KeyGenerator) -> CuckooFilter {
+    return harness::build(CuckooFilter::with_fpr(SAMPLE_SIZE as usize, 0.01), &keys.disjoint.0);
+}
+
+// the adaptive filter and its key store, which here is just a Vec.
+fn adaptive(keys: &KeyGenerator) -> (AdaptiveCuckooFilter, Vec<u64>) {
+    let mut filter = AdaptiveCuckooFilter::new(SAMPLE_SIZE as usize, 0.01);
+    let mut store = vec![0; filter.slots() as usize];
+    for key in &keys.disjoint.0 {
+        filter.insert(*key, &mut store);
+    }
+    return (filter, store);
+}
+
+// the false positive rate over a stream of non-members, printed once next to the timings. the adaptive filter is told
+// about each false positive as it happens, the way a cache would after the disk read finds nothing.
+fn report_skewed_fpr() {
+    let keys = harness::load_keys();
+    let queries = keys.skewed_queries(QUERIES, DISTINCT, EXPONENT);
+    let plain_filter = plain(&keys);
+    let (mut adaptive_filter, store) = adaptive(&keys);
+    let plain_false_positives = queries.iter().filter(|key| plain_filter.member(**key)).count();
+    let mut adaptive_false_positives = 0;
+    for key in &queries {
+        if adaptive_filter.member(*key) {
+            adaptive_false_positives += 1;
+            adaptive_filter.report_false_positive(*key, &store);
+        }
+    }
+    println!("skewed stream of {} lookups over {} keys, exponent {}", QUERIES, DISTINCT, EXPONENT);
+    println!("cuckoo filter: fpr {}", plain_false_positives as f64 / QUERIES as f64);
+    println!("adaptive cuckoo filter: fpr {}", adaptive_false_positives as f64 / QUERIES as f64);
+}
+
+fn bench_adaptive_cuckoo_filter_uniform_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let (adaptive_cuckoo_filter, _) = adaptive(&keys);
+    harness::bench_member(c, "bench_adaptive_cuckoo_filter_uniform_member", &adaptive_cuckoo_filter, &keys.disjoint.0);
+}
+
+fn bench_adaptive_cuckoo_filter_disjoint_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let (adaptive_cuckoo_filter, _) = adaptive(&keys);
+    harness::bench_member(c, "bench_adaptive_cuckoo_filter_disjoint_member", &adaptive_cuckoo_filter, &keys.disjoint.1);
+}
+
+fn bench_adaptive_cuckoo_filter_skewed_member(c: &mut Criterion) {
+    report_skewed_fpr();
+    let keys = harness::load_keys();
+    let queries = keys.skewed_queries(QUERIES, DISTINCT, EXPONENT);
+    let plain_filter = plain(&keys);
+    let (adaptive_cuckoo_filter, _) = adaptive(&keys);
+    harness::bench_member(c, "bench_cuckoo_filter_skewed_member", &plain_filter, &queries);
+    harness::bench_member(c, "bench_adaptive_cuckoo_filter_skewed_member", &adaptive_cuckoo_filter, &queries);
+}
+
+
+criterion_group!(benches, bench_adaptive_cuckoo_filter_uniform_member, bench_adaptive_cuckoo_filter_disjoint_member,
+    bench_adaptive_cuckoo_filter_skewed_member);
+criterion_main!(benches);
@@ -0,0 +1,34 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use diss::bloom::{AgePartitionedBloomFilter, Window};
+use diss::keygenerator::SAMPLE_SIZE;
+
+mod harness;
+
+fn bench_age_partitioned_bloom_filter_uniform_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let age_partitioned_bloom_filter = harness::build(AgePartitionedBloomFilter::new(Window::Inserts(SAMPLE_SIZE), 0.01), &keys.disjoint.0);
+    harness::bench_member(c, "bench_age_partitioned_bloom_filter_uniform_member", &age_partitioned_bloom_filter, &keys.disjoint.0);
+}
+
+fn bench_age_partitioned_bloom_filter_mixed_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let age_partitioned_bloom_filter = harness::build(AgePartitionedBloomFilter::new(Window::Inserts(SAMPLE_SIZE), 0.01), &keys.mixed.0);
+    harness::bench_member(c, "bench_age_partitioned_bloom_filter_mixed_member", &age_partitioned_bloom_filter, &keys.mixed.0);
+}
+
+fn bench_age_partitioned_bloom_filter_disjoint_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let age_partitioned_bloom_filter = harness::build(AgePartitionedBloomFilter::new(Window::Inserts(SAMPLE_SIZE), 0.01), &keys.disjoint.0);
+    harness::bench_member(c, "bench_age_partitioned_bloom_filter_disjoint_member", &age_partitioned_bloom_filter, &keys.disjoint.1);
+}
+
+fn bench_age_partitioned_bloom_filter_random_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let age_partitioned_bloom_filter = harness::build(AgePartitionedBloomFilter::new(Window::Inserts(SAMPLE_SIZE), 0.01), &keys.random.0);
+    harness::bench_member(c, "bench_age_partitioned_bloom_filter_random_member", &age_partitioned_bloom_filter, &keys.random.0);
+}
+
+
+criterion_group!(benches, bench_age_partitioned_bloom_filter_uniform_member, bench_age_partitioned_bloom_filter_disjoint_member,
+    bench_age_partitioned_bloom_filter_mixed_member, bench_age_partitioned_bloom_filter_random_member);
+criterion_main!(benches);
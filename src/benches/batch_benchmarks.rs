@@ -0,0 +1,56 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use diss::binaryfuse::{FourWiseBinaryFuseFilter8, ThreeWiseBinaryFuseFilter8};
+use diss::bloom::{BlockedBloomFilter, BloomFilter};
+use diss::cuckoo::CuckooFilter;
+use diss::keygenerator::SAMPLE_SIZE;
+use diss::xor::XorFilter8;
+
+mod harness;
+
+// each filter answers the same random queries one key at a time and through member_batch.
+fn bench_bloom_filter_batch_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let filter = harness::build(BloomFilter::new(SAMPLE_SIZE, 0.01), &keys.random.0);
+    harness::bench_member(c, "bench_bloom_filter_scalar_member", &filter, &keys.random.1);
+    harness::bench_member_batch(c, "bench_bloom_filter_batch_member", &filter, &keys.random.1);
+}
+
+fn bench_blocked_bloom_filter_batch_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let filter = harness::build(BlockedBloomFilter::new(SAMPLE_SIZE, 512, 0.01), &keys.random.0);
+    harness::bench_member(c, "bench_blocked_bloom_filter_scalar_member", &filter, &keys.random.1);
+    harness::bench_member_batch(c, "bench_blocked_bloom_filter_batch_member", &filter, &keys.random.1);
+}
+
+fn bench_cuckoo_filter_batch_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let filter = harness::build(CuckooFilter::new((SAMPLE_SIZE as f64*1.1) as usize, 1000_usize, 2), &keys.random.0);
+    harness::bench_member(c, "bench_cuckoo_filter_scalar_member", &filter, &keys.random.1);
+    harness::bench_member_batch(c, "bench_cuckoo_filter_batch_member", &filter, &keys.random.1);
+}
+
+fn bench_xor8_filter_batch_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let filter = XorFilter8::new(&keys.random.0);
+    harness::bench_member(c, "bench_xor8_filter_scalar_member", &filter, &keys.random.1);
+    harness::bench_member_batch(c, "bench_xor8_filter_batch_member", &filter, &keys.random.1);
+}
+
+fn bench_binary3_fuse8_filter_batch_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let filter = ThreeWiseBinaryFuseFilter8::new(&keys.random.0);
+    harness::bench_member(c, "bench_binary3_fuse8_filter_scalar_member", &filter, &keys.random.1);
+    harness::bench_member_batch(c, "bench_binary3_fuse8_filter_batch_member", &filter, &keys.random.1);
+}
+
+fn bench_binary4_fuse8_filter_batch_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let filter = FourWiseBinaryFuseFilter8::new(&keys.random.0);
+    harness::bench_member(c, "bench_binary4_fuse8_filter_scalar_member", &filter, &keys.random.1);
+    harness::bench_member_batch(c, "bench_binary4_fuse8_filter_batch_member", &filter, &keys.random.1);
+}
+
+
+criterion_group!(benches, bench_bloom_filter_batch_member, bench_blocked_bloom_filter_batch_member, bench_cuckoo_filter_batch_member,
+    bench_xor8_filter_batch_member, bench_binary3_fuse8_filter_batch_member, bench_binary4_fuse8_filter_batch_member);
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use diss::cuckoo::CountingCuckooFilter;
+use diss::keygenerator::SAMPLE_SIZE;
+
+mod harness;
+
+fn bench_counting_cuckoo_filter_uniform_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let counting_cuckoo_filter = harness::build(CountingCuckooFilter::new(SAMPLE_SIZE as usize, 0.01), &keys.disjoint.0);
+    harness::bench_member(c, "bench_counting_cuckoo_filter_uniform_member", &counting_cuckoo_filter, &keys.disjoint.0);
+}
+
+fn bench_counting_cuckoo_filter_mixed_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let counting_cuckoo_filter = harness::build(CountingCuckooFilter::new(SAMPLE_SIZE as usize, 0.01), &keys.mixed.0);
+    harness::bench_member(c, "bench_counting_cuckoo_filter_mixed_member", &counting_cuckoo_filter, &keys.mixed.0);
+}
+
+fn bench_counting_cuckoo_filter_disjoint_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let counting_cuckoo_filter = harness::build(CountingCuckooFilter::new(SAMPLE_SIZE as usize, 0.01), &keys.disjoint.0);
+    harness::bench_member(c, "bench_counting_cuckoo_filter_disjoint_member", &counting_cuckoo_filter, &keys.disjoint.1);
+}
+
+fn bench_counting_cuckoo_filter_random_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let counting_cuckoo_filter = harness::build(CountingCuckooFilter::new(SAMPLE_SIZE as usize, 0.01), &keys.random.0);
+    harness::bench_member(c, "bench_counting_cuckoo_filter_random_member", &counting_cuckoo_filter, &keys.random.0);
+}
+
+// a sixteenth of the keys, each inserted sixteen times, so that most cells count past their counter.
+fn bench_counting_cuckoo_filter_duplicate_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let distinct = &keys.disjoint.0[..keys.disjoint.0.len() / 16];
+    let duplicated: Vec<u64> = distinct.iter().flat_map(|key| [*key; 16]).collect();
+    let counting_cuckoo_filter = harness::build(CountingCuckooFilter::new(distinct.len(), 0.01), &duplicated);
+    harness::bench_member(c, "bench_counting_cuckoo_filter_duplicate_member", &counting_cuckoo_filter, &keys.disjoint.1);
+}
+
+
+criterion_group!(benches, bench_counting_cuckoo_filter_uniform_member, bench_counting_cuckoo_filter_disjoint_member,
+    bench_counting_cuckoo_filter_mixed_member, bench_counting_cuckoo_filter_random_member,
+    bench_counting_cuckoo_filter_duplicate_member);
+criterion_main!(benches);
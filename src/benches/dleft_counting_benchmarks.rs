@@ -0,0 +1,34 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use diss::bloom::DLeftCountingFilter;
+use diss::keygenerator::SAMPLE_SIZE;
+
+mod harness;
+
+fn bench_dleft_counting_filter_uniform_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let dleft_counting_filter = harness::build(DLeftCountingFilter::new(SAMPLE_SIZE, 0.01), &keys.disjoint.0);
+    harness::bench_member(c, "bench_dleft_counting_filter_uniform_member", &dleft_counting_filter, &keys.disjoint.0);
+}
+
+fn bench_dleft_counting_filter_mixed_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let dleft_counting_filter = harness::build(DLeftCountingFilter::new(SAMPLE_SIZE, 0.01), &keys.mixed.0);
+    harness::bench_member(c, "bench_dleft_counting_filter_mixed_member", &dleft_counting_filter, &keys.mixed.0);
+}
+
+fn bench_dleft_counting_filter_disjoint_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let dleft_counting_filter = harness::build(DLeftCountingFilter::new(SAMPLE_SIZE, 0.01), &keys.disjoint.0);
+    harness::bench_member(c, "bench_dleft_counting_filter_disjoint_member", &dleft_counting_filter, &keys.disjoint.1);
+}
+
+fn bench_dleft_counting_filter_random_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let dleft_counting_filter = harness::build(DLeftCountingFilter::new(SAMPLE_SIZE, 0.01), &keys.random.0);
+    harness::bench_member(c, "bench_dleft_counting_filter_random_member", &dleft_counting_filter, &keys.random.0);
+}
+
+
+criterion_group!(benches, bench_dleft_counting_filter_uniform_member, bench_dleft_counting_filter_disjoint_member,
+    bench_dleft_counting_filter_mixed_member, bench_dleft_counting_filter_random_member);
+criterion_main!(benches);
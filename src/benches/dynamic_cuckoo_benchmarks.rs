@@ -0,0 +1,34 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use diss::cuckoo::DynamicCuckooFilter;
+use diss::keygenerator::SAMPLE_SIZE;
+
+mod harness;
+
+fn bench_dynamic_cuckoo_filter_uniform_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let dynamic_cuckoo_filter = harness::build(DynamicCuckooFilter::new((SAMPLE_SIZE / 16) as usize, 0.01), &keys.disjoint.0);
+    harness::bench_member(c, "bench_dynamic_cuckoo_filter_uniform_member", &dynamic_cuckoo_filter, &keys.disjoint.0);
+}
+
+fn bench_dynamic_cuckoo_filter_mixed_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let dynamic_cuckoo_filter = harness::build(DynamicCuckooFilter::new((SAMPLE_SIZE / 16) as usize, 0.01), &keys.mixed.0);
+    harness::bench_member(c, "bench_dynamic_cuckoo_filter_mixed_member", &dynamic_cuckoo_filter, &keys.mixed.0);
+}
+
+fn bench_dynamic_cuckoo_filter_disjoint_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let dynamic_cuckoo_filter = harness::build(DynamicCuckooFilter::new((SAMPLE_SIZE / 16) as usize, 0.01), &keys.disjoint.0);
+    harness::bench_member(c, "bench_dynamic_cuckoo_filter_disjoint_member", &dynamic_cuckoo_filter, &keys.disjoint.1);
+}
+
+fn bench_dynamic_cuckoo_filter_random_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let dynamic_cuckoo_filter = harness::build(DynamicCuckooFilter::new((SAMPLE_SIZE / 16) as usize, 0.01), &keys.random.0);
+    harness::bench_member(c, "bench_dynamic_cuckoo_filter_random_member", &dynamic_cuckoo_filter, &keys.random.0);
+}
+
+
+criterion_group!(benches, bench_dynamic_cuckoo_filter_uniform_member, bench_dynamic_cuckoo_filter_disjoint_member,
+    bench_dynamic_cuckoo_filter_mixed_member, bench_dynamic_cuckoo_filter_random_member);
+criterion_main!(benches);
@@ -0,0 +1,77 @@
+#![allow(dead_code)]
+/*
+Shared benchmarking code. Every bench file builds its filter and then hands it to these functions, so the
+timing loop is the same for every filter.
+ */
+use std::time::Instant;
+use criterion::{black_box, Criterion};
+use diss::filter::{DynamicFilter, Filter, StaticFilter};
+use diss::keygenerator::KeyGenerator;
+
+pub fn load_keys() -> KeyGenerator {
+    let mut keys = KeyGenerator::new_empty();
+    keys.read_from_file().expect("");
+    return keys;
+}
+
+// insert every key into an empty dynamic filter.
+pub fn build<F: DynamicFilter>(mut filter: F, keys: &[u64]) -> F {
+    for key in keys {
+        filter.insert(*key);
+    }
+    return filter;
+}
+
+// criterion picks the number of iterations, so walk the query keys as many times as needed
+// and finish with a partial pass over the remainder.
+pub fn bench_member<F: Filter + ?Sized>(c: &mut Criterion, name: &str, filter: &F, queries: &[u64]) {
+    let n = queries.len() as u64;
+    c.bench_function(name, |b| {
+        b.iter_custom(|iters| {
+            let num_runs = iters / n;
+            let remainder = (iters % n) as usize;
+            let start = Instant::now();
+            for _ in 0..num_runs {
+                for key in queries {
+                    black_box(filter.member(*key));
+                }
+            }
+            for key in &queries[..remainder] {
+                black_box(filter.member(*key));
+            }
+            return start.elapsed();
+        });
+    });
+}
+
+// as bench_member, but the queries go through member_batch. an iteration is still one key.
+pub fn bench_member_batch<F: Filter + ?Sized>(c: &mut Criterion, name: &str, filter: &F, queries: &[u64]) {
+    let n = queries.len() as u64;
+    let mut out = vec![false; queries.len()];
+    c.bench_function(name, |b| {
+        b.iter_custom(|iters| {
+            let num_runs = iters / n;
+            let remainder = (iters % n) as usize;
+            let start = Instant::now();
+            for _ in 0..num_runs {
+                filter.member_batch(queries, &mut out);
+                black_box(&out);
+            }
+            filter.member_batch(&queries[..remainder], &mut out[..remainder]);
+            black_box(&out);
+            return start.elapsed();
+        });
+    });
+}
+
+pub fn bench_create<F: StaticFilter>(c: &mut Criterion, name: &str, keys: &[u64]) {
+    c.bench_function(name, |b| {
+        b.iter_custom(|iters| {
+            let start = Instant::now();
+            for _ in 0..iters {
+                black_box(F::from_keys(keys));
+            }
+            return start.elapsed();
+        });
+    });
+}
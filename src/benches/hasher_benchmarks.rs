@@ -0,0 +1,42 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use diss::bloom::BloomFilter;
+use diss::hasher::{AHash, Fnv, KeyHasher, Murmur3, MultiplyShift, TabulationHashing};
+use diss::keygenerator::SAMPLE_SIZE;
+use diss::xor::XorFilter8;
+
+mod harness;
+
+/*
+Compare the hash functions on the same filters. The filter code is identical, only the KeyHasher changes.
+ */
+
+fn bench_bloom_filter_with<H: KeyHasher>(c: &mut Criterion, name: &str) {
+    let keys = harness::load_keys();
+    let bloom_filter = harness::build(BloomFilter::<H>::with_hasher(SAMPLE_SIZE, 0.01), &keys.disjoint.0);
+    harness::bench_member(c, name, &bloom_filter, &keys.disjoint.0);
+}
+
+fn bench_xor8_filter_with<H: KeyHasher>(c: &mut Criterion, name: &str) {
+    let keys = harness::load_keys();
+    let xor_filter = XorFilter8::<H>::with_hasher(&keys.disjoint.0);
+    harness::bench_member(c, name, &xor_filter, &keys.disjoint.0);
+}
+
+fn bench_bloom_filter_hashers(c: &mut Criterion) {
+    bench_bloom_filter_with::<MultiplyShift>(c, "bench_bloom_filter_multiply_shift_member");
+    bench_bloom_filter_with::<TabulationHashing>(c, "bench_bloom_filter_tabulation_member");
+    bench_bloom_filter_with::<Murmur3>(c, "bench_bloom_filter_murmur3_member");
+    bench_bloom_filter_with::<AHash>(c, "bench_bloom_filter_ahash_member");
+    bench_bloom_filter_with::<Fnv>(c, "bench_bloom_filter_fnv_member");
+}
+
+fn bench_xor8_filter_hashers(c: &mut Criterion) {
+    bench_xor8_filter_with::<MultiplyShift>(c, "bench_xor8_filter_multiply_shift_member");
+    bench_xor8_filter_with::<TabulationHashing>(c, "bench_xor8_filter_tabulation_member");
+    bench_xor8_filter_with::<Murmur3>(c, "bench_xor8_filter_murmur3_member");
+    bench_xor8_filter_with::<AHash>(c, "bench_xor8_filter_ahash_member");
+    bench_xor8_filter_with::<Fnv>(c, "bench_xor8_filter_fnv_member");
+}
+
+criterion_group!(benches, bench_bloom_filter_hashers, bench_xor8_filter_hashers);
+criterion_main!(benches);
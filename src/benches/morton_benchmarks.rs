@@ -1,186 +1,34 @@
-// let morton_filter = RefCell::new(mortonfilter::mortonFilter::new((SAMPLE_SIZE as f64*1.1) as usize, 1000 as usize, 4));
-use std::cell::RefCell;
-use std::time::Instant;
-use criterion::{black_box, Criterion, criterion_group, criterion_main};
-
-#[path = "../mortonfilter.rs"]
-mod mortonfilter;
-
-#[path = "../keygenerator.rs"]
-mod keygenerator;
-
-static  SAMPLE_SIZE: u64 = keygenerator::SAMPLE_SIZE;
+use criterion::{Criterion, criterion_group, criterion_main};
+use diss::morton::MortonFilter;
+use diss::keygenerator::SAMPLE_SIZE;
 
+mod harness;
 
 fn bench_morton_filter_uniform_member(c: &mut Criterion) {
-    // setup
-    let mut keys = keygenerator::KeyGenerator::new(SAMPLE_SIZE);
-    keys.write_to_file().expect("");
-    let disjoint_keys = keys.disjoint;
-    let mut  morton_filter = RefCell::new(mortonfilter::MortonFilter::new((SAMPLE_SIZE as f64 * 1.1)as u64, 0.01));
-    for i in &disjoint_keys.0 {
-        morton_filter.borrow_mut().insert(*i);
-    }
-
-    // custom benchmarking function.
-    c.bench_function("bench_morton_filter_uniform_member", |b| {
-        b.iter_custom(|iters| {
-            let mut num_runs = (iters as f64 / SAMPLE_SIZE as f64).ceil() as u64;
-            let remainder = iters % SAMPLE_SIZE;
-            if num_runs <= 1 {
-                //  just run the for loop form 0 - iters in here.
-                let start = Instant::now();
-                for i in 0..(iters as usize) {
-                    black_box(morton_filter.borrow().member(disjoint_keys.0[i]));
-                    // xor_filter.borrow().member(random_keys.1[i]);
-                }
-                return start.elapsed();
-            }
-            else {
-                num_runs -= 1;
-                let start = Instant::now();
-                for _ in 0..num_runs {
-                    for i in 0..(SAMPLE_SIZE as usize) {
-                        // check 1st pair, i.e the same that we inserted.
-                        black_box(morton_filter.borrow().member(disjoint_keys.0[i]));
-                    }
-                }
-                for i in 0..(remainder as usize) {
-                    black_box(morton_filter.borrow().member(disjoint_keys.0[i]));
-                }
-                return start.elapsed();
-            }
-
-        });
-    });
+    let keys = harness::load_keys();
+    let morton_filter = harness::build(MortonFilter::new(SAMPLE_SIZE), &keys.disjoint.0);
+    harness::bench_member(c, "bench_morton_filter_uniform_member", &morton_filter, &keys.disjoint.0);
 }
-fn bench_morton_filter_mixed_member(c: &mut Criterion) {
-    // setup
-    let mut keys = keygenerator::KeyGenerator::new_empty();
-    keys.read_from_file().expect("");
-    let mixed_keys = keys.mixed;
-    let mut  morton_filter = RefCell::new(mortonfilter::MortonFilter::new((SAMPLE_SIZE as f64 * 1.1)as u64, 0.01));
 
-    for i in &mixed_keys.0 {
-        morton_filter.borrow_mut().insert(*i);
-    }
-    // custom benchmarking function.
-    c.bench_function("bench_morton_filter_mixed_member", |b| {
-        b.iter_custom(|iters| {
-            let mut num_runs = (iters as f64 / SAMPLE_SIZE as f64).ceil() as u64;
-            let remainder = iters % SAMPLE_SIZE;
-            if num_runs <= 1 {
-                //  just run the for loop form 0 - iters in here.
-                let start = Instant::now();
-                for i in 0..(iters as usize) {
-                    black_box(morton_filter.borrow().member(mixed_keys.0[i]));
-                    // xor_filter.borrow().member(random_keys.1[i]);
-                }
-                return start.elapsed();
-            }
-            else {
-                num_runs -= 1;
-                let start = Instant::now();
-                for _ in 0..num_runs {
-                    for i in 0..(SAMPLE_SIZE as usize) {
-                        // check 1st pair, i.e the same that we inserted.
-                        black_box(morton_filter.borrow().member(mixed_keys.0[i]));
-                    }
-                }
-                for i in 0..(remainder as usize) {
-                    black_box(morton_filter.borrow().member(mixed_keys.0[i]));
-                }
-                return start.elapsed();
-            }
-        });
-    });
+fn bench_morton_filter_mixed_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let morton_filter = harness::build(MortonFilter::new(SAMPLE_SIZE), &keys.mixed.0);
+    harness::bench_member(c, "bench_morton_filter_mixed_member", &morton_filter, &keys.mixed.0);
 }
-fn bench_morton_filter_disjoint_member(c: &mut Criterion) {
-    // setup
-    let mut keys = keygenerator::KeyGenerator::new_empty();
-    keys.read_from_file().expect("");
-    let disjoint_keys = keys.disjoint;
-    let mut  morton_filter = RefCell::new(mortonfilter::MortonFilter::new((SAMPLE_SIZE as f64 * 1.1)as u64, 0.01));
 
-    for i in &disjoint_keys.0 {
-        morton_filter.borrow_mut().insert(*i);
-    }
-    // custom benchmarking function.
-    c.bench_function("bench_morton_filter_disjoint_member", |b| {
-        b.iter_custom(|iters| {
-            let mut num_runs = (iters as f64 / SAMPLE_SIZE as f64).ceil() as u64;
-            let remainder = iters % SAMPLE_SIZE;
-            if num_runs <= 1 {
-                //  just run the for loop form 0 - iters in here.
-                let start = Instant::now();
-                for i in 0..(iters as usize) {
-                    black_box(morton_filter.borrow().member(disjoint_keys.1[i]));
-                    // xor_filter.borrow().member(random_keys.1[i]);
-                }
-                return start.elapsed();
-            }
-            else {
-                num_runs -= 1;
-                let start = Instant::now();
-                for _ in 0..num_runs {
-                    for i in 0..(SAMPLE_SIZE as usize) {
-                        // check 1st pair, i.e the same that we inserted.
-                        black_box(morton_filter.borrow().member(disjoint_keys.1[i]));
-                    }
-                }
-                for i in 0..(remainder as usize) {
-                    black_box(morton_filter.borrow().member(disjoint_keys.1[i]));
-                }
-                return start.elapsed();
-            }
-        });
-    });
+fn bench_morton_filter_disjoint_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let morton_filter = harness::build(MortonFilter::new(SAMPLE_SIZE), &keys.disjoint.0);
+    harness::bench_member(c, "bench_morton_filter_disjoint_member", &morton_filter, &keys.disjoint.1);
 }
-fn bench_morton_filter_random_member(c: &mut Criterion) {
-
-    // setup
-    let mut keys = keygenerator::KeyGenerator::new_empty();
-    keys.read_from_file().expect("");
-    let random_keys = keys.random;
-    let mut  morton_filter = RefCell::new(mortonfilter::MortonFilter::new((SAMPLE_SIZE as f64 * 1.1)as u64, 0.01));
 
-    for i in &random_keys.0 {
-        morton_filter.borrow_mut().insert(*i);
-    }
-    println!("insertion complete");
-    // custom benchmarking function.
-    c.bench_function("bench_morton_filter_random_member", |b| {
-        b.iter_custom(|iters| {
-            let mut num_runs = (iters as f64 / SAMPLE_SIZE as f64).ceil() as u64;
-            let remainder = iters % SAMPLE_SIZE;
-            if num_runs <= 1 {
-                //  just run the for loop form 0 - iters in here.
-                let start = Instant::now();
-                for i in 0..(iters as usize) {
-                    black_box(morton_filter.borrow().member(random_keys.0[i]));
-                    // xor_filter.borrow().member(random_keys.1[i]);
-                }
-                return start.elapsed();
-            }
-            else {
-                num_runs -= 1;
-                let start = Instant::now();
-                for _ in 0..num_runs {
-                    for i in 0..(SAMPLE_SIZE as usize) {
-                        // check 1st pair, i.e the same that we inserted.
-                        black_box(morton_filter.borrow().member(random_keys.0[i]));
-                    }
-                }
-                for i in 0..(remainder as usize) {
-                    black_box(morton_filter.borrow().member(random_keys.0[i]));
-                }
-                return start.elapsed();
-            }
-        });
-    });
+fn bench_morton_filter_random_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let morton_filter = harness::build(MortonFilter::new(SAMPLE_SIZE), &keys.random.0);
+    harness::bench_member(c, "bench_morton_filter_random_member", &morton_filter, &keys.random.0);
 }
 
 
 criterion_group!(benches, bench_morton_filter_uniform_member, bench_morton_filter_disjoint_member,
     bench_morton_filter_mixed_member, bench_morton_filter_random_member);
-criterion_main!(benches);
\ No newline at end of file
+criterion_main!(benches);
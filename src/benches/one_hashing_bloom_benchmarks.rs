@@ -0,0 +1,34 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use diss::bloom::OneHashingBloomFilter;
+use diss::keygenerator::SAMPLE_SIZE;
+
+mod harness;
+
+fn bench_one_hashing_bloom_filter_uniform_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let one_hashing_bloom_filter = harness::build(OneHashingBloomFilter::new(SAMPLE_SIZE, 0.01), &keys.disjoint.0);
+    harness::bench_member(c, "bench_one_hashing_bloom_filter_uniform_member", &one_hashing_bloom_filter, &keys.disjoint.0);
+}
+
+fn bench_one_hashing_bloom_filter_mixed_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let one_hashing_bloom_filter = harness::build(OneHashingBloomFilter::new(SAMPLE_SIZE, 0.01), &keys.mixed.0);
+    harness::bench_member(c, "bench_one_hashing_bloom_filter_mixed_member", &one_hashing_bloom_filter, &keys.mixed.0);
+}
+
+fn bench_one_hashing_bloom_filter_disjoint_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let one_hashing_bloom_filter = harness::build(OneHashingBloomFilter::new(SAMPLE_SIZE, 0.01), &keys.disjoint.0);
+    harness::bench_member(c, "bench_one_hashing_bloom_filter_disjoint_member", &one_hashing_bloom_filter, &keys.disjoint.1);
+}
+
+fn bench_one_hashing_bloom_filter_random_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let one_hashing_bloom_filter = harness::build(OneHashingBloomFilter::new(SAMPLE_SIZE, 0.01), &keys.random.0);
+    harness::bench_member(c, "bench_one_hashing_bloom_filter_random_member", &one_hashing_bloom_filter, &keys.random.0);
+}
+
+
+criterion_group!(benches, bench_one_hashing_bloom_filter_uniform_member, bench_one_hashing_bloom_filter_disjoint_member,
+    bench_one_hashing_bloom_filter_mixed_member, bench_one_hashing_bloom_filter_random_member);
+criterion_main!(benches);
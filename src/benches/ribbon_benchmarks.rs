@@ -0,0 +1,57 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use diss::ribbon::{BumpedRibbonFilter, RibbonFilter};
+
+mod harness;
+
+fn bench_ribbon_filter_uniform_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let ribbon_filter = RibbonFilter::new(&keys.disjoint.0, 64, 8);
+    harness::bench_member(c, "bench_ribbon_filter_uniform_member", &ribbon_filter, &keys.disjoint.0);
+}
+
+fn bench_ribbon_filter_mixed_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let ribbon_filter = RibbonFilter::new(&keys.mixed.0, 64, 8);
+    harness::bench_member(c, "bench_ribbon_filter_mixed_member", &ribbon_filter, &keys.mixed.0);
+}
+
+fn bench_ribbon_filter_disjoint_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let ribbon_filter = RibbonFilter::new(&keys.disjoint.0, 64, 8);
+    harness::bench_member(c, "bench_ribbon_filter_disjoint_member", &ribbon_filter, &keys.disjoint.1);
+}
+
+fn bench_ribbon_filter_random_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let ribbon_filter = RibbonFilter::new(&keys.random.0, 64, 8);
+    harness::bench_member(c, "bench_ribbon_filter_random_member", &ribbon_filter, &keys.random.0);
+}
+
+fn bench_bumped_ribbon_filter_uniform_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let ribbon_filter = BumpedRibbonFilter::new(&keys.disjoint.0, 64, 8);
+    harness::bench_member(c, "bench_bumped_ribbon_filter_uniform_member", &ribbon_filter, &keys.disjoint.0);
+}
+
+fn bench_bumped_ribbon_filter_mixed_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let ribbon_filter = BumpedRibbonFilter::new(&keys.mixed.0, 64, 8);
+    harness::bench_member(c, "bench_bumped_ribbon_filter_mixed_member", &ribbon_filter, &keys.mixed.0);
+}
+
+fn bench_bumped_ribbon_filter_disjoint_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let ribbon_filter = BumpedRibbonFilter::new(&keys.disjoint.0, 64, 8);
+    harness::bench_member(c, "bench_bumped_ribbon_filter_disjoint_member", &ribbon_filter, &keys.disjoint.1);
+}
+
+fn bench_bumped_ribbon_filter_random_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let ribbon_filter = BumpedRibbonFilter::new(&keys.random.0, 64, 8);
+    harness::bench_member(c, "bench_bumped_ribbon_filter_random_member", &ribbon_filter, &keys.random.0);
+}
+
+
+criterion_group!(benches, bench_ribbon_filter_uniform_member, bench_ribbon_filter_mixed_member, bench_ribbon_filter_disjoint_member, bench_ribbon_filter_random_member,
+    bench_bumped_ribbon_filter_uniform_member, bench_bumped_ribbon_filter_mixed_member, bench_bumped_ribbon_filter_disjoint_member, bench_bumped_ribbon_filter_random_member);
+criterion_main!(benches);
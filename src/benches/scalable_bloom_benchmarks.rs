@@ -0,0 +1,34 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use diss::bloom::ScalableBloomFilter;
+use diss::keygenerator::SAMPLE_SIZE;
+
+mod harness;
+
+fn bench_scalable_bloom_filter_uniform_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let scalable_bloom_filter = harness::build(ScalableBloomFilter::new(SAMPLE_SIZE / 16, 0.01), &keys.disjoint.0);
+    harness::bench_member(c, "bench_scalable_bloom_filter_uniform_member", &scalable_bloom_filter, &keys.disjoint.0);
+}
+
+fn bench_scalable_bloom_filter_mixed_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let scalable_bloom_filter = harness::build(ScalableBloomFilter::new(SAMPLE_SIZE / 16, 0.01), &keys.mixed.0);
+    harness::bench_member(c, "bench_scalable_bloom_filter_mixed_member", &scalable_bloom_filter, &keys.mixed.0);
+}
+
+fn bench_scalable_bloom_filter_disjoint_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let scalable_bloom_filter = harness::build(ScalableBloomFilter::new(SAMPLE_SIZE / 16, 0.01), &keys.disjoint.0);
+    harness::bench_member(c, "bench_scalable_bloom_filter_disjoint_member", &scalable_bloom_filter, &keys.disjoint.1);
+}
+
+fn bench_scalable_bloom_filter_random_member(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    let scalable_bloom_filter = harness::build(ScalableBloomFilter::new(SAMPLE_SIZE / 16, 0.01), &keys.random.0);
+    harness::bench_member(c, "bench_scalable_bloom_filter_random_member", &scalable_bloom_filter, &keys.random.0);
+}
+
+
+criterion_group!(benches, bench_scalable_bloom_filter_uniform_member, bench_scalable_bloom_filter_disjoint_member,
+    bench_scalable_bloom_filter_mixed_member, bench_scalable_bloom_filter_random_member);
+criterion_main!(benches);
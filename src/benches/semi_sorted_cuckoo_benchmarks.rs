@@ -0,0 +1,60 @@
+use criterion::{black_box, Criterion, criterion_group, criterion_main};
+use diss::cuckoo::CuckooFilter;
+use diss::filter::FilterStats;
+use diss::fpr;
+use diss::keygenerator::SAMPLE_SIZE;
+
+mod harness;
+
+// the plain and semi-sorted layouts with the same fingerprints, so the same false positive rate.
+const FINGERPRINT_BITS: u32 = 12;
+// a fixed seed, so that runs can be compared.
+const SEED: u64 = 0;
+
+fn buckets() -> usize {
+    return (SAMPLE_SIZE as f64 / (4.0 * 0.95)) as usize;
+}
+
+fn plain() -> CuckooFilter {
+    return CuckooFilter::with_fingerprint_bits(buckets(), 500, 4, FINGERPRINT_BITS, SEED);
+}
+
+fn semi_sorted() -> CuckooFilter {
+    return CuckooFilter::with_semi_sorted_buckets(buckets(), 500, FINGERPRINT_BITS, SEED);
+}
+
+// the space side of the trade off, printed once next to the timings.
+fn report_space() {
+    let keys = harness::load_keys();
+    for (name, filter) in [("plain", harness::build(plain(), &keys.disjoint.0)),
+                           ("semi-sorted", harness::build(semi_sorted(), &keys.disjoint.0))] {
+        println!("{} cuckoo filter: {:.2} bits per key, fpr {}", name, filter.bits_per_key(),
+                 fpr::false_positive_rate(&filter, &keys.disjoint.1));
+    }
+}
+
+fn bench_cuckoo_filter_layout_member(c: &mut Criterion) {
+    report_space();
+    let keys = harness::load_keys();
+    let plain_filter = harness::build(plain(), &keys.disjoint.0);
+    let semi_sorted_filter = harness::build(semi_sorted(), &keys.disjoint.0);
+    harness::bench_member(c, "bench_plain_cuckoo_filter_uniform_member", &plain_filter, &keys.disjoint.0);
+    harness::bench_member(c, "bench_semi_sorted_cuckoo_filter_uniform_member", &semi_sorted_filter, &keys.disjoint.0);
+    harness::bench_member(c, "bench_plain_cuckoo_filter_disjoint_member", &plain_filter, &keys.disjoint.1);
+    harness::bench_member(c, "bench_semi_sorted_cuckoo_filter_disjoint_member", &semi_sorted_filter, &keys.disjoint.1);
+}
+
+// filling the filter, one iteration per full build.
+fn bench_cuckoo_filter_layout_insert(c: &mut Criterion) {
+    let keys = harness::load_keys();
+    c.bench_function("bench_plain_cuckoo_filter_insert", |b| {
+        b.iter(|| black_box(harness::build(plain(), &keys.disjoint.0)));
+    });
+    c.bench_function("bench_semi_sorted_cuckoo_filter_insert", |b| {
+        b.iter(|| black_box(harness::build(semi_sorted(), &keys.disjoint.0)));
+    });
+}
+
+
+criterion_group!(benches, bench_cuckoo_filter_layout_member, bench_cuckoo_filter_layout_insert);
+criterion_main!(benches);
@@ -1,58 +1,70 @@
-#[path = "bloomfilter.rs"]
-mod bloomfilter;
 use std::f64;
 
 
-use rand::Rng;
 use slab::Slab;
 
-#[path = "utils.rs"]
-mod utils;
+use crate::utils;
 
-pub struct BlockedBloomFilter {
-    size: u64,
+use rand_chacha::ChaCha8Rng;
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::filter::{self, DynamicFilter, Filter, FilterStats, MergeError, MergeableFilter};
+use crate::serialize::{ensure, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
+
+#[derive(Clone)]
+pub struct BlockedBloomFilter<H = MultiplyShift> {
     blocks: Slab<Vec<u8>>,
     block_size: usize,
     num_blocks: u64,
     num_hashes: usize,
-    hash_functions: Vec<(u64,u64,u64)>,
+    hash_functions: Vec<H>,
+    seed: u64,
     binary_info: (u32,u32)
 }
 
 impl BlockedBloomFilter {
     // block_size = size of cache line in bytes.
-    pub fn new(expected_inserts : u64, block_size: usize, false_positive_rate: f64) -> Self {
+    pub fn new(expected_inserts : u64, block_size: usize, false_positive_rate: f64) -> BlockedBloomFilter {
+        return Self::with_hasher(expected_inserts, block_size, false_positive_rate);
+    }
+}
+
+impl<H: KeyHasher> BlockedBloomFilter<H> {
+    // same as new, but draws the hash functions from H instead of multiply-shift.
+    pub fn with_hasher(expected_inserts : u64, block_size: usize, false_positive_rate: f64) -> Self {
+        return Self::with_seed(expected_inserts, block_size, false_positive_rate, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    pub fn with_seed(expected_inserts : u64, block_size: usize, false_positive_rate: f64, seed: u64) -> Self {
+        let mut rng = utils::seeded_rng(seed);
         let size: u64 = ((-1.44 * (expected_inserts as f64)).ceil()
             * (false_positive_rate/5.0).log2() + 0.5) as u64 ;
         let num_hashes = (-false_positive_rate.log2() + 1.5) as usize;
-        let num_blocks = (size + ((block_size*8) - 1) as u64) / (block_size*8) as u64;
-        let mut rng = rand::thread_rng();
-        let _a1 = rng.gen_range(1..=u64::MAX);
-        let _a2 = rng.gen_range(1..=u64::MAX);
-        let _b = rng.gen_range(1..=u64::MAX);
-        let pair = (utils::log_base(num_blocks as f64, 2f64) as u32, utils::log_base(block_size as f64, 2f64) as u32);
+        // at least one block, or no inserts expected would leave nothing to hash into.
+        let num_blocks = ((size + ((block_size*8) - 1) as u64) / (block_size*8) as u64).max(1);
+        let pair = (utils::log_base(num_blocks as f64, 2f64) as u32, utils::log_base((block_size * 8) as f64, 2f64) as u32);
         BlockedBloomFilter {
-            size,
             blocks: Self::generate_blocks(num_blocks, block_size),
             block_size,
             num_blocks,
             num_hashes,
             // first hash function is always to find the block.
-            hash_functions: Self::generate_hash_functions(num_hashes),
+            hash_functions: Self::generate_hash_functions(num_hashes, &mut rng),
+            seed,
             binary_info: pair
         }
     }
 
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
 
-    fn generate_hash_functions(num_hashes: usize) -> Vec<(u64, u64,u64)> {
-        let mut rng = rand::thread_rng();
+    fn generate_hash_functions(num_hashes: usize, rng: &mut ChaCha8Rng) -> Vec<H> {
         let mut hash_functions = Vec::new();
 
         for _ in 0..num_hashes {
-            let a1: u64 = rng.gen_range(1..=u64::MAX );
-            let a2: u64 = rng.gen_range(1..=u64::MAX);
-            let b: u64 = rng.gen_range(1..=u64::MAX);
-            hash_functions.push((a1,a2,b));
+            hash_functions.push(H::from_rng(rng));
         }
         return hash_functions;
     }
@@ -66,43 +78,196 @@ impl BlockedBloomFilter {
         return slab;
     }
 
+    // the 32 bit hash is scaled to the blocks, rather than taken modulo them, so every block is used even when their
+    // number is not a power of two.
     fn get_block_id(&self, element: u64) -> usize {
-        // need binary log of the number of blocks here.
-        if(self.num_blocks <= 1) {
-            return 0;
+        return ((self.hash_functions[0].hash_bits(element, 32) as u64 * self.num_blocks) >> 32) as usize;
+    }
+}
+
+impl<H: KeyHasher> Filter for BlockedBloomFilter<H> {
+    // Check if an element is present in the correct block.
+    fn member(&self, element: u64) -> bool {
+        let block_id = self.get_block_id(element);
+        let block = self.blocks.get(block_id).unwrap();
+
+        for i in 1..self.num_hashes {
+            let hash_function = &self.hash_functions[i];
+            let index : u64 = (hash_function.hash_bits(element, self.binary_info.1) % (self.block_size * 8) as u32) as u64;
+            let mask = 1 << (index % 8);
+            if (block[(index / 8) as usize] & mask) == 0  {
+                return false;
+            }
         }
-        return (utils::hash(element, self.binary_info.0, self.hash_functions[0].0, self.hash_functions[0].1,
-                           self.hash_functions[0].2) as usize ) % self.num_blocks as usize;
+        return true;
     }
 
+    // find the block and bits of a batch of keys and prefetch them, then check them.
+    fn member_batch(&self, keys: &[u64], out: &mut [bool]) {
+        assert_eq!(keys.len(), out.len(), "member_batch needs one output per key");
+        let k = self.num_hashes.max(1) - 1;
+        let mut block_ids = [0usize; utils::BATCH];
+        let mut indexes = vec![0usize; utils::BATCH * k];
+        for (keys, out) in keys.chunks(utils::BATCH).zip(out.chunks_mut(utils::BATCH)) {
+            for i in 0..keys.len() {
+                block_ids[i] = self.get_block_id(keys[i]);
+                let block = self.blocks.get(block_ids[i]).unwrap();
+                for j in 0..k {
+                    let hash_function = &self.hash_functions[j + 1];
+                    let index = (hash_function.hash_bits(keys[i], self.binary_info.1) % (self.block_size * 8) as u32) as usize;
+                    utils::prefetch(&block[index / 8]);
+                    indexes[i * k + j] = index;
+                }
+            }
+            for i in 0..keys.len() {
+                let block = self.blocks.get(block_ids[i]).unwrap();
+                out[i] = indexes[i * k..(i + 1) * k].iter().all(|index| block[index / 8] & (1 << (index % 8)) != 0);
+            }
+        }
+    }
+}
+
+impl<H: KeyHasher> DynamicFilter for BlockedBloomFilter<H> {
     // Add an element to the correct block.
-    pub fn insert(&mut self, element: u64) {
+    fn insert(&mut self, element: u64) -> bool {
         let block_id = self.get_block_id(element);
         let block = self.blocks.get_mut(block_id).unwrap();
 
         for i in 1..self.num_hashes {
-            let hash_function = self.hash_functions[i];
-            let index : u64 = (utils::hash(element, self.binary_info.1, hash_function.0, hash_function.1,
-                                           hash_function.2) % self.block_size as u32) as u64;
+            let hash_function = &self.hash_functions[i];
+            let index : u64 = (hash_function.hash_bits(element, self.binary_info.1) % (self.block_size * 8) as u32) as u64;
             block[(index / 8) as usize] |= 1 << (index % 8);
         }
+        return true;
     }
+}
 
-    // Check if an element is present in the correct block.
-    pub fn member(&self, element: u64) -> bool {
-        let block_id = self.get_block_id(element);
-        let block = self.blocks.get(block_id).unwrap();
+impl<H: SerializableHasher> SerializableFilter for BlockedBloomFilter<H> {
+    const KIND: FilterKind = FilterKind::BlockedBloom;
+    const HASHER: HasherKind = H::KIND;
 
-        for i in 1..self.num_hashes {
-            let hash_function = self.hash_functions[i];
-            let index : u64 = (utils::hash(element, self.binary_info.1, hash_function.0, hash_function.1,
-                                           hash_function.2) % self.block_size as u32) as u64;
-            let mask = 1 << (index % 8);
-            if !((block[(index / 8) as usize] & mask)!= 0) {
-                return false;
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_u64(self.block_size as u64);
+        enc.put_u64(self.num_blocks);
+        enc.put_u64(self.num_hashes as u64);
+        enc.put_u64(self.binary_info.0 as u64);
+        enc.put_u64(self.binary_info.1 as u64);
+        enc.put_hashers(&self.hash_functions);
+        // slab keys are 0..=num_blocks, and iteration is in key order.
+        let mut bytes = Vec::with_capacity(self.blocks.len() * self.block_size);
+        for (_, block) in &self.blocks {
+            bytes.extend_from_slice(block);
+        }
+        enc.put_u8s(&bytes);
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let block_size = dec.get_usize()?;
+        let num_blocks = dec.get_u64()?;
+        let num_hashes = dec.get_usize()?;
+        let binary_info = (dec.get_u32()?, dec.get_u32()?);
+        let hash_functions = dec.get_hashers(Some(num_hashes))?;
+        let bytes = dec.get_u8s()?;
+        ensure(block_size as u32 != 0, "block size is zero")?;
+        ensure(num_hashes > 0, "no hash functions")?;
+        ensure(binary_info.0 <= 64 && binary_info.1 <= 64, "l out of range")?;
+        let expected = num_blocks.checked_add(1).and_then(|n| n.checked_mul(block_size as u64));
+        ensure(expected == Some(bytes.len() as u64), "blocks do not match the block count")?;
+        let mut blocks = Slab::with_capacity(num_blocks as usize + 1);
+        for block in bytes.chunks_exact(block_size) {
+            blocks.insert(block.to_vec());
+        }
+        return Ok(BlockedBloomFilter {
+            blocks,
+            block_size,
+            num_blocks,
+            num_hashes,
+            hash_functions,
+            seed,
+            binary_info,
+        });
+    }
+}
+
+impl<H: KeyHasher> BlockedBloomFilter<H> {
+    fn check_mergeable(&self, other: &Self) -> Result<(), MergeError> {
+        return MergeError::check((self.seed, other.seed), &[
+            ("block size", self.block_size as u64, other.block_size as u64),
+            ("number of blocks", self.num_blocks, other.num_blocks),
+            ("number of hashes", self.num_hashes as u64, other.num_hashes as u64),
+        ]);
+    }
+}
+
+impl<H: KeyHasher> MergeableFilter for BlockedBloomFilter<H> {
+    fn union_with(&mut self, other: &Self) -> Result<(), MergeError> {
+        self.check_mergeable(other)?;
+        for ((_, block), (_, other)) in self.blocks.iter_mut().zip(other.blocks.iter()) {
+            for (byte, other) in block.iter_mut().zip(other) {
+                *byte |= *other;
             }
         }
-        return true;
+        return Ok(());
+    }
+
+    fn intersect_with(&mut self, other: &Self) -> Result<(), MergeError> {
+        self.check_mergeable(other)?;
+        for ((_, block), (_, other)) in self.blocks.iter_mut().zip(other.blocks.iter()) {
+            for (byte, other) in block.iter_mut().zip(other) {
+                *byte &= *other;
+            }
+        }
+        return Ok(());
+    }
+
+    // estimated block by block, as each key sets all of its bits in one block.
+    fn estimate_cardinality(&self) -> f64 {
+        let k = self.num_hashes.max(1) as u64 - 1;
+        let mut n = 0.0;
+        for (_, block) in self.blocks.iter() {
+            let set: u64 = block.iter().map(|byte| byte.count_ones() as u64).sum();
+            n += filter::swamidass_baldi(set, (self.block_size * 8) as u64, k);
+        }
+        return n;
     }
 }
 
+// the slab has one block more than get_block_id can reach, which is left out.
+impl<H: KeyHasher> FilterStats for BlockedBloomFilter<H> {
+    fn len(&self) -> u64 {
+        return self.estimate_cardinality().round() as u64;
+    }
+
+    fn capacity(&self) -> u64 {
+        return filter::half_full(self.slots(), self.num_hashes.max(1) as u64 - 1);
+    }
+
+    fn slots(&self) -> u64 {
+        return self.num_blocks * (self.block_size * 8) as u64;
+    }
+
+    fn occupied(&self) -> u64 {
+        let mut set = 0;
+        for (_, block) in self.blocks.iter() {
+            set += block.iter().map(|byte| byte.count_ones() as u64).sum::<u64>();
+        }
+        return set;
+    }
+
+    fn size_in_bits(&self) -> usize {
+        return self.blocks.len() * self.block_size * 8;
+    }
+
+    // a key picks a block at random, then needs all of its bits to be set in it.
+    fn false_positive_rate(&self) -> f64 {
+        let k = self.num_hashes.max(1) as i32 - 1;
+        let mut rate = 0.0;
+        for (_, block) in self.blocks.iter().take(self.num_blocks as usize) {
+            let set: u32 = block.iter().map(|byte| byte.count_ones()).sum();
+            rate += (set as f64 / (self.block_size * 8) as f64).powi(k);
+        }
+        return rate / self.num_blocks as f64;
+    }
+}
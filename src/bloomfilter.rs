@@ -1,80 +1,221 @@
-
-use rand::Rng;
 use std::f64;
 
-#[path = "bitvector.rs"]
-mod bitvector;
+use crate::bitvector;
+
+use crate::utils;
 
-#[path = "utils.rs"]
-mod utils;
+use rand_chacha::ChaCha8Rng;
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::filter::{self, DynamicFilter, Filter, FilterStats, MergeError, MergeableFilter};
+use crate::serialize::{ensure, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
 
-pub struct BloomFilter {
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BloomFilter<H = MultiplyShift> {
     pub(crate) bit_array: bitvector::BitVector,
-    pub(crate) hash_functions: Vec<(u64,u64,u64)>,
+    pub(crate) hash_functions: Vec<H>,
+    seed: u64,
     size: u64,
     l: u32,
 }
 impl BloomFilter {
     pub fn new(expected_inserts: u64, false_positive_rate: f64) -> BloomFilter {
-        let size: u64 = utils::closest_power_of_two(((-1.44 * (expected_inserts as f64)).ceil()
-            * false_positive_rate.log2() + 0.5) as u64);
-        let num_hashes = (-false_positive_rate.log2() + 0.5) as usize;
+        return Self::with_hasher(expected_inserts, false_positive_rate);
+    }
+}
+
+impl<H: KeyHasher> BloomFilter<H> {
+    // same as new, but draws the hash functions from H instead of multiply-shift.
+    pub fn with_hasher(expected_inserts: u64, false_positive_rate: f64) -> Self {
+        return Self::with_seed(expected_inserts, false_positive_rate, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    pub fn with_seed(expected_inserts: u64, false_positive_rate: f64, seed: u64) -> Self {
+        let size = utils::closest_power_of_two(Self::bits_for(expected_inserts, false_positive_rate));
+        return Self::with_size(size, false_positive_rate, seed);
+    }
+
+    // as with_seed, but the size is rounded up to a power of two rather than to the closest one, and doubled again if
+    // the whole number of hash functions still leaves the expected rate (1 - e^(-kn/m))^k above the target. so once
+    // full the filter meets its rate.
+    pub(crate) fn with_size_rounded_up(expected_inserts: u64, false_positive_rate: f64, seed: u64) -> Self {
+        let mut size = Self::bits_for(expected_inserts, false_positive_rate).next_power_of_two();
+        let k = Self::hashes_for(false_positive_rate) as f64;
+        while (1.0 - (-k * expected_inserts as f64 / size as f64).exp()).powf(k) > false_positive_rate {
+            size *= 2;
+        }
+        return Self::with_size(size, false_positive_rate, seed);
+    }
 
+    // at least one, or every key would be a member.
+    fn hashes_for(false_positive_rate: f64) -> usize {
+        return ((-false_positive_rate.log2() + 0.5) as usize).max(1);
+    }
+
+    // at least one bit, or no inserts expected would leave nothing to hash into.
+    fn bits_for(expected_inserts: u64, false_positive_rate: f64) -> u64 {
+        return (((-1.44 * (expected_inserts as f64)).ceil() * false_positive_rate.log2() + 0.5) as u64).max(1);
+    }
 
+    // size is a power of two.
+    fn with_size(size: u64, false_positive_rate: f64, seed: u64) -> Self {
+        let mut rng = utils::seeded_rng(seed);
+        let num_hashes = Self::hashes_for(false_positive_rate);
         BloomFilter {
             bit_array: bitvector::BitVector::new(size),
-            hash_functions: Self::generate_hash_functions(num_hashes, size),
+            hash_functions: Self::generate_hash_functions(num_hashes, size, &mut rng),
+            seed,
             size,
             l: utils::log_base(size as f64, 2f64) as u32,
         }
     }
 
-    fn generate_hash_functions(n: usize, _m: u64) -> Vec<(u64, u64,u64)> {
-        let mut rng = rand::thread_rng();
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    fn generate_hash_functions(n: usize, _m: u64, rng: &mut ChaCha8Rng) -> Vec<H> {
         let mut hash_functions = Vec::new();
 
         for _ in 0..n {
-            let a1: u64 = rng.gen_range(1..=u64::MAX );
-            let a2: u64 = rng.gen_range(1..=u64::MAX);
-            let b: u64 = rng.gen_range(1..=u64::MAX);
-            hash_functions.push((a1,a2,b));
+            hash_functions.push(H::from_rng(rng));
         }
         return hash_functions;
     }
+}
 
-
-
+impl<H: KeyHasher> DynamicFilter for BloomFilter<H> {
     // insert hashes the key for all hash functions and sets them to be true.
     // requires a mutable reference to itself. and a reference to the key.
-    pub fn insert(&mut self, key: u64) {
+    fn insert(&mut self, key: u64) -> bool {
         for hash_function in &self.hash_functions {
-            let index : u64 = (utils::hash(key, self.l, hash_function.0, hash_function.1, hash_function.2) % self.size as u32) as u64;
+            let index : u64 = (hash_function.hash_bits(key, self.l) % self.size as u32) as u64;
             // println!("{}", index);
-            // println!("{}", Self::hash( key, self.l, hash_function.0, hash_function.1, hash_function.2) );
+            // println!("{}", Self::hash_function.hash_bits(key, self.l) );
             self.bit_array.insert(index);
         }
+        return true;
     }
+}
 
-    pub fn member(&self, key: u64) -> bool {
+impl<H: KeyHasher> Filter for BloomFilter<H> {
+    fn member(&self, key: u64) -> bool {
         for hash_function in &self.hash_functions {
-            let index: u64 = (utils::hash(key, self.l, hash_function.0, hash_function.1, hash_function.2) % self.size as u32) as u64;
+            let index: u64 = (hash_function.hash_bits(key, self.l) % self.size as u32) as u64;
             if !self.bit_array.member(index) {
                 return false;
             }
         }
         return true;
     }
+
+    // hash a batch of keys and prefetch all of their bits, then check them.
+    fn member_batch(&self, keys: &[u64], out: &mut [bool]) {
+        assert_eq!(keys.len(), out.len(), "member_batch needs one output per key");
+        let k = self.hash_functions.len();
+        let mut indexes = vec![0u64; utils::BATCH * k];
+        for (keys, out) in keys.chunks(utils::BATCH).zip(out.chunks_mut(utils::BATCH)) {
+            for i in 0..keys.len() {
+                for j in 0..k {
+                    let index = (self.hash_functions[j].hash_bits(keys[i], self.l) % self.size as u32) as u64;
+                    utils::prefetch(&self.bit_array.array[(index >> 3) as usize]);
+                    indexes[i * k + j] = index;
+                }
+            }
+            for i in 0..keys.len() {
+                out[i] = indexes[i * k..(i + 1) * k].iter().all(|index| self.bit_array.member(*index));
+            }
+        }
+    }
+}
+
+impl<H: SerializableHasher> SerializableFilter for BloomFilter<H> {
+    const KIND: FilterKind = FilterKind::Bloom;
+    const HASHER: HasherKind = H::KIND;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_u64(self.size);
+        enc.put_u64(self.l as u64);
+        enc.put_hashers(&self.hash_functions);
+        enc.put_u8s(&self.bit_array.array);
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let size = dec.get_u64()?;
+        let l = dec.get_u32()?;
+        let hash_functions = dec.get_hashers(None)?;
+        let array = dec.get_u8s()?;
+        ensure(size as u32 != 0, "size is zero")?;
+        ensure(l <= 64, "l out of range")?;
+        ensure(array.len() as u64 * 8 >= size, "bit array is shorter than the filter")?;
+        return Ok(BloomFilter {
+            bit_array: bitvector::BitVector { array },
+            hash_functions,
+            seed,
+            size,
+            l,
+        });
+    }
+}
+
+impl<H: KeyHasher> BloomFilter<H> {
+    fn check_mergeable(&self, other: &Self) -> Result<(), MergeError> {
+        return MergeError::check((self.seed, other.seed), &[
+            ("size", self.size, other.size),
+            ("l", self.l as u64, other.l as u64),
+            ("number of hashes", self.hash_functions.len() as u64, other.hash_functions.len() as u64),
+        ]);
+    }
 }
 
-fn main() {
-    let num_inserts = 100;
-    let fpr = 0.01;
-    let mut bloom_filter = BloomFilter::new(num_inserts, fpr);
+impl<H: KeyHasher> MergeableFilter for BloomFilter<H> {
+    fn union_with(&mut self, other: &Self) -> Result<(), MergeError> {
+        self.check_mergeable(other)?;
+        for (byte, other) in self.bit_array.array.iter_mut().zip(&other.bit_array.array) {
+            *byte |= *other;
+        }
+        return Ok(());
+    }
 
-    bloom_filter.insert(23);
-    bloom_filter.insert(2);
-    bloom_filter.insert(11);
+    fn intersect_with(&mut self, other: &Self) -> Result<(), MergeError> {
+        self.check_mergeable(other)?;
+        for (byte, other) in self.bit_array.array.iter_mut().zip(&other.bit_array.array) {
+            *byte &= *other;
+        }
+        return Ok(());
+    }
 
-    println!("Contains '23': {}", bloom_filter.member(23));
-    println!("Contains '12': {}", bloom_filter.member(12));
+    fn estimate_cardinality(&self) -> f64 {
+        return filter::swamidass_baldi(self.bit_array.count_ones(), self.size, self.hash_functions.len() as u64);
+    }
+}
+
+impl<H: KeyHasher> FilterStats for BloomFilter<H> {
+    fn len(&self) -> u64 {
+        return self.estimate_cardinality().round() as u64;
+    }
+
+    fn capacity(&self) -> u64 {
+        return filter::half_full(self.size, self.hash_functions.len() as u64);
+    }
+
+    fn slots(&self) -> u64 {
+        return self.size;
+    }
+
+    fn occupied(&self) -> u64 {
+        return self.bit_array.count_ones();
+    }
+
+    fn size_in_bits(&self) -> usize {
+        return self.bit_array.array.len() * 8;
+    }
+
+    // a key not in the filter is a false positive if all k of its bits happen to be set.
+    fn false_positive_rate(&self) -> f64 {
+        return self.fill_ratio().powi(self.hash_functions.len() as i32);
+    }
 }
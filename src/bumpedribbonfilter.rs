@@ -0,0 +1,271 @@
+use rand_chacha::ChaCha8Rng;
+
+use crate::utils;
+
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::filter::{Filter, FilterStats, StaticFilter};
+use crate::ribbonfilter::{self, Added, Banding, Ribbon};
+use crate::serialize::{ensure, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
+
+/*
+Bumped Ribbon Retrieval, BuRR (Dillinger, Hübschle-Schneider, Sanders and Walzer, 2022).
+A standard ribbon needs a few percent of spare slots for the elimination to succeed. BuRR instead makes the table
+slightly smaller than the number of keys, and splits it into buckets of `ribbon_width` slots. Keys are added bucket
+by bucket in order of their start slot. When a bucket cannot be added in full, the keys that start lowest in it are
+"bumped": left out of this layer and passed on to the next one, which is a smaller BuRR over just those keys.
+A small threshold per bucket records which keys were bumped, so a query knows which layer to ask.
+The last layer is a standard ribbon.
+ */
+
+// the share of the keys each layer has slots for. the rest are bumped to the next layer.
+const LOAD: f64 = 0.98;
+
+// once this few keys are left, they go into a standard ribbon.
+const LAST_LAYER_KEYS: usize = 2048;
+
+// a bucket bumps keys that start below threshold * ribbon_width / 4 in it. 4 bumps the whole bucket.
+const THRESHOLDS: [usize; 4] = [0, 1, 2, 4];
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Layer<H> {
+    ribbon: Ribbon<H>,
+    // 2 bits per bucket, an index into THRESHOLDS.
+    thresholds: Vec<u8>,
+}
+
+impl<H: KeyHasher> Layer<H> {
+    fn buckets(slots: usize, ribbon_width: u32) -> usize {
+        return slots.div_ceil(ribbon_width as usize);
+    }
+
+    fn threshold(&self, bucket: usize) -> usize {
+        let code = (self.thresholds[bucket / 4] >> ((bucket % 4) * 2)) & 3;
+        return THRESHOLDS[code as usize] * self.ribbon.ribbon_width as usize / 4;
+    }
+
+    fn set_threshold(&mut self, bucket: usize, code: u8) {
+        self.thresholds[bucket / 4] |= code << ((bucket % 4) * 2);
+    }
+
+    // whether this layer holds the key, rather than passing it on.
+    fn holds(&self, start: usize) -> bool {
+        let w = self.ribbon.ribbon_width as usize;
+        return start % w >= self.threshold(start / w);
+    }
+
+    // add as many of the keys as possible, bucket by bucket, and return the keys that were bumped. the layer has slots
+    // for load of the keys.
+    fn build(keys: &[u64], ribbon_width: u32, fingerprint_bits: u32, load: f64, rng: &mut ChaCha8Rng) -> (Layer<H>, Vec<u64>) {
+        let slots = ((keys.len() as f64 * load).ceil() as usize).max(ribbon_width as usize);
+        let mut layer = Layer {
+            ribbon: Ribbon::<H>::new(slots, ribbon_width, fingerprint_bits, rng),
+            thresholds: vec![0; Self::buckets(slots, ribbon_width).div_ceil(4)],
+        };
+        let w = ribbon_width as usize;
+        let mut rows: Vec<(u64, ribbonfilter::Row)> = keys.iter().map(|key| (*key, layer.ribbon.row(*key))).collect();
+        rows.sort_by_key(|(_, row)| row.start);
+
+        let mut banding = Banding::new(slots);
+        let mut bumped = Vec::new();
+        let mut i = 0;
+        while i < rows.len() {
+            let bucket = rows[i].1.start / w;
+            let mut end = i;
+            while end < rows.len() && rows[end].1.start / w == bucket {
+                end += 1;
+            }
+            for code in 0..THRESHOLDS.len() {
+                let threshold = THRESHOLDS[code] * w / 4;
+                let mut added = Vec::new();
+                let mut failed = false;
+                for (_, row) in &rows[i..end] {
+                    if row.start % w < threshold {
+                        continue;
+                    }
+                    match banding.add(row) {
+                        Added::Slot(slot) => added.push(slot),
+                        Added::Redundant => {}
+                        Added::Failed => {
+                            failed = true;
+                            break;
+                        }
+                    }
+                }
+                if !failed {
+                    layer.set_threshold(bucket, code as u8);
+                    for (key, row) in &rows[i..end] {
+                        if row.start % w < threshold {
+                            bumped.push(*key);
+                        }
+                    }
+                    break;
+                }
+                // undo this bucket and try again with more of it bumped.
+                for slot in added {
+                    banding.clear(slot);
+                }
+            }
+            i = end;
+        }
+        layer.ribbon.solve(&banding, rng);
+        return (layer, bumped);
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BumpedRibbonFilter<H = MultiplyShift> {
+    layers: Vec<Layer<H>>,
+    last: Ribbon<H>,
+    ribbon_width: u32,
+    fingerprint_bits: u32,
+    seed: u64,
+}
+
+impl BumpedRibbonFilter {
+    // ribbon_width is 32, 64 or 128. the false positive rate is 2^-fingerprint_bits.
+    pub fn new(keys: &[u64], ribbon_width: u32, fingerprint_bits: u32) -> BumpedRibbonFilter {
+        return Self::with_hasher(keys, ribbon_width, fingerprint_bits);
+    }
+}
+
+impl<H: KeyHasher> BumpedRibbonFilter<H> {
+    // same as new, but draws the hash functions from H instead of multiply-shift.
+    pub fn with_hasher(keys: &[u64], ribbon_width: u32, fingerprint_bits: u32) -> Self {
+        return Self::with_seed(keys, ribbon_width, fingerprint_bits, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    pub fn with_seed(keys: &[u64], ribbon_width: u32, fingerprint_bits: u32, seed: u64) -> Self {
+        assert!((1..=32).contains(&fingerprint_bits), "fingerprint bits must be between 1 and 32");
+        ribbonfilter::overhead(ribbon_width);
+        let mut rng = utils::seeded_rng(seed);
+        let mut layers = Vec::new();
+        let mut remaining = keys.to_vec();
+        let mut load = LOAD;
+        let mut attempts = 0;
+        while remaining.len() > LAST_LAYER_KEYS {
+            let (layer, bumped) = Layer::build(&remaining, ribbon_width, fingerprint_bits, load, &mut rng);
+            // a layer that bumps every key would hand the next one the same keys forever. as in the standard ribbon,
+            // retry it with new hash functions, and grow it every few attempts.
+            if bumped.len() == remaining.len() {
+                attempts += 1;
+                if attempts % ribbonfilter::RETRIES_BEFORE_GROWING == 0 {
+                    load += 0.02;
+                }
+                continue;
+            }
+            layers.push(layer);
+            remaining = bumped;
+            load = LOAD;
+            attempts = 0;
+        }
+        return BumpedRibbonFilter {
+            layers,
+            last: ribbonfilter::build(&remaining, ribbon_width, fingerprint_bits, &mut rng),
+            ribbon_width,
+            fingerprint_bits,
+            seed,
+        };
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+}
+
+impl<H: KeyHasher> Filter for BumpedRibbonFilter<H> {
+    fn member(&self, key: u64) -> bool {
+        for layer in &self.layers {
+            let row = layer.ribbon.row(key);
+            if layer.holds(row.start) {
+                return layer.ribbon.check(&row);
+            }
+        }
+        return self.last.check(&self.last.row(key));
+    }
+}
+
+impl<H: KeyHasher> StaticFilter for BumpedRibbonFilter<H> {
+    fn from_keys(keys: &[u64]) -> Self {
+        return Self::with_hasher(keys, 64, 8);
+    }
+}
+
+impl<H: SerializableHasher> SerializableFilter for BumpedRibbonFilter<H> {
+    const KIND: FilterKind = FilterKind::BumpedRibbon;
+    const HASHER: HasherKind = H::KIND;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_u64(self.ribbon_width as u64);
+        enc.put_u64(self.fingerprint_bits as u64);
+        enc.put_u64(self.layers.len() as u64);
+        for layer in &self.layers {
+            layer.ribbon.encode(enc);
+            enc.put_u8s(&layer.thresholds);
+        }
+        self.last.encode(enc);
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let ribbon_width = dec.get_u32()?;
+        let fingerprint_bits = dec.get_u32()?;
+        ribbonfilter::check_params(ribbon_width, fingerprint_bits)?;
+        let num_layers = dec.get_usize()?;
+        let mut layers = Vec::new();
+        for _ in 0..num_layers {
+            let ribbon = Ribbon::decode(dec, ribbon_width, fingerprint_bits)?;
+            let thresholds = dec.get_u8s()?;
+            let buckets = Layer::<H>::buckets(ribbon.slots, ribbon_width);
+            ensure(thresholds.len() == buckets.div_ceil(4), "thresholds do not match the buckets")?;
+            layers.push(Layer {
+                ribbon,
+                thresholds,
+            });
+        }
+        return Ok(BumpedRibbonFilter {
+            layers,
+            last: Ribbon::decode(dec, ribbon_width, fingerprint_bits)?,
+            ribbon_width,
+            fingerprint_bits,
+            seed,
+        });
+    }
+}
+
+impl<H: KeyHasher> FilterStats for BumpedRibbonFilter<H> {
+    // the first layer has slots for LOAD of all the keys.
+    fn len(&self) -> u64 {
+        match self.layers.first() {
+            Some(layer) => return (layer.ribbon.slots as f64 / LOAD).round() as u64,
+            None => return ribbonfilter::keys_for_slots(self.last.slots, self.ribbon_width),
+        }
+    }
+
+    fn capacity(&self) -> u64 {
+        return self.len();
+    }
+
+    fn slots(&self) -> u64 {
+        return (self.last.slots + self.layers.iter().map(|layer| layer.ribbon.slots).sum::<usize>()) as u64;
+    }
+
+    // every key is solved for in a slot of its own, in the layer that holds it.
+    fn occupied(&self) -> u64 {
+        return self.len();
+    }
+
+    // the size of the solutions and thresholds of every layer, in bits.
+    fn size_in_bits(&self) -> usize {
+        let mut bits = self.last.slots * self.fingerprint_bits as usize;
+        for layer in &self.layers {
+            bits += layer.ribbon.slots * self.fingerprint_bits as usize + layer.thresholds.len() * 8;
+        }
+        return bits;
+    }
+
+    fn false_positive_rate(&self) -> f64 {
+        return 0.5f64.powi(self.fingerprint_bits as i32);
+    }
+}
@@ -1,77 +1,243 @@
-
-use rand::Rng;
-
-
-#[path="utils.rs"]
-mod utils;
-
-pub struct CountingBloomFilter {
-    pub(crate) count_array: Vec<u8>,
-    hash_functions: Vec<(u64,u64,u64)>,
+use rand_chacha::ChaCha8Rng;
+use crate::utils;
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::filter::{self, CounterError, DeletableFilter, DynamicFilter, Filter, FilterStats};
+use crate::serialize::{ensure, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
+
+/*
+Counting Bloom filter (Fan, Cao, Almeida and Broder, 2000).
+A Bloom filter with a small counter in place of each bit, so that keys can be deleted as well as inserted.
+Counters are packed into u64s at 4, 8 or 16 bits each. Four bits are enough for almost any filter: a counter only
+reaches 16 with a probability of about 1.37e-15 * m.
+A counter that is full is never added to, since it would no longer know how many keys it stands for, so an insert
+that would overflow one is refused instead. Likewise a delete of a key that is not a member is refused, as it could only
+take away from other keys. The smallest of a key's counters is an upper bound on how many times it was inserted, which
+makes the filter a spectral Bloom filter (Cohen and Matias, 2003) as well.
+ */
+
+// the counter width used by new.
+const COUNTER_BITS: u32 = 4;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CountingBloomFilter<H = MultiplyShift> {
+    // counters packed counter_bits at a time, low bits first. a counter never straddles two words.
+    pub(crate) count_array: Vec<u64>,
+    hash_functions: Vec<H>,
+    seed: u64,
     size: u64,
     l: u32,
+    counter_bits: u32,
 }
 
 
 impl CountingBloomFilter {
     pub fn new(expected_inserts: u64, false_positive_rate: f64) -> CountingBloomFilter {
-        let size: u64 = ((-1.44 * (expected_inserts as f64)).ceil()
-            * false_positive_rate.log2() + 0.5) as u64 ;
+        return Self::with_hasher(expected_inserts, false_positive_rate);
+    }
+}
+
+impl<H: KeyHasher> CountingBloomFilter<H> {
+    // same as new, but draws the hash functions from H instead of multiply-shift.
+    pub fn with_hasher(expected_inserts: u64, false_positive_rate: f64) -> Self {
+        return Self::with_seed(expected_inserts, false_positive_rate, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    pub fn with_seed(expected_inserts: u64, false_positive_rate: f64, seed: u64) -> Self {
+        return Self::with_counter_bits(expected_inserts, false_positive_rate, COUNTER_BITS, seed);
+    }
+
+    // counters of counter_bits bits, 4, 8 or 16. wider counters take more keys on a counter before it overflows,
+    // which matters when the same keys are inserted many times.
+    pub fn with_counter_bits(expected_inserts: u64, false_positive_rate: f64, counter_bits: u32, seed: u64) -> Self {
+        assert!(matches!(counter_bits, 4 | 8 | 16), "counters must be 4, 8 or 16 bits");
+        let mut rng = utils::seeded_rng(seed);
+        // at least one counter, or no inserts expected would leave nothing to hash into.
+        let size: u64 = (((-1.44 * (expected_inserts as f64)).ceil()
+            * false_positive_rate.log2() + 0.5) as u64).max(1);
         let num_hashes = (-false_positive_rate.log2() + 0.5) as usize;
         CountingBloomFilter {
-            count_array: vec![0; size as usize],
-            hash_functions: Self::generate_hash_functions(num_hashes, size),
+            count_array: vec![0; Self::words(size, counter_bits)],
+            hash_functions: Self::generate_hash_functions(num_hashes, size, &mut rng),
+            seed,
             size,
             l: 64 - (size - 1).leading_zeros(),
+            counter_bits,
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    pub fn counter_bits(&self) -> u32 {
+        return self.counter_bits;
+    }
+
+    // the most keys a single counter can stand for.
+    pub fn counter_max(&self) -> u64 {
+        return (1 << self.counter_bits) - 1;
+    }
+
+    // how many times the key was inserted, less the times it was deleted. the smallest of its counters, so it may
+    // overcount, like member, but never undercounts.
+    pub fn count(&self, key: u64) -> u64 {
+        return self.indexes(key).iter().map(|index| self.get_counter(*index)).min().unwrap_or(0);
+    }
+
+    // as insert, but says which counter would have overflowed. the filter is unchanged if it returns an error.
+    pub fn try_insert(&mut self, key: u64) -> Result<(), CounterError> {
+        let indexes = self.indexes(key);
+        for index in &indexes {
+            // a key can hash to the same counter more than once, and adds one to it each time.
+            let times = indexes.iter().filter(|other| *other == index).count() as u64;
+            if self.get_counter(*index) + times > self.counter_max() {
+                return Err(CounterError::Overflow { index: *index as u64, max: self.counter_max() });
+            }
+        }
+        for index in indexes {
+            self.set_counter(index, self.get_counter(index) + 1);
+        }
+        return Ok(());
+    }
+
+    // as delete, but says why the key could not be deleted. the filter is unchanged if it returns an error.
+    pub fn try_delete(&mut self, key: u64) -> Result<(), CounterError> {
+        if !self.member(key) {
+            return Err(CounterError::NotMember);
+        }
+        let indexes = self.indexes(key);
+        for index in &indexes {
+            let times = indexes.iter().filter(|other| *other == index).count() as u64;
+            if self.get_counter(*index) < times {
+                return Err(CounterError::Underflow { index: *index as u64 });
+            }
+        }
+        for index in indexes {
+            self.set_counter(index, self.get_counter(index) - 1);
         }
+        return Ok(());
     }
 
-    fn generate_hash_functions(n: usize, _m: u64) -> Vec<(u64, u64,u64)> {
-        let mut rng = rand::thread_rng();
+    fn generate_hash_functions(n: usize, _m: u64, rng: &mut ChaCha8Rng) -> Vec<H> {
         let mut hash_functions = Vec::new();
         for _ in 0..n {
-            let a1: u64 = rng.gen_range(1..=u64::MAX );
-            let a2: u64 = rng.gen_range(1..=u64::MAX);
-            let b: u64 = rng.gen_range(1..=u64::MAX);
-            hash_functions.push((a1,a2,b));
+            hash_functions.push(H::from_rng(rng));
         }
         return hash_functions;
     }
 
+    fn words(size: u64, counter_bits: u32) -> usize {
+        return (size * counter_bits as u64).div_ceil(64) as usize;
+    }
 
+    fn indexes(&self, key: u64) -> Vec<usize> {
+        return self.hash_functions.iter()
+            .map(|hash_function| (hash_function.hash_bits(key, self.l) % self.size as u32) as usize)
+            .collect();
+    }
 
-    // insert hashes the key for all hash functions and sets them to be true.
-    // requires a mutable reference to itself. and a reference to the key.
-    pub fn insert(&mut self, key: u64) {
-        for hash_function in &self.hash_functions {
-            let index: usize = (utils::hash(key, self.l, hash_function.0, hash_function.1, hash_function.2) % self.size as u32) as usize;
-            self.count_array[index] = self.count_array[index].saturating_add(1);
-        }
+    fn get_counter(&self, index: usize) -> u64 {
+        let per_word = (64 / self.counter_bits) as usize;
+        let offset = (index % per_word) as u32 * self.counter_bits;
+        return (self.count_array[index / per_word] >> offset) & self.counter_max();
     }
 
-    pub fn member(&self, key: u64) -> bool {
+    fn set_counter(&mut self, index: usize, value: u64) {
+        let per_word = (64 / self.counter_bits) as usize;
+        let offset = (index % per_word) as u32 * self.counter_bits;
+        let mask = self.counter_max() << offset;
+        let word = &mut self.count_array[index / per_word];
+        *word = (*word & !mask) | (value << offset);
+    }
+}
+
+impl<H: KeyHasher> DynamicFilter for CountingBloomFilter<H> {
+    // insert adds one to the counter of every hash function.
+    // returns false, and leaves the filter as it was, if one of the counters is full.
+    fn insert(&mut self, key: u64) -> bool {
+        return self.try_insert(key).is_ok();
+    }
+}
+
+impl<H: KeyHasher> Filter for CountingBloomFilter<H> {
+    fn member(&self, key: u64) -> bool {
         for hash_function in &self.hash_functions {
-            let index: usize = (utils::hash(key, self.l, hash_function.0, hash_function.1, hash_function.2) % self.size as u32) as usize;
-            if self.count_array[index] <= 0 {
+            let index: usize = (hash_function.hash_bits(key, self.l) % self.size as u32) as usize;
+            if self.get_counter(index) == 0 {
                 return false;
             }
         }
         true
     }
+}
 
-    pub(crate) fn delete(&mut self, key: u64) {
-        if key >= self.size {
-            return
-        }
-        for hash_function in &self.hash_functions {
-            let index: usize = (utils::hash(key, self.l, hash_function.0, hash_function.1, hash_function.2) % self.size as u32) as usize;
-            self.count_array[index] = self.count_array[index].saturating_sub(1);
-        }
-
+impl<H: KeyHasher> DeletableFilter for CountingBloomFilter<H> {
+    // returns false, and leaves the filter as it was, if the key is not a member.
+    fn delete(&mut self, key: u64) -> bool {
+        return self.try_delete(key).is_ok();
     }
 }
 
-fn main() {
+impl<H: SerializableHasher> SerializableFilter for CountingBloomFilter<H> {
+    const KIND: FilterKind = FilterKind::CountingBloom;
+    const HASHER: HasherKind = H::KIND;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_u64(self.size);
+        enc.put_u64(self.l as u64);
+        enc.put_u64(self.counter_bits as u64);
+        enc.put_hashers(&self.hash_functions);
+        enc.put_u64s(&self.count_array);
+    }
 
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let size = dec.get_u64()?;
+        let l = dec.get_u32()?;
+        let counter_bits = dec.get_u32()?;
+        let hash_functions = dec.get_hashers(None)?;
+        let count_array = dec.get_u64s()?;
+        ensure(size as u32 != 0 && size <= u32::MAX as u64, "size out of range")?;
+        ensure(l <= 64, "l out of range")?;
+        ensure(matches!(counter_bits, 4 | 8 | 16), "counter width out of range")?;
+        ensure(count_array.len() == Self::words(size, counter_bits), "counter array does not match the size")?;
+        return Ok(CountingBloomFilter {
+            count_array,
+            hash_functions,
+            seed,
+            size,
+            l,
+            counter_bits,
+        });
+    }
 }
 
+impl<H: KeyHasher> FilterStats for CountingBloomFilter<H> {
+    // every insert adds one to k counters, and none is refused part way, so this is exact.
+    fn len(&self) -> u64 {
+        let total: u64 = (0..self.size as usize).map(|index| self.get_counter(index)).sum();
+        return total / self.hash_functions.len().max(1) as u64;
+    }
+
+    fn capacity(&self) -> u64 {
+        return filter::half_full(self.size, self.hash_functions.len() as u64);
+    }
+
+    fn slots(&self) -> u64 {
+        return self.size;
+    }
+
+    fn occupied(&self) -> u64 {
+        return (0..self.size as usize).filter(|index| self.get_counter(*index) != 0).count() as u64;
+    }
+
+    fn size_in_bits(&self) -> usize {
+        return self.count_array.len() * 64;
+    }
+
+    fn false_positive_rate(&self) -> f64 {
+        return self.fill_ratio().powi(self.hash_functions.len() as i32);
+    }
+}
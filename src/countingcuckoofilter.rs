@@ -0,0 +1,323 @@
+use std::collections::BTreeMap;
+
+use crate::utils;
+
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::cuckoofilter::{self, CuckooTable, PartialKey, Relocate, BUCKET_SIZE, MAX_KICKS};
+use crate::filter::{self, DeletableFilter, DynamicFilter, Filter, FilterStats};
+use crate::serialize::{ensure, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
+
+/*
+Counting cuckoo filter, a multiset version of the cuckoo filter.
+A plain cuckoo filter stores a fingerprint once per insert, so a key inserted more than 2b times fills both of its
+buckets and no further copy fits. Here each slot holds a fingerprint and a small counter, and inserting a key whose
+fingerprint is already in one of its buckets only adds one to the counter. A counter that is full carries on in an
+overflow map, keyed by the fingerprint and the lower of its two buckets, which stays the same when the slot is kicked.
+count(key) is how many times the key was inserted less the times it was deleted. Like member it can overcount, when
+another key shares the fingerprint and buckets, but it never undercounts.
+The table is a CuckooTable with the counter packed above each fingerprint, so buckets, kicks and the victim stash are
+the cuckoo filter's, and a counter moves along with its fingerprint.
+ */
+
+const COUNTER_BITS: u32 = 4;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CountingCuckooFilter<H = MultiplyShift> {
+    // cells of a fingerprint with its counter above it.
+    pub(crate) table: CuckooTable,
+    l: u32,
+    hasher: H,
+    seed: u64,
+    // counts past the counter maximum, by lower bucket and fingerprint.
+    overflow: BTreeMap<(usize, u64), u64>,
+    // the sum of all counts.
+    items: u64,
+}
+
+impl CountingCuckooFilter {
+    pub fn new(capacity: usize, false_positive_rate: f64) -> CountingCuckooFilter {
+        return Self::with_hasher(capacity, false_positive_rate);
+    }
+}
+
+impl<H: KeyHasher> CountingCuckooFilter<H> {
+    // same as new, but draws the hash functions from H instead of multiply-shift.
+    pub fn with_hasher(capacity: usize, false_positive_rate: f64) -> Self {
+        return Self::with_seed(capacity, false_positive_rate, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    pub fn with_seed(capacity: usize, false_positive_rate: f64, seed: u64) -> Self {
+        return Self::with_counter_bits(capacity, false_positive_rate, COUNTER_BITS, seed);
+    }
+
+    // capacity is the number of distinct keys. wider counters send fewer counts to the overflow map, which only
+    // matters when most keys are inserted many times.
+    pub fn with_counter_bits(capacity: usize, false_positive_rate: f64, counter_bits: u32, seed: u64) -> Self {
+        assert!((1..=16).contains(&counter_bits), "counters must be 1 to 16 bits");
+        let (bucket_count, fingerprint_bits) = cuckoofilter::sized_for(capacity, false_positive_rate);
+        let mut rng = utils::seeded_rng(seed);
+        let hasher = H::from_rng(&mut rng);
+        let table = CuckooTable::new(bucket_count, BUCKET_SIZE, fingerprint_bits, counter_bits, false, MAX_KICKS, rng);
+        CountingCuckooFilter {
+            l: table.bucket_count.trailing_zeros(),
+            table,
+            hasher,
+            seed,
+            overflow: BTreeMap::new(),
+            items: 0,
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    // how many times the key was inserted, less the times it was deleted. may overcount, like member.
+    pub fn count(&self, key: u64) -> u64 {
+        let (i_1, f) = self.locate(key);
+        let i_2 = self.alternate(i_1, f);
+        return self.find(i_1, i_2, f).map(|(_, count)| count).unwrap_or(0);
+    }
+
+    // whether an insert ran out of kicks, so that the victim stash is in use.
+    pub fn is_full(&self) -> bool {
+        return self.table.victim.is_some();
+    }
+
+    fn partial_key(&self) -> PartialKey<'_, H> {
+        return PartialKey { hasher: &self.hasher, l: self.l };
+    }
+
+    fn locate(&self, key: u64) -> (usize, u64) {
+        return self.partial_key().locate(key, self.table.fingerprint_bits);
+    }
+
+    fn alternate(&self, bucket: usize, f: u64) -> usize {
+        return self.partial_key().alternate(bucket, f);
+    }
+
+    fn counter_max(&self) -> u64 {
+        return (1 << self.table.extra_bits) - 1;
+    }
+
+    fn cell(&self, f: u64, counter: u64) -> u64 {
+        return f | counter << self.table.fingerprint_bits;
+    }
+
+    fn counter(&self, cell: u64) -> u64 {
+        return cell >> self.table.fingerprint_bits;
+    }
+
+    // the slot holding f in either bucket, or None for the victim, with the full count, overflow included.
+    fn find(&self, i_1: usize, i_2: usize, f: u64) -> Option<(Option<usize>, u64)> {
+        let overflow = self.overflow.get(&(i_1.min(i_2), f)).copied().unwrap_or(0);
+        for slot in self.table.slots_of(i_1, i_2) {
+            let cell = self.table.get_cell(slot);
+            if self.table.fingerprint(cell) == f {
+                return Some((Some(slot), self.counter(cell) + overflow));
+            }
+        }
+        match self.table.victim_in(i_1, i_2) {
+            Some(cell) if self.table.fingerprint(cell) == f => return Some((None, self.counter(cell) + overflow)),
+            _ => return None,
+        }
+    }
+
+    // the counter of f, in its slot or in the victim stash, becomes counter.
+    fn set_counter(&mut self, slot: Option<usize>, f: u64, counter: u64) {
+        let cell = self.cell(f, counter);
+        match slot {
+            Some(slot) => self.table.set_cell(slot, cell),
+            None => self.table.victim.as_mut().unwrap().1 = cell,
+        }
+    }
+}
+
+impl<H: KeyHasher> DynamicFilter for CountingCuckooFilter<H> {
+    // returns false if the key is new, both of its buckets are full and the victim stash is in use already.
+    fn insert(&mut self, key: u64) -> bool {
+        let (i_1, f) = self.locate(key);
+        let i_2 = self.alternate(i_1, f);
+        match self.find(i_1, i_2, f) {
+            Some((slot, _)) => {
+                let counter = self.counter(match slot {
+                    Some(slot) => self.table.get_cell(slot),
+                    None => self.table.victim.unwrap().1,
+                });
+                if counter == self.counter_max() {
+                    *self.overflow.entry((i_1.min(i_2), f)).or_insert(0) += 1;
+                } else {
+                    self.set_counter(slot, f, counter + 1);
+                }
+            }
+            None => {
+                let cell = self.cell(f, 1);
+                if !self.table.insert(i_1, i_2, cell, &mut PartialKey { hasher: &self.hasher, l: self.l }) {
+                    return false;
+                }
+            }
+        }
+        self.items += 1;
+        return true;
+    }
+}
+
+impl<H: KeyHasher> Filter for CountingCuckooFilter<H> {
+    fn member(&self, key: u64) -> bool {
+        let (i_1, f) = self.locate(key);
+        return self.find(i_1, self.alternate(i_1, f), f).is_some();
+    }
+}
+
+impl<H: KeyHasher> DeletableFilter for CountingCuckooFilter<H> {
+    // takes one off the key's count. the overflow is used up before the counter.
+    fn delete(&mut self, key: u64) -> bool {
+        let (i_1, f) = self.locate(key);
+        let i_2 = self.alternate(i_1, f);
+        let slot = match self.find(i_1, i_2, f) {
+            Some((slot, _)) => slot,
+            None => return false,
+        };
+        self.items -= 1;
+        let lower = (i_1.min(i_2), f);
+        if let Some(overflow) = self.overflow.get_mut(&lower) {
+            *overflow -= 1;
+            if *overflow == 0 {
+                self.overflow.remove(&lower);
+            }
+            return true;
+        }
+        let counter = self.counter(match slot {
+            Some(slot) => self.table.get_cell(slot),
+            None => self.table.victim.unwrap().1,
+        });
+        if counter > 1 {
+            self.set_counter(slot, f, counter - 1);
+            return true;
+        }
+        match slot {
+            Some(slot) => {
+                self.table.clear(slot);
+                // a delete makes room, so the victim, if there is one, gets another go at finding a slot.
+                self.table.reinsert_victim(&mut PartialKey { hasher: &self.hasher, l: self.l });
+            }
+            None => self.table.victim = None,
+        }
+        return true;
+    }
+}
+
+impl<H: KeyHasher> FilterStats for CountingCuckooFilter<H> {
+    // every insert still counted, duplicates included.
+    fn len(&self) -> u64 {
+        return self.items;
+    }
+
+    // distinct keys, one per cell.
+    fn capacity(&self) -> u64 {
+        return self.slots();
+    }
+
+    fn slots(&self) -> u64 {
+        return self.table.slots() as u64;
+    }
+
+    fn occupied(&self) -> u64 {
+        return self.table.occupied as u64;
+    }
+
+    fn size_in_bits(&self) -> usize {
+        return self.table.words.len() * 64 + self.overflow.len() * 3 * 64;
+    }
+
+    // a query compares its fingerprint against the cells in use in its two buckets.
+    fn false_positive_rate(&self) -> f64 {
+        let compared = 2.0 * self.table.bucket_size as f64 * self.fill_ratio();
+        return filter::fingerprint_false_positive_rate(compared, self.table.fingerprint_bits);
+    }
+}
+
+impl<H: SerializableHasher> SerializableFilter for CountingCuckooFilter<H> {
+    const KIND: FilterKind = FilterKind::CountingCuckoo;
+    const HASHER: HasherKind = H::KIND;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_u64(self.table.bucket_count as u64);
+        enc.put_u64(self.table.bucket_size as u64);
+        enc.put_u64(self.table.fingerprint_bits as u64);
+        enc.put_u64(self.table.extra_bits as u64);
+        enc.put_u64(self.table.max_kicks as u64);
+        enc.put_u64(self.table.victim.is_some() as u64);
+        let (bucket, victim) = self.table.victim.unwrap_or((0, 0));
+        enc.put_u64(bucket as u64);
+        enc.put_u64(self.table.fingerprint(victim));
+        enc.put_u64(self.counter(victim));
+        self.table.encode_rng(enc);
+        enc.put_hashers(std::slice::from_ref(&self.hasher));
+        enc.put_u64s(&self.table.words);
+        enc.put_u64s(&self.overflow.keys().map(|(bucket, _)| *bucket as u64).collect::<Vec<u64>>());
+        enc.put_u64s(&self.overflow.keys().map(|(_, f)| *f).collect::<Vec<u64>>());
+        enc.put_u64s(&self.overflow.values().copied().collect::<Vec<u64>>());
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let bucket_count = dec.get_usize()?;
+        let bucket_size = dec.get_usize()?;
+        let fingerprint_bits = dec.get_u32()?;
+        let counter_bits = dec.get_u32()?;
+        let max_kicks = dec.get_usize()?;
+        let has_victim = dec.get_bool()?;
+        let victim_bucket = dec.get_usize()?;
+        let victim = dec.get_u64()?;
+        let victim_counter = dec.get_u64()?;
+        let rng = CuckooTable::decode_rng(dec)?;
+        let hasher = dec.get_hashers(Some(1))?.pop().unwrap();
+        let words = dec.get_u64s()?;
+        let overflow_buckets = dec.get_u64s()?;
+        let overflow_fingerprints = dec.get_u64s()?;
+        let overflow_counts = dec.get_u64s()?;
+        ensure((1..=16).contains(&counter_bits), "counter width out of range")?;
+        // checked again with the rest of the table, but the victim's cell is put together before that.
+        ensure(matches!(fingerprint_bits, 4 | 8 | 12 | 16 | 32), "fingerprint width out of range")?;
+        ensure(!has_victim || (victim >> fingerprint_bits == 0 && victim_counter != 0 && victim_counter >> counter_bits == 0),
+               "victim out of range")?;
+        ensure(overflow_buckets.len() == overflow_fingerprints.len() && overflow_buckets.len() == overflow_counts.len(),
+               "overflow columns differ in length")?;
+        ensure(overflow_counts.iter().all(|count| *count != 0), "empty overflow count")?;
+        let table = CuckooTable {
+            words,
+            bucket_count,
+            bucket_size,
+            fingerprint_bits,
+            extra_bits: counter_bits,
+            semi_sorted: false,
+            max_kicks,
+            rng,
+            victim: if has_victim { Some((victim_bucket, victim | victim_counter << fingerprint_bits)) } else { None },
+            occupied: 0,
+        }.checked()?;
+        let mut filter = CountingCuckooFilter {
+            l: bucket_count.trailing_zeros(),
+            table,
+            hasher,
+            seed,
+            overflow: BTreeMap::new(),
+            items: victim_counter * has_victim as u64,
+        };
+        for slot in 0..filter.table.slots() {
+            let cell = filter.table.get_cell(slot);
+            let (f, counter) = (filter.table.fingerprint(cell), filter.counter(cell));
+            ensure((f == 0) == (counter == 0), "cell with a count but no fingerprint, or the other way round")?;
+            filter.items += counter;
+        }
+        for i in 0..overflow_buckets.len() {
+            ensure(overflow_buckets[i] < bucket_count as u64, "overflow bucket out of range")?;
+            filter.overflow.insert((overflow_buckets[i] as usize, overflow_fingerprints[i]), overflow_counts[i]);
+            filter.items += overflow_counts[i];
+        }
+        return Ok(filter);
+    }
+}
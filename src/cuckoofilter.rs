@@ -1,131 +1,607 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
-use fastmurmur3;
+use crate::utils;
+use crate::semisort;
 
-use rand::Rng;
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::filter::{self, DeletableFilter, DynamicFilter, Filter, FilterStats};
+use crate::serialize::{ensure, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
 
-#[path="utils.rs"]
-mod utils;
+/*
+Cuckoo filter (Fan, Andersen, Kaminsky and Mitzenmacher, 2014).
+A table of buckets, each with a few slots for fingerprints. A key's fingerprint goes in one of two buckets, i_1 from
+the key's hash and i_2 = i_1 xor hash(f), so either bucket can be found from the other and the fingerprint alone.
+When both are full a fingerprint is kicked out to its other bucket, and so on, up to max_kicks times. If that runs
+out, the fingerprint left over goes into a victim stash of one, so no key is ever lost, and the filter takes no more
+keys that need kicks until a delete makes room for the victim again.
+Fingerprints are 4, 8, 12, 16 or 32 bits, packed one after the other into u64s, and buckets hold 2, 4 or 8 of them.
+Zero marks an empty slot, so fingerprints are drawn from 1..2^bits. The bucket count is a power of two, which makes
+the xor above its own inverse. The packed table, kicks and victim stash are a CuckooTable, which the counting and
+adaptive cuckoo filters are built on too, with their own data for each slot packed above its fingerprint.
+A table with buckets of 4 can be semi-sorted (section 5.2 of the paper). The order of the fingerprints in a bucket does
+not matter, so they are kept sorted, and the top 4 bits of the four of them, one of only 3876 sorted tuples, are
+stored as a 12 bit code instead of 16 bits. That saves one bit per fingerprint at the same false positive rate, for
+the cost of a table lookup on every bucket read or write. See semisort.
+ */
 
-pub struct CuckooFilter {
-    pub(crate) buckets: Vec<Vec<u8>>,
-    bucket_count: usize,
-    bucket_size: usize,
-    max_kicks: usize,
-    l: u32,
-    hash_coefficients: (u64,u64,u64),
-    full: bool,
+// the load a table with buckets of 4 reaches before inserts start to fail, from the paper.
+pub(crate) const LOAD: f64 = 0.95;
+// used by with_fpr.
+pub(crate) const BUCKET_SIZE: usize = 4;
+const FINGERPRINT_BITS: u32 = 8;
+pub(crate) const MAX_KICKS: usize = 500;
+
+// the bucket count and fingerprint width for capacity keys at the false positive rate, with buckets of BUCKET_SIZE.
+// a query compares its fingerprint with up to 2b others, so b-bit fingerprints with 2b / 2^bits <= fpr are enough,
+// rounded up to a width the table supports.
+pub(crate) fn sized_for(capacity: usize, false_positive_rate: f64) -> (usize, u32) {
+    assert!(false_positive_rate > 0.0 && false_positive_rate < 1.0, "false positive rate must be between 0 and 1");
+    let needed = (2.0 * BUCKET_SIZE as f64 / false_positive_rate).log2().ceil() as u32;
+    let fingerprint_bits = [4, 8, 12, 16, 32].into_iter().find(|bits| *bits >= needed).unwrap_or(32);
+    let bucket_count = (capacity as f64 / (BUCKET_SIZE as f64 * LOAD)).ceil() as usize;
+    return (bucket_count, fingerprint_bits);
 }
 
-impl CuckooFilter {
-    pub fn new(bucket_count: usize, max_kicks: usize, bucket_size: usize) -> Self {
-        CuckooFilter {
-            buckets: vec![vec![0; bucket_size]; bucket_count],
+// how a filter on a CuckooTable finds the other bucket of an entry that is being moved. kick and reinsert_victim move
+// one entry at a time: it goes into a slot, and whatever was in that slot is the entry being moved from then on.
+pub(crate) trait Relocate {
+    // the other bucket of the entry being moved, which is in bucket with fingerprint f.
+    fn alternate(&self, bucket: usize, f: u64) -> usize;
+
+    // the entry being moved has gone into slot. a filter that keeps something for each slot outside the table moves
+    // it along here.
+    fn placed(&mut self, _slot: usize) {}
+}
+
+// partial-key cuckoo hashing: the first bucket and the fingerprint from one hash of the key, and the other bucket from
+// the first and the fingerprint alone, so that a kicked fingerprint can be moved without its key.
+pub(crate) struct PartialKey<'a, H> {
+    pub(crate) hasher: &'a H,
+    pub(crate) l: u32,
+}
+
+impl<H: KeyHasher> PartialKey<'_, H> {
+    // the first bucket from the top l bits of the hash, and the fingerprint from the bits below them, so the two are
+    // independent. the fingerprint is never zero.
+    pub(crate) fn locate(&self, key: u64, fingerprint_bits: u32) -> (usize, u64) {
+        let hash = self.hasher.hash(key);
+        let i_1 = hash.checked_shr(64 - self.l).unwrap_or(0) as usize;
+        let below = (hash << self.l) >> (64 - fingerprint_bits);
+        return (i_1, below % ((1 << fingerprint_bits) - 1) + 1);
+    }
+}
+
+impl<H: KeyHasher> Relocate for PartialKey<'_, H> {
+    fn alternate(&self, bucket: usize, f: u64) -> usize {
+        return bucket ^ self.hasher.hash_bits(f, self.l) as usize;
+    }
+}
+
+// the packed table, kicks and victim stash of the cuckoo filters. a slot holds a cell: a fingerprint in the low
+// fingerprint_bits, zero when the slot is empty, and above it extra_bits of whatever else the filter keeps for the
+// slot, the counting filter's counter or the adaptive filter's selector. slot s is in bucket s / bucket_size.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct CuckooTable {
+    // buckets packed one after the other, with a spare word at the end so that a field straddling two words can be
+    // read as a pair. a bucket is its cells, or if it is semi-sorted the code of their sorted prefixes followed by the
+    // rest of each fingerprint.
+    pub(crate) words: Vec<u64>,
+    pub(crate) bucket_count: usize,
+    pub(crate) bucket_size: usize,
+    pub(crate) fingerprint_bits: u32,
+    pub(crate) extra_bits: u32,
+    pub(crate) semi_sorted: bool,
+    pub(crate) max_kicks: usize,
+    // picks the victim when kicking, so that inserts are reproducible from the seed too.
+    pub(crate) rng: ChaCha8Rng,
+    // the bucket and cell that could not be placed after max_kicks kicks.
+    pub(crate) victim: Option<(usize, u64)>,
+    // slots holding a fingerprint.
+    pub(crate) occupied: usize,
+}
+
+impl CuckooTable {
+    // bucket_count is rounded up to a power of two.
+    pub(crate) fn new(bucket_count: usize, bucket_size: usize, fingerprint_bits: u32, extra_bits: u32, semi_sorted: bool,
+                      max_kicks: usize, rng: ChaCha8Rng) -> Self {
+        assert!(matches!(bucket_size, 2 | 4 | 8), "buckets must hold 2, 4 or 8 fingerprints");
+        assert!(matches!(fingerprint_bits, 4 | 8 | 12 | 16 | 32), "fingerprints must be 4, 8, 12, 16 or 32 bits");
+        assert!(!semi_sorted || (bucket_size == 4 && extra_bits == 0), "only plain buckets of 4 can be semi-sorted");
+        let bucket_count = bucket_count.max(1).next_power_of_two();
+        assert!(bucket_count <= 1 << 32, "at most 2^32 buckets");
+        let bucket_bits = Self::bucket_bits(bucket_size, fingerprint_bits + extra_bits, semi_sorted);
+        return CuckooTable {
+            words: vec![0; Self::words(bucket_count, bucket_bits)],
             bucket_count,
             bucket_size,
+            fingerprint_bits,
+            extra_bits,
+            semi_sorted,
             max_kicks,
-            l: utils::log_base(bucket_count as f64, 2f64) as u32,
-            hash_coefficients: Self::get_hash_coefficients(),
-            full: false
+            rng,
+            victim: None,
+            occupied: 0,
+        };
+    }
+
+    // check a table that a filter's decode has read back, and count its occupied slots. the filter checks its own
+    // extra bits.
+    pub(crate) fn checked(mut self) -> Result<Self, SerializeError> {
+        let bits = self.fingerprint_bits;
+        ensure(self.bucket_count.is_power_of_two() && self.bucket_count <= 1 << 32, "bucket count is not a power of two")?;
+        ensure(matches!(self.bucket_size, 2 | 4 | 8), "bucket size out of range")?;
+        ensure(matches!(bits, 4 | 8 | 12 | 16 | 32), "fingerprint width out of range")?;
+        ensure(self.extra_bits <= 16, "extra bits out of range")?;
+        ensure(!self.semi_sorted || (self.bucket_size == 4 && self.extra_bits == 0),
+               "semi-sorted buckets hold 4 fingerprints")?;
+        let bucket_bits = Self::bucket_bits(self.bucket_size, bits + self.extra_bits, self.semi_sorted);
+        ensure(self.bucket_count.checked_mul(bucket_bits).is_some()
+            && Self::words(self.bucket_count, bucket_bits) == self.words.len(), "table does not match the bucket count")?;
+        if let Some((bucket, cell)) = self.victim {
+            ensure(bucket < self.bucket_count && self.fingerprint(cell) != 0 && cell >> (bits + self.extra_bits) == 0,
+                   "victim out of range")?;
         }
+        // a semi-sorted code can only be checked once the table is in place. every code below 3876 is some sorted tuple.
+        for bucket in 0..self.bucket_count {
+            if self.semi_sorted {
+                let code = self.get_bits(bucket * bucket_bits, semisort::CODE_BITS);
+                ensure((code as usize) < semisort::CODES, "semi-sorted code out of range")?;
+            }
+            let cells = self.get_bucket(bucket);
+            self.occupied += cells[..self.bucket_size].iter().filter(|cell| self.fingerprint(**cell) != 0).count();
+        }
+        return Ok(self);
+    }
+
+    pub(crate) fn bucket_bits(bucket_size: usize, cell_bits: u32, semi_sorted: bool) -> usize {
+        if semi_sorted {
+            return semisort::CODE_BITS as usize + 4 * (cell_bits - semisort::PREFIX_BITS) as usize;
+        }
+        return bucket_size * cell_bits as usize;
     }
 
-    fn get_hash_coefficients() -> (u64,u64,u64) {
-        let mut rng = rand::thread_rng();
-        let a1: u64 = rng.gen_range(1..=u64::MAX );
-        let a2: u64 = rng.gen_range(1..=u64::MAX);
-        let b: u64 = rng.gen_range(1..=u64::MAX);
-        return (a1, a2, b);
+    fn words(bucket_count: usize, bucket_bits: usize) -> usize {
+        return (bucket_count * bucket_bits).div_ceil(64) + 1;
     }
-    
-    fn fingerprint(&self, key: u64) -> u8 {
-        return utils::hash(key, self.l, self.hash_coefficients.0, self.hash_coefficients.1, self.hash_coefficients.2) as u8;
-        return fastmurmur3::hash(&key.to_ne_bytes()) as u8;
-        //return murmur3_x64_128(&mut b"{key}", seed).unwrap() as u32;
+
+    pub(crate) fn slots(&self) -> usize {
+        return self.bucket_count * self.bucket_size;
     }
-    fn hash2(&self, i_1: u32, f: u32) -> u32 {
-        return i_1 ^ utils::hash(f as u64, self.l, self.hash_coefficients.0, self.hash_coefficients.1, self.hash_coefficients.2);
+
+    // the word a bucket starts in, to prefetch.
+    pub(crate) fn bucket_word(&self, bucket: usize) -> &u64 {
+        let bucket_bits = Self::bucket_bits(self.bucket_size, self.fingerprint_bits + self.extra_bits, self.semi_sorted);
+        return &self.words[bucket * bucket_bits / 64];
     }
-    fn find_empty_and_set(&mut self, index: usize, f: u8) -> bool {
-        for j in 0..self.bucket_size {
-            if self.buckets[index as usize][j] == 0 {
-                self.buckets[index as usize][j] = f as u8;
-                return true;
-            }
+
+    pub(crate) fn fingerprint(&self, cell: u64) -> u64 {
+        return cell & ((1 << self.fingerprint_bits) - 1);
+    }
+
+    // a 4 bit semi-sorted fingerprint has no bits past its prefix, and its empty field can sit past the last word.
+    fn get_bits(&self, bit: usize, width: u32) -> u64 {
+        if width == 0 {
+            return 0;
         }
-        return false;
+        let (word, offset) = (bit / 64, (bit % 64) as u32);
+        let pair = self.words[word] as u128 | (self.words[word + 1] as u128) << 64;
+        return (pair >> offset) as u64 & ((1u64 << width) - 1);
     }
 
-    pub fn insert(&mut self, key: u64) -> bool {
-        if self.full {
-            return false;
+    fn set_bits(&mut self, bit: usize, width: u32, value: u64) {
+        if width == 0 {
+            return;
         }
-        let mut f = self.fingerprint(key) as u64;
-        let i_1 = utils::hash(key,self.l, self.hash_coefficients.0, self.hash_coefficients.1, self.hash_coefficients.2) % self.bucket_count as u32;
-        let i_2 = Self::hash2(self,i_1, f as u32) % self.bucket_count as u32;
+        let (word, offset) = (bit / 64, (bit % 64) as u32);
+        let mask = (((1u64 << width) - 1) as u128) << offset;
+        let mut pair = self.words[word] as u128 | (self.words[word + 1] as u128) << 64;
+        pair = (pair & !mask) | ((value as u128) << offset);
+        self.words[word] = pair as u64;
+        self.words[word + 1] = (pair >> 64) as u64;
+    }
 
-        // Try insert in bucket i_1
-        if Self::find_empty_and_set(self, i_1 as usize, f as u8) {
-            //println!("all fine");
-            return true;
+    // the cell in a slot. semi-sorted buckets have no fixed slots, so they are only read a bucket at a time.
+    pub(crate) fn get_cell(&self, slot: usize) -> u64 {
+        debug_assert!(!self.semi_sorted);
+        let cell_bits = self.fingerprint_bits + self.extra_bits;
+        return self.get_bits(slot * cell_bits as usize, cell_bits);
+    }
+
+    pub(crate) fn set_cell(&mut self, slot: usize, cell: u64) {
+        debug_assert!(!self.semi_sorted);
+        let cell_bits = self.fingerprint_bits + self.extra_bits;
+        self.set_bits(slot * cell_bits as usize, cell_bits, cell);
+    }
+
+    // the cells of a bucket, zero for an empty slot. only the first bucket_size are used.
+    pub(crate) fn get_bucket(&self, bucket: usize) -> [u64; 8] {
+        let mut cells = [0; 8];
+        if self.semi_sorted {
+            let bits = self.fingerprint_bits;
+            let rest = bits - semisort::PREFIX_BITS;
+            let start = bucket * Self::bucket_bits(4, bits, true);
+            let prefixes = semisort::decode(self.get_bits(start, semisort::CODE_BITS) as u16);
+            for i in 0..4 {
+                let low = self.get_bits(start + semisort::CODE_BITS as usize + i * rest as usize, rest);
+                cells[i] = (prefixes[i] as u64) << rest | low;
+            }
+            return cells;
         }
-        // Try insert in bucket i_2
-        if Self::find_empty_and_set(self, i_2 as usize, f as u8) {
-            //println!("all fine");
-            return true;
+        for i in 0..self.bucket_size {
+            cells[i] = self.get_cell(bucket * self.bucket_size + i);
+        }
+        return cells;
+    }
+
+    pub(crate) fn set_bucket(&mut self, bucket: usize, cells: &[u64; 8]) {
+        if self.semi_sorted {
+            // sorting the whole fingerprints sorts their prefixes, and keeps each rest in line with its prefix.
+            let bits = self.fingerprint_bits;
+            let rest = bits - semisort::PREFIX_BITS;
+            let start = bucket * Self::bucket_bits(4, bits, true);
+            let mut sorted = [cells[0], cells[1], cells[2], cells[3]];
+            sorted.sort_unstable();
+            let code = semisort::encode(sorted.map(|f| (f >> rest) as u8));
+            self.set_bits(start, semisort::CODE_BITS, code as u64);
+            for i in 0..4 {
+                self.set_bits(start + semisort::CODE_BITS as usize + i * rest as usize, rest, sorted[i] & ((1 << rest) - 1));
+            }
+            return;
+        }
+        for i in 0..self.bucket_size {
+            self.set_cell(bucket * self.bucket_size + i, cells[i]);
         }
+    }
 
-        // Both buckets are occupied, perform cuckoo eviction
-        let random_bucket = if rand::random() { i_1 } else { i_2 };
-        for _count in 0..self.max_kicks {
-            let mut rng = rand::thread_rng();
-            let random_index = rng.gen_range(0..=self.bucket_size - 1);
-            let kicked_key = std::mem::replace(&mut self.buckets[random_bucket as usize][random_index], f as u8);
-            f = kicked_key as u64;
-            let new_i = (random_bucket ^ utils::hash(f,self.l, self.hash_coefficients.0, self.hash_coefficients.1, self.hash_coefficients.2))
-                % self.bucket_count as u32;
+    // the slots of bucket i_1 and then of i_2, once only if they are the same bucket.
+    pub(crate) fn slots_of(&self, i_1: usize, i_2: usize) -> impl Iterator<Item = usize> {
+        let b = self.bucket_size;
+        let second = if i_1 == i_2 { 0..0 } else { i_2 * b..(i_2 + 1) * b };
+        return (i_1 * b..(i_1 + 1) * b).chain(second);
+    }
 
-            // try inserting kicked_key into new_i
-            if Self::find_empty_and_set(self, new_i as usize, f as u8) {
-                //println!("finally inserted, after: '{}' kicks", count);
+    pub(crate) fn bucket_contains(&self, bucket: usize, f: u64) -> bool {
+        return self.get_bucket(bucket)[..self.bucket_size].iter().any(|cell| self.fingerprint(*cell) == f);
+    }
+
+    pub(crate) fn victim_in(&self, i_1: usize, i_2: usize) -> Option<u64> {
+        return match self.victim {
+            Some((bucket, cell)) if bucket == i_1 || bucket == i_2 => Some(cell),
+            _ => None,
+        };
+    }
+
+    // the slot the cell went into, which in a semi-sorted bucket only says that it went in.
+    pub(crate) fn find_empty_and_set(&mut self, bucket: usize, cell: u64) -> Option<usize> {
+        let mut cells = self.get_bucket(bucket);
+        for slot in 0..self.bucket_size {
+            if cells[slot] == 0 {
+                cells[slot] = cell;
+                self.set_bucket(bucket, &cells);
+                self.occupied += 1;
+                return Some(bucket * self.bucket_size + slot);
+            }
+        }
+        return None;
+    }
+
+    // empty the first slot of the bucket with fingerprint f.
+    pub(crate) fn remove_from(&mut self, bucket: usize, f: u64) -> bool {
+        let mut cells = self.get_bucket(bucket);
+        for slot in 0..self.bucket_size {
+            if self.fingerprint(cells[slot]) == f {
+                cells[slot] = 0;
+                self.set_bucket(bucket, &cells);
+                self.occupied -= 1;
                 return true;
             }
         }
-        // Failed to evict after maximum kicks
-        //println!("failed to insert.");
-        self.full = true;
         return false;
     }
 
-    pub fn member(&self, key: u64) -> bool {
-        let f = self.fingerprint(key) as u64;
-        let i_1 = utils::hash(key,self.l, self.hash_coefficients.0, self.hash_coefficients.1, self.hash_coefficients.2) % self.bucket_count as u32;
-        let i_2 = Self::hash2(self,i_1, f as u32) % self.bucket_count as u32;
-        for j in 0..self.bucket_size {
-            if self.buckets[i_1 as usize][j] == f as u8 || self.buckets[i_2 as usize][j] == f as u8 {
-                return true
+    pub(crate) fn clear(&mut self, slot: usize) {
+        self.set_cell(slot, 0);
+        self.occupied -= 1;
+    }
+
+    // put the cell in either of its buckets, kicking if both are full. returns false, and changes nothing, if both
+    // are full and the victim stash is in use already.
+    pub(crate) fn insert(&mut self, i_1: usize, i_2: usize, cell: u64, entries: &mut impl Relocate) -> bool {
+        if let Some(slot) = self.find_empty_and_set(i_1, cell).or_else(|| self.find_empty_and_set(i_2, cell)) {
+            entries.placed(slot);
+            return true;
+        }
+        if self.victim.is_some() {
+            return false;
+        }
+        let bucket = if self.rng.gen() { i_1 } else { i_2 };
+        self.kick(bucket, cell, entries);
+        return true;
+    }
+
+    // put the cell in the full bucket by kicking a random entry out to its other bucket, and so on. whatever is left
+    // after max_kicks becomes the victim.
+    fn kick(&mut self, mut bucket: usize, mut cell: u64, entries: &mut impl Relocate) {
+        for _ in 0..self.max_kicks {
+            let slot = self.rng.gen_range(0..self.bucket_size);
+            let mut cells = self.get_bucket(bucket);
+            let kicked = std::mem::replace(&mut cells[slot], cell);
+            self.set_bucket(bucket, &cells);
+            // kick only ever starts at, or moves on to, a bucket that find_empty_and_set found full.
+            debug_assert_ne!(kicked, 0, "kicked from a bucket with an empty slot");
+            entries.placed(bucket * self.bucket_size + slot);
+            cell = kicked;
+            bucket = entries.alternate(bucket, self.fingerprint(cell));
+            if let Some(slot) = self.find_empty_and_set(bucket, cell) {
+                entries.placed(slot);
+                return;
             }
         }
-        return false;
+        self.victim = Some((bucket, cell));
     }
 
-    pub(crate) fn delete(&mut self, key: u64) -> bool {
-        let f = self.fingerprint(key) as u64;
-        let i_1 = utils::hash(key,self.l, self.hash_coefficients.0, self.hash_coefficients.1, self.hash_coefficients.2) % self.bucket_count as u32;
-        let i_2 = Self::hash2(self,i_1, f as u32) % self.bucket_count as u32;
-        for j in 0..self.bucket_size {
-            if self.buckets[i_1 as usize][j] == f as u8 {
-                self.buckets[i_1 as usize][j] = 0;
-                return true;
+    // after a delete, put the victim in a free slot of either of its buckets, and only kick if both are still full.
+    pub(crate) fn reinsert_victim(&mut self, entries: &mut impl Relocate) {
+        if let Some((bucket, cell)) = self.victim.take() {
+            let other = entries.alternate(bucket, self.fingerprint(cell));
+            if let Some(slot) = self.find_empty_and_set(bucket, cell).or_else(|| self.find_empty_and_set(other, cell)) {
+                entries.placed(slot);
+                return;
             }
-            else if self.buckets[i_2 as usize][j] == f as u8 {
-                self.buckets[i_2 as usize][j] = 0;
-                return true;
+            self.kick(bucket, cell, entries);
+        }
+    }
+
+    // the eviction rng is saved mid stream, so a loaded filter kicks the same victims as the original.
+    pub(crate) fn encode_rng(&self, enc: &mut Encoder) {
+        enc.put_u8s(&self.rng.get_seed());
+        enc.put_u64(self.rng.get_stream());
+        let word_pos = self.rng.get_word_pos();
+        enc.put_u64(word_pos as u64);
+        enc.put_u64((word_pos >> 64) as u64);
+    }
+
+    pub(crate) fn decode_rng(dec: &mut Decoder) -> Result<ChaCha8Rng, SerializeError> {
+        let rng_seed: [u8; 32] = dec.get_u8s()?.try_into()
+            .map_err(|_| SerializeError::Corrupt("rng seed is not 32 bytes"))?;
+        let mut rng = ChaCha8Rng::from_seed(rng_seed);
+        rng.set_stream(dec.get_u64()?);
+        rng.set_word_pos(dec.get_u64()? as u128 | (dec.get_u64()? as u128) << 64);
+        return Ok(rng);
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CuckooFilter<H = MultiplyShift> {
+    pub(crate) table: CuckooTable,
+    l: u32,
+    hasher: H,
+    seed: u64,
+}
+
+impl CuckooFilter {
+    pub fn new(bucket_count: usize, max_kicks: usize, bucket_size: usize) -> CuckooFilter {
+        return Self::with_hasher(bucket_count, max_kicks, bucket_size);
+    }
+
+    // a filter for capacity keys at the false positive rate, with the fingerprint width and bucket count picked
+    // to match.
+    pub fn with_fpr(capacity: usize, false_positive_rate: f64) -> CuckooFilter {
+        return Self::with_fpr_seed(capacity, false_positive_rate, rand::random());
+    }
+}
+
+impl<H: KeyHasher> CuckooFilter<H> {
+    // same as new, but draws the hash functions from H instead of multiply-shift.
+    pub fn with_hasher(bucket_count: usize, max_kicks: usize, bucket_size: usize) -> Self {
+        return Self::with_seed(bucket_count, max_kicks, bucket_size, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    pub fn with_seed(bucket_count: usize, max_kicks: usize, bucket_size: usize, seed: u64) -> Self {
+        return Self::with_fingerprint_bits(bucket_count, max_kicks, bucket_size, FINGERPRINT_BITS, seed);
+    }
+
+    // bucket_count is rounded up to a power of two.
+    pub fn with_fingerprint_bits(bucket_count: usize, max_kicks: usize, bucket_size: usize, fingerprint_bits: u32, seed: u64) -> Self {
+        return Self::build(bucket_count, max_kicks, bucket_size, fingerprint_bits, false, seed);
+    }
+
+    // buckets of 4 fingerprints, semi-sorted, so one bit per fingerprint smaller than with_fingerprint_bits with the
+    // same fingerprints.
+    pub fn with_semi_sorted_buckets(bucket_count: usize, max_kicks: usize, fingerprint_bits: u32, seed: u64) -> Self {
+        return Self::build(bucket_count, max_kicks, 4, fingerprint_bits, true, seed);
+    }
+
+    fn build(bucket_count: usize, max_kicks: usize, bucket_size: usize, fingerprint_bits: u32, semi_sorted: bool, seed: u64) -> Self {
+        let mut rng = utils::seeded_rng(seed);
+        let hasher = H::from_rng(&mut rng);
+        let table = CuckooTable::new(bucket_count, bucket_size, fingerprint_bits, 0, semi_sorted, max_kicks, rng);
+        CuckooFilter {
+            l: table.bucket_count.trailing_zeros(),
+            table,
+            hasher,
+            seed,
+        }
+    }
+
+    pub fn with_fpr_seed(capacity: usize, false_positive_rate: f64, seed: u64) -> Self {
+        let (bucket_count, fingerprint_bits) = sized_for(capacity, false_positive_rate);
+        return Self::with_fingerprint_bits(bucket_count, MAX_KICKS, BUCKET_SIZE, fingerprint_bits, seed);
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    pub fn fingerprint_bits(&self) -> u32 {
+        return self.table.fingerprint_bits;
+    }
+
+    pub fn bucket_count(&self) -> usize {
+        return self.table.bucket_count;
+    }
+
+    pub fn is_semi_sorted(&self) -> bool {
+        return self.table.semi_sorted;
+    }
+
+    // whether an insert ran out of kicks, so that the victim stash is in use. a full filter only takes keys that have
+    // an empty slot in one of their buckets.
+    pub fn is_full(&self) -> bool {
+        return self.table.victim.is_some();
+    }
+
+    fn partial_key(&self) -> PartialKey<'_, H> {
+        return PartialKey { hasher: &self.hasher, l: self.l };
+    }
+
+    fn locate(&self, key: u64) -> (usize, u64) {
+        return self.partial_key().locate(key, self.table.fingerprint_bits);
+    }
+
+    fn alternate(&self, bucket: usize, f: u64) -> usize {
+        return self.partial_key().alternate(bucket, f);
+    }
+
+    fn contains(&self, i_1: usize, i_2: usize, f: u64) -> bool {
+        return self.table.bucket_contains(i_1, f) || self.table.bucket_contains(i_2, f)
+            || self.table.victim_in(i_1, i_2) == Some(f);
+    }
+}
+
+impl<H: KeyHasher> DynamicFilter for CuckooFilter<H> {
+    // returns false if both of the key's buckets are full and the victim stash is in use already.
+    fn insert(&mut self, key: u64) -> bool {
+        let (i_1, f) = self.locate(key);
+        let i_2 = self.alternate(i_1, f);
+        return self.table.insert(i_1, i_2, f, &mut PartialKey { hasher: &self.hasher, l: self.l });
+    }
+}
+
+impl<H: KeyHasher> Filter for CuckooFilter<H> {
+    fn member(&self, key: u64) -> bool {
+        let (i_1, f) = self.locate(key);
+        return self.contains(i_1, self.alternate(i_1, f), f);
+    }
+
+    // find both buckets of a batch of keys and prefetch them, then search them.
+    fn member_batch(&self, keys: &[u64], out: &mut [bool]) {
+        assert_eq!(keys.len(), out.len(), "member_batch needs one output per key");
+        let mut slots = [(0usize, 0usize, 0u64); utils::BATCH];
+        for (keys, out) in keys.chunks(utils::BATCH).zip(out.chunks_mut(utils::BATCH)) {
+            for i in 0..keys.len() {
+                let (i_1, f) = self.locate(keys[i]);
+                let i_2 = self.alternate(i_1, f);
+                utils::prefetch(self.table.bucket_word(i_1));
+                utils::prefetch(self.table.bucket_word(i_2));
+                slots[i] = (i_1, i_2, f);
+            }
+            for i in 0..keys.len() {
+                let (i_1, i_2, f) = slots[i];
+                out[i] = self.contains(i_1, i_2, f);
             }
         }
+    }
+}
+
+impl<H: KeyHasher> DeletableFilter for CuckooFilter<H> {
+    // a delete makes room, so the victim, if there is one, gets another go at finding a slot.
+    fn delete(&mut self, key: u64) -> bool {
+        let (i_1, f) = self.locate(key);
+        let i_2 = self.alternate(i_1, f);
+        if self.table.remove_from(i_1, f) || self.table.remove_from(i_2, f) {
+            self.table.reinsert_victim(&mut PartialKey { hasher: &self.hasher, l: self.l });
+            return true;
+        }
+        if self.table.victim_in(i_1, i_2) == Some(f) {
+            self.table.victim = None;
+            return true;
+        }
         return false;
     }
 }
 
-fn main() {
+impl<H: SerializableHasher> SerializableFilter for CuckooFilter<H> {
+    const KIND: FilterKind = FilterKind::Cuckoo;
+    const HASHER: HasherKind = H::KIND;
 
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_u64(self.table.bucket_count as u64);
+        enc.put_u64(self.table.bucket_size as u64);
+        enc.put_u64(self.table.fingerprint_bits as u64);
+        enc.put_u64(self.table.semi_sorted as u64);
+        enc.put_u64(self.table.max_kicks as u64);
+        enc.put_u64(self.table.victim.is_some() as u64);
+        let (bucket, victim) = self.table.victim.unwrap_or((0, 0));
+        enc.put_u64(bucket as u64);
+        enc.put_u64(victim);
+        self.table.encode_rng(enc);
+        enc.put_hashers(std::slice::from_ref(&self.hasher));
+        enc.put_u64s(&self.table.words);
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let bucket_count = dec.get_usize()?;
+        let bucket_size = dec.get_usize()?;
+        let fingerprint_bits = dec.get_u32()?;
+        let semi_sorted = dec.get_bool()?;
+        let max_kicks = dec.get_usize()?;
+        let has_victim = dec.get_bool()?;
+        let victim_bucket = dec.get_usize()?;
+        let victim = dec.get_u64()?;
+        let rng = CuckooTable::decode_rng(dec)?;
+        let hasher = dec.get_hashers(Some(1))?.pop().unwrap();
+        let words = dec.get_u64s()?;
+        let table = CuckooTable {
+            words,
+            bucket_count,
+            bucket_size,
+            fingerprint_bits,
+            extra_bits: 0,
+            semi_sorted,
+            max_kicks,
+            rng,
+            victim: if has_victim { Some((victim_bucket, victim)) } else { None },
+            occupied: 0,
+        }.checked()?;
+        return Ok(CuckooFilter {
+            l: bucket_count.trailing_zeros(),
+            table,
+            hasher,
+            seed,
+        });
+    }
+}
+
+impl<H: KeyHasher> FilterStats for CuckooFilter<H> {
+    // every fingerprint in the table, and the victim.
+    fn len(&self) -> u64 {
+        return (self.table.occupied + self.table.victim.is_some() as usize) as u64;
+    }
+
+    fn capacity(&self) -> u64 {
+        return self.slots();
+    }
+
+    fn slots(&self) -> u64 {
+        return self.table.slots() as u64;
+    }
+
+    fn occupied(&self) -> u64 {
+        return self.table.occupied as u64;
+    }
+
+    fn size_in_bits(&self) -> usize {
+        return self.table.words.len() * 64;
+    }
+
+    // a query compares its fingerprint against the slots in use in its two buckets.
+    fn false_positive_rate(&self) -> f64 {
+        let compared = 2.0 * self.table.bucket_size as f64 * self.fill_ratio();
+        return filter::fingerprint_false_positive_rate(compared, self.table.fingerprint_bits);
+    }
 }
@@ -0,0 +1,314 @@
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::utils;
+
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::filter::{self, DeletableFilter, DynamicFilter, Filter, FilterStats};
+use crate::serialize::{ensure, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
+
+/*
+d-left counting Bloom filter (Bonomi, Mitzenmacher, Panigrahy, Singh and Varghese, 2006).
+The table is split into D sub-tables of buckets, and each bucket holds BUCKET_SIZE cells of a remainder and a small
+counter. A key hashes to a true fingerprint f. Sub-table i applies its own permutation to f and splits the result into
+a bucket index and a remainder. The key goes into the least loaded of its D buckets, leftmost on a tie, or bumps the
+counter of the cell that already holds its remainder.
+Since the permutations are invertible, two keys that share a cell have the same f, and so the same D buckets.
+A key's cell is therefore always the one its own f leads to, which is what makes delete safe.
+Cells are packed into u64s at remainder + COUNTER_BITS bits each, instead of a u8 per counter.
+ */
+
+const D: usize = 4;
+const BUCKET_SIZE: usize = 8;
+const COUNTER_BITS: u32 = 4;
+const COUNTER_MAX: u64 = (1 << COUNTER_BITS) - 1;
+// the share of the cells expected to be in use once every key is inserted.
+const LOAD: f64 = 0.75;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DLeftCountingFilter<H = MultiplyShift> {
+    pub(crate) cells: Vec<u64>,
+    hasher: H,
+    // two odd multipliers per sub-table, for its permutation of the true fingerprint.
+    multipliers: Vec<u64>,
+    seed: u64,
+    // buckets per sub-table, a power of two.
+    buckets: usize,
+    log_buckets: u32,
+    // bits in the remainder stored in each cell.
+    r: u32,
+    // cells in use, and the sum of their counters.
+    occupied: usize,
+    items: u64,
+}
+
+impl DLeftCountingFilter {
+    pub fn new(expected_inserts: u64, false_positive_rate: f64) -> DLeftCountingFilter {
+        return Self::with_hasher(expected_inserts, false_positive_rate);
+    }
+}
+
+impl<H: KeyHasher> DLeftCountingFilter<H> {
+    // same as new, but draws the hash functions from H instead of multiply-shift.
+    pub fn with_hasher(expected_inserts: u64, false_positive_rate: f64) -> Self {
+        return Self::with_seed(expected_inserts, false_positive_rate, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    pub fn with_seed(expected_inserts: u64, false_positive_rate: f64, seed: u64) -> Self {
+        let mut rng = utils::seeded_rng(seed);
+        let cells_needed = (expected_inserts as f64 / LOAD).ceil() as usize;
+        let buckets = cells_needed.div_ceil(D * BUCKET_SIZE).max(1).next_power_of_two();
+        let log_buckets = buckets.trailing_zeros();
+        // a query compares against the remainders in D buckets, about expected_inserts / buckets of them once full,
+        // and each matches with chance 2^-r.
+        let expected_remainders = (expected_inserts as f64 / buckets as f64).max(1.0);
+        let r = (expected_remainders / false_positive_rate).log2().ceil().clamp(1.0, (60 - log_buckets) as f64) as u32;
+        let width = (r + COUNTER_BITS) as usize;
+        DLeftCountingFilter {
+            // one spare word so a cell can always be read as two.
+            cells: vec![0; (D * buckets * BUCKET_SIZE * width).div_ceil(64) + 1],
+            hasher: H::from_rng(&mut rng),
+            multipliers: Self::generate_multipliers(&mut rng),
+            seed,
+            buckets,
+            log_buckets,
+            r,
+            occupied: 0,
+            items: 0,
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    // how many times the key was inserted, less the times it was deleted. may overcount, like member, and stops at
+    // the counter maximum.
+    pub fn count(&self, key: u64) -> u64 {
+        let f = self.fingerprint(key);
+        for table in 0..D {
+            let (bucket, remainder) = self.locate(table, f);
+            if let Some(cell) = self.find(bucket, remainder) {
+                return self.get_cell(cell) & COUNTER_MAX;
+            }
+        }
+        return 0;
+    }
+
+    fn generate_multipliers(rng: &mut ChaCha8Rng) -> Vec<u64> {
+        let mut multipliers = Vec::new();
+        for _ in 0..2 * D {
+            multipliers.push(rng.gen::<u64>() | 1);
+        }
+        return multipliers;
+    }
+
+    fn fingerprint(&self, key: u64) -> u64 {
+        return self.hasher.hash(key) >> (64 - self.log_buckets - self.r);
+    }
+
+    // permute f within its log_buckets + r bits for this sub-table, and split it into the first cell of the bucket
+    // and the remainder.
+    fn locate(&self, table: usize, f: u64) -> (usize, u64) {
+        let bits = self.log_buckets + self.r;
+        let mask = (1u64 << bits) - 1;
+        let shift = bits.div_ceil(2);
+        let mut x = f;
+        for multiplier in &self.multipliers[2 * table..2 * table + 2] {
+            x ^= x >> shift;
+            x = x.wrapping_mul(*multiplier) & mask;
+        }
+        let bucket = table * self.buckets + (x >> self.r) as usize;
+        return (bucket * BUCKET_SIZE, x & ((1 << self.r) - 1));
+    }
+
+    // the cell in the bucket holding the remainder, if there is one.
+    fn find(&self, bucket: usize, remainder: u64) -> Option<usize> {
+        for cell in bucket..bucket + BUCKET_SIZE {
+            let value = self.get_cell(cell);
+            if value & COUNTER_MAX != 0 && value >> COUNTER_BITS == remainder {
+                return Some(cell);
+            }
+        }
+        return None;
+    }
+
+    // cells in use and the sum of their counters, counted from the cells themselves.
+    fn tally(&self) -> (usize, u64) {
+        let (mut occupied, mut items) = (0, 0);
+        for cell in 0..D * self.buckets * BUCKET_SIZE {
+            let count = self.get_cell(cell) & COUNTER_MAX;
+            if count != 0 {
+                occupied += 1;
+                items += count;
+            }
+        }
+        return (occupied, items);
+    }
+
+    fn load(&self, bucket: usize) -> usize {
+        return (bucket..bucket + BUCKET_SIZE).filter(|cell| self.get_cell(*cell) & COUNTER_MAX != 0).count();
+    }
+
+    fn get_cell(&self, cell: usize) -> u64 {
+        let width = self.r + COUNTER_BITS;
+        let bit = cell * width as usize;
+        let (word, offset) = (bit / 64, (bit % 64) as u32);
+        let pair = self.cells[word] as u128 | (self.cells[word + 1] as u128) << 64;
+        return (pair >> offset) as u64 & ((1u64 << width) - 1);
+    }
+
+    fn set_cell(&mut self, cell: usize, value: u64) {
+        let width = self.r + COUNTER_BITS;
+        let bit = cell * width as usize;
+        let (word, offset) = (bit / 64, (bit % 64) as u32);
+        let mask = (((1u64 << width) - 1) as u128) << offset;
+        let mut pair = self.cells[word] as u128 | (self.cells[word + 1] as u128) << 64;
+        pair = (pair & !mask) | ((value as u128) << offset);
+        self.cells[word] = pair as u64;
+        self.cells[word + 1] = (pair >> 64) as u64;
+    }
+}
+
+impl<H: KeyHasher> DynamicFilter for DLeftCountingFilter<H> {
+    // returns false if all D buckets of the key are full.
+    fn insert(&mut self, key: u64) -> bool {
+        let f = self.fingerprint(key);
+        let mut target = None;
+        let mut least = BUCKET_SIZE;
+        for table in 0..D {
+            let (bucket, remainder) = self.locate(table, f);
+            if let Some(cell) = self.find(bucket, remainder) {
+                // a full counter stays full, since it no longer knows how many keys it stands for.
+                let value = self.get_cell(cell);
+                if value & COUNTER_MAX != COUNTER_MAX {
+                    self.set_cell(cell, value + 1);
+                    self.items += 1;
+                }
+                return true;
+            }
+            let load = self.load(bucket);
+            if load < least {
+                least = load;
+                target = Some((bucket + load, remainder));
+            }
+        }
+        match target {
+            // cells in a bucket are kept packed to the front, so the first free one is at its load.
+            Some((cell, remainder)) => {
+                self.set_cell(cell, remainder << COUNTER_BITS | 1);
+                self.occupied += 1;
+                self.items += 1;
+                return true;
+            }
+            None => return false,
+        }
+    }
+}
+
+impl<H: KeyHasher> Filter for DLeftCountingFilter<H> {
+    fn member(&self, key: u64) -> bool {
+        return self.count(key) != 0;
+    }
+}
+
+impl<H: KeyHasher> DeletableFilter for DLeftCountingFilter<H> {
+    fn delete(&mut self, key: u64) -> bool {
+        let f = self.fingerprint(key);
+        for table in 0..D {
+            let (bucket, remainder) = self.locate(table, f);
+            if let Some(cell) = self.find(bucket, remainder) {
+                let value = self.get_cell(cell);
+                if value & COUNTER_MAX == COUNTER_MAX {
+                    return true;
+                }
+                self.items -= 1;
+                if value & COUNTER_MAX > 1 {
+                    self.set_cell(cell, value - 1);
+                    return true;
+                }
+                // move the last cell in the bucket into the hole, to keep it packed.
+                let last = bucket + self.load(bucket) - 1;
+                let moved = self.get_cell(last);
+                self.set_cell(cell, moved);
+                self.set_cell(last, 0);
+                self.occupied -= 1;
+                return true;
+            }
+        }
+        return false;
+    }
+}
+
+impl<H: SerializableHasher> SerializableFilter for DLeftCountingFilter<H> {
+    const KIND: FilterKind = FilterKind::DLeftCounting;
+    const HASHER: HasherKind = H::KIND;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_u64(self.buckets as u64);
+        enc.put_u64(self.r as u64);
+        enc.put_hashers(std::slice::from_ref(&self.hasher));
+        enc.put_u64s(&self.multipliers);
+        enc.put_u64s(&self.cells);
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let buckets = dec.get_usize()?;
+        let r = dec.get_u32()?;
+        let hasher = dec.get_hashers(Some(1))?.pop().unwrap();
+        let multipliers = dec.get_u64s()?;
+        let cells = dec.get_u64s()?;
+        ensure(buckets.is_power_of_two(), "buckets is not a power of two")?;
+        let log_buckets = buckets.trailing_zeros();
+        ensure(r >= 1 && log_buckets + r <= 60, "r out of range")?;
+        ensure(multipliers.len() == 2 * D && multipliers.iter().all(|m| m & 1 == 1), "bad permutation multipliers")?;
+        let width = (r + COUNTER_BITS) as usize;
+        ensure(buckets.checked_mul(D * BUCKET_SIZE * width).map(|bits| bits.div_ceil(64) + 1) == Some(cells.len()),
+               "cell array does not match the size")?;
+        let mut filter = DLeftCountingFilter {
+            cells,
+            hasher,
+            multipliers,
+            seed,
+            buckets,
+            log_buckets,
+            r,
+            occupied: 0,
+            items: 0,
+        };
+        (filter.occupied, filter.items) = filter.tally();
+        return Ok(filter);
+    }
+}
+
+impl<H: KeyHasher> FilterStats for DLeftCountingFilter<H> {
+    // exact, apart from keys counted by a counter that has saturated.
+    fn len(&self) -> u64 {
+        return self.items;
+    }
+
+    fn capacity(&self) -> u64 {
+        return (self.slots() as f64 * LOAD) as u64;
+    }
+
+    fn slots(&self) -> u64 {
+        return (D * self.buckets * BUCKET_SIZE) as u64;
+    }
+
+    fn occupied(&self) -> u64 {
+        return self.occupied as u64;
+    }
+
+    // size of the cell array in bits.
+    fn size_in_bits(&self) -> usize {
+        return self.cells.len() * 64;
+    }
+
+    // a query compares its remainder against the cells in use in its D buckets.
+    fn false_positive_rate(&self) -> f64 {
+        return filter::fingerprint_false_positive_rate((D * BUCKET_SIZE) as f64 * self.fill_ratio(), self.r);
+    }
+}
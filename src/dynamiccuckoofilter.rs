@@ -0,0 +1,164 @@
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::filter::{DeletableFilter, DynamicFilter, Filter, FilterStats};
+use crate::cuckoofilter::{self, CuckooFilter};
+use crate::serialize::{ensure, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
+
+/*
+Dynamic cuckoo filter (Chen, Liao, Jin and Wu, 2017).
+A cuckoo filter is sized up front, and once it is full it takes no more keys. This filter is a chain of cuckoo
+filters of the same size, all built from the same seed, and adds a new one when the newest reaches its load limit.
+Keys go into the newest table, and a query or delete asks every table.
+Since every table hashes a key to the same buckets and the same fingerprint, two keys with the same fingerprint in
+the same buckets look alike in any table, so deleting either one from whichever table matches first is safe.
+A table that a delete leaves empty is dropped, unless it is the only one.
+The false positive rate grows with the number of tables, about t times that of one table.
+ */
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DynamicCuckooFilter<H = MultiplyShift> {
+    tables: Vec<CuckooFilter<H>>,
+    table_capacity: usize,
+    false_positive_rate: f64,
+    seed: u64,
+}
+
+impl DynamicCuckooFilter {
+    pub fn new(table_capacity: usize, false_positive_rate: f64) -> DynamicCuckooFilter {
+        return Self::with_hasher(table_capacity, false_positive_rate);
+    }
+}
+
+impl<H: KeyHasher> DynamicCuckooFilter<H> {
+    // same as new, but draws the hash functions from H instead of multiply-shift.
+    pub fn with_hasher(table_capacity: usize, false_positive_rate: f64) -> Self {
+        return Self::with_seed(table_capacity, false_positive_rate, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    pub fn with_seed(table_capacity: usize, false_positive_rate: f64, seed: u64) -> Self {
+        assert!(table_capacity > 0, "table capacity must be at least 1");
+        assert!(false_positive_rate > 0.0 && false_positive_rate < 1.0, "false positive rate must be between 0 and 1");
+        let mut filter = DynamicCuckooFilter {
+            tables: Vec::new(),
+            table_capacity,
+            false_positive_rate,
+            seed,
+        };
+        filter.add_table();
+        return filter;
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    pub fn num_tables(&self) -> usize {
+        return self.tables.len();
+    }
+
+    fn add_table(&mut self) {
+        self.tables.push(CuckooFilter::with_fpr_seed(self.table_capacity, self.false_positive_rate, self.seed));
+    }
+
+    // a table at its load limit, or with its victim stash in use, takes no more keys.
+    fn is_full(table: &CuckooFilter<H>) -> bool {
+        return table.is_full() || table.fill_ratio() >= cuckoofilter::LOAD;
+    }
+}
+
+impl<H: KeyHasher> Filter for DynamicCuckooFilter<H> {
+    fn member(&self, key: u64) -> bool {
+        return self.tables.iter().any(|table| table.member(key));
+    }
+}
+
+impl<H: KeyHasher> DynamicFilter for DynamicCuckooFilter<H> {
+    // never fails, since a full table is followed by a new one.
+    fn insert(&mut self, key: u64) -> bool {
+        if Self::is_full(self.tables.last().unwrap()) || !self.tables.last_mut().unwrap().insert(key) {
+            self.add_table();
+            return self.tables.last_mut().unwrap().insert(key);
+        }
+        return true;
+    }
+}
+
+impl<H: KeyHasher> DeletableFilter for DynamicCuckooFilter<H> {
+    fn delete(&mut self, key: u64) -> bool {
+        for i in 0..self.tables.len() {
+            if self.tables[i].delete(key) {
+                if self.tables[i].is_empty() && self.tables.len() > 1 {
+                    self.tables.remove(i);
+                }
+                return true;
+            }
+        }
+        return false;
+    }
+}
+
+impl<H: KeyHasher> FilterStats for DynamicCuckooFilter<H> {
+    fn len(&self) -> u64 {
+        return self.tables.iter().map(|table| table.len()).sum();
+    }
+
+    fn capacity(&self) -> u64 {
+        return self.tables.iter().map(|table| table.capacity()).sum();
+    }
+
+    fn slots(&self) -> u64 {
+        return self.tables.iter().map(|table| table.slots()).sum();
+    }
+
+    fn occupied(&self) -> u64 {
+        return self.tables.iter().map(|table| table.occupied()).sum();
+    }
+
+    fn size_in_bits(&self) -> usize {
+        return self.tables.iter().map(|table| table.size_in_bits()).sum();
+    }
+
+    // a key not in the filter is a false positive if any table says it is a member.
+    fn false_positive_rate(&self) -> f64 {
+        return 1.0 - self.tables.iter().map(|table| 1.0 - table.false_positive_rate()).product::<f64>();
+    }
+}
+
+impl<H: SerializableHasher> SerializableFilter for DynamicCuckooFilter<H> {
+    const KIND: FilterKind = FilterKind::DynamicCuckoo;
+    const HASHER: HasherKind = H::KIND;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_u64(self.table_capacity as u64);
+        enc.put_u64(self.false_positive_rate.to_bits());
+        enc.put_u64(self.tables.len() as u64);
+        for table in &self.tables {
+            table.encode(enc);
+        }
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let table_capacity = dec.get_usize()?;
+        let false_positive_rate = f64::from_bits(dec.get_u64()?);
+        let num_tables = dec.get_usize()?;
+        ensure(table_capacity > 0, "table capacity is zero")?;
+        ensure(false_positive_rate > 0.0 && false_positive_rate < 1.0, "false positive rate out of range")?;
+        ensure(num_tables > 0, "no tables")?;
+        let mut tables: Vec<CuckooFilter<H>> = Vec::new();
+        for _ in 0..num_tables {
+            let table = CuckooFilter::decode(dec)?;
+            ensure(table.seed() == seed, "table built from a different seed")?;
+            ensure(tables.first().is_none_or(|first| first.slots() == table.slots()
+                && first.fingerprint_bits() == table.fingerprint_bits()), "tables differ in size")?;
+            tables.push(table);
+        }
+        return Ok(DynamicCuckooFilter {
+            tables,
+            table_capacity,
+            false_positive_rate,
+            seed,
+        });
+    }
+}
@@ -0,0 +1,234 @@
+/*
+Common interface shared by every filter in the crate.
+Filters are split by what they support: every filter answers membership queries, dynamic filters
+can have keys added after construction, some of those can also remove keys, and static filters
+are built once from the full key set.
+Every filter can report how full it is through FilterStats, and bit array filters built with the same parameters
+and seed can also be merged: the union of two such filters is
+exactly the filter of the union of their keys.
+Keys are u64s. Any other Hash key, or a byte string, can be used through the provided methods, which reduce it to
+a u64 with hasher::reduce_key or hasher::reduce_bytes first. A u64 passed to those comes out unchanged.
+ */
+
+use std::fmt;
+use std::hash::Hash;
+use crate::hasher::{reduce_bytes, reduce_key};
+
+pub trait Filter {
+    // true if the key may be in the set. false means the key is definitely not in the set.
+    fn member(&self, key: u64) -> bool;
+
+    // member for every key, written to the same position in out. filters that can overlap the cache misses of
+    // several keys override this to hash a batch of keys first, prefetch what they touch, and only then read it.
+    fn member_batch(&self, keys: &[u64], out: &mut [bool]) {
+        assert_eq!(keys.len(), out.len(), "member_batch needs one output per key");
+        for i in 0..keys.len() {
+            out[i] = self.member(keys[i]);
+        }
+    }
+
+    fn contains<K: Hash + ?Sized>(&self, key: &K) -> bool where Self: Sized {
+        return self.member(reduce_key(key));
+    }
+
+    fn contains_bytes(&self, key: &[u8]) -> bool {
+        return self.member(reduce_bytes(key));
+    }
+}
+
+pub trait DynamicFilter: Filter {
+    // returns false if the key could not be stored, e.g. a cuckoo filter that ran out of kicks.
+    fn insert(&mut self, key: u64) -> bool;
+
+    fn insert_key<K: Hash + ?Sized>(&mut self, key: &K) -> bool where Self: Sized {
+        return self.insert(reduce_key(key));
+    }
+
+    fn insert_bytes(&mut self, key: &[u8]) -> bool {
+        return self.insert(reduce_bytes(key));
+    }
+}
+
+pub trait DeletableFilter: DynamicFilter {
+    // returns false if the key was not found, in which case the filter is left unchanged.
+    fn delete(&mut self, key: u64) -> bool;
+
+    fn delete_key<K: Hash + ?Sized>(&mut self, key: &K) -> bool where Self: Sized {
+        return self.delete(reduce_key(key));
+    }
+
+    fn delete_bytes(&mut self, key: &[u8]) -> bool {
+        return self.delete(reduce_bytes(key));
+    }
+}
+
+pub trait StaticFilter: Filter {
+    // build the filter from the complete set of keys. keys must not contain duplicates.
+    fn from_keys(keys: &[u64]) -> Self where Self: Sized;
+
+    fn from_hashed_keys<K: Hash>(keys: &[K]) -> Self where Self: Sized {
+        let reduced: Vec<u64> = keys.iter().map(reduce_key).collect();
+        return Self::from_keys(&reduced);
+    }
+
+    fn from_byte_keys<B: AsRef<[u8]>>(keys: &[B]) -> Self where Self: Sized {
+        let reduced: Vec<u64> = keys.iter().map(|key| reduce_bytes(key.as_ref())).collect();
+        return Self::from_keys(&reduced);
+    }
+}
+
+pub trait MergeableFilter: Filter + Sized {
+    // afterwards the filter holds the keys of both filters, as if they had all been inserted into it.
+    fn union_with(&mut self, other: &Self) -> Result<(), MergeError>;
+
+    // afterwards the filter holds the keys common to both filters. keys in only one of them may still be members,
+    // so the false positive rate is at least that of either filter, not of the intersection itself.
+    fn intersect_with(&mut self, other: &Self) -> Result<(), MergeError>;
+
+    // the number of distinct keys inserted, estimated from how many bits are set (Swamidass and Baldi, 2007).
+    // once every bit is set the count can no longer be told apart, and the estimate stops growing.
+    fn estimate_cardinality(&self) -> f64;
+
+    fn union(&self, other: &Self) -> Result<Self, MergeError> where Self: Clone {
+        let mut merged = self.clone();
+        merged.union_with(other)?;
+        return Ok(merged);
+    }
+
+    fn intersect(&self, other: &Self) -> Result<Self, MergeError> where Self: Clone {
+        let mut merged = self.clone();
+        merged.intersect_with(other)?;
+        return Ok(merged);
+    }
+
+    // |A n B| / |A u B| of the two key sets. the union is estimated from the merged bits, and the intersection from
+    // |A| + |B| - |A u B|, which is more accurate than estimating it from the anded bits.
+    fn estimate_jaccard(&self, other: &Self) -> Result<f64, MergeError> where Self: Clone {
+        let union = self.union(other)?.estimate_cardinality();
+        if union == 0.0 {
+            // two empty sets.
+            return Ok(1.0);
+        }
+        let intersection = self.estimate_cardinality() + other.estimate_cardinality() - union;
+        return Ok((intersection / union).clamp(0.0, 1.0));
+    }
+}
+
+// why two filters could not be merged. only filters built with the same parameters and seed set the same bits
+// for the same key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeError {
+    SeedMismatch { left: u64, right: u64 },
+    // the filters were sized differently, e.g. for a different number of keys or false positive rate.
+    ParameterMismatch { parameter: &'static str, left: u64, right: u64 },
+}
+
+impl MergeError {
+    // the first difference between two filters, or Ok if there is none.
+    pub(crate) fn check(seeds: (u64, u64), parameters: &[(&'static str, u64, u64)]) -> Result<(), MergeError> {
+        if seeds.0 != seeds.1 {
+            return Err(MergeError::SeedMismatch { left: seeds.0, right: seeds.1 });
+        }
+        for (parameter, left, right) in parameters {
+            if left != right {
+                return Err(MergeError::ParameterMismatch { parameter, left: *left, right: *right });
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::SeedMismatch { left, right } => write!(f, "filters were built with different seeds, {} and {}", left, right),
+            MergeError::ParameterMismatch { parameter, left, right } => {
+                write!(f, "filters differ in {}, {} and {}", parameter, left, right)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+// why a counting filter refused to change its counters. the filter is left as it was.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CounterError {
+    // a counter of the key is at its maximum, and adding to it would lose count of the keys it stands for.
+    Overflow { index: u64, max: u64 },
+    // a counter of the key would go below zero, which would make another key a false negative.
+    Underflow { index: u64 },
+    // the key is not in the filter, so deleting it could only take away from the counters of other keys.
+    NotMember,
+}
+
+impl fmt::Display for CounterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CounterError::Overflow { index, max } => write!(f, "counter {} is already at its maximum of {}", index, max),
+            CounterError::Underflow { index } => write!(f, "counter {} would go below zero", index),
+            CounterError::NotMember => write!(f, "the key is not in the filter"),
+        }
+    }
+}
+
+impl std::error::Error for CounterError {}
+
+// the number of keys that would set `set` of `bits` bits, with `k` bits per key.
+// all bits set is counted as all but one, which would otherwise give infinity.
+pub(crate) fn swamidass_baldi(set: u64, bits: u64, k: u64) -> f64 {
+    if set == 0 {
+        return 0.0;
+    }
+    let (set, bits, k) = (set.min(bits - 1) as f64, bits as f64, k as f64);
+    return -(bits / k) * (1.0 - set / bits).ln();
+}
+
+// how full a filter is. everything is worked out from what the filter holds now, so it follows inserts and deletes.
+pub trait FilterStats: Filter {
+    // keys held. exact for filters that keep a fingerprint or counter per key, estimated from the bits set for
+    // Bloom filters, and from the size of the table for static filters.
+    fn len(&self) -> u64;
+
+    // keys the filter is sized for. for a Bloom filter, the keys at which half of its bits are expected to be set,
+    // beyond which each key costs more in false positives than the last.
+    fn capacity(&self) -> u64;
+
+    // places a key can be stored: bits in a Bloom filter, counters in a counting filter, fingerprint slots otherwise.
+    fn slots(&self) -> u64;
+
+    // slots in use: bits set in a Bloom filter, counters that are not zero, or slots holding a fingerprint.
+    fn occupied(&self) -> u64;
+
+    fn size_in_bits(&self) -> usize;
+
+    // the false positive rate the filter has now, given how full it is, rather than the rate it was sized for.
+    fn false_positive_rate(&self) -> f64;
+
+    fn is_empty(&self) -> bool {
+        return self.len() == 0;
+    }
+
+    fn load_factor(&self) -> f64 {
+        return self.len() as f64 / self.capacity() as f64;
+    }
+
+    fn fill_ratio(&self) -> f64 {
+        return self.occupied() as f64 / self.slots() as f64;
+    }
+
+    // infinite while the filter is empty.
+    fn bits_per_key(&self) -> f64 {
+        return self.size_in_bits() as f64 / self.len() as f64;
+    }
+}
+
+// the keys a Bloom filter of `bits` bits and `k` bits per key holds when half of its bits are set.
+pub(crate) fn half_full(bits: u64, k: u64) -> u64 {
+    return (bits as f64 * std::f64::consts::LN_2 / k.max(1) as f64) as u64;
+}
+
+// the chance that a key not in the filter matches one of `compared` fingerprints of `bits` bits.
+pub(crate) fn fingerprint_false_positive_rate(compared: f64, bits: u32) -> f64 {
+    return 1.0 - (1.0 - 0.5f64.powi(bits as i32)).powf(compared);
+}
@@ -0,0 +1,315 @@
+use std::ops::BitXor;
+
+use crate::hasher::MultiplyShift;
+use crate::filter::Filter;
+use crate::serialize::{check_binary_fuse, check_file, check_xor, file_body, Decoder, FilterKind, HasherKind, SerializableHasher, SerializeError};
+
+/*
+Read only views of the static filters that query a serialized filter in place. The fingerprints are borrowed
+straight out of the bytes, e.g. a memory mapped file, and only the few hash functions are decoded, so any number
+of processes can share the one page cache copy of a filter.
+
+The arrays of the format are 8 byte aligned relative to the start of the file, so the bytes handed to a view have
+to start on an 8 byte boundary too. mmap always gives that. A view answers exactly like the filter it was written
+from.
+ */
+
+// the fingerprint widths a view can borrow.
+pub trait Fingerprint: bytemuck::Pod + Eq + BitXor<Output = Self> {
+    const THREE_WISE: FilterKind;
+    const FOUR_WISE: FilterKind;
+
+    // the fingerprint of a key, truncated from its hash the same way the owning filters do it.
+    fn from_hash(hash: u32) -> Self;
+
+    // the stored value, which is little endian on disk.
+    fn to_native(self) -> Self;
+}
+
+// the widths that xor filters come in.
+pub trait XorFingerprint: Fingerprint {
+    const XOR: FilterKind;
+}
+
+impl Fingerprint for u8 {
+    const THREE_WISE: FilterKind = FilterKind::ThreeWiseBinaryFuse8;
+    const FOUR_WISE: FilterKind = FilterKind::FourWiseBinaryFuse8;
+
+    fn from_hash(hash: u32) -> Self {
+        return hash as u8;
+    }
+
+    fn to_native(self) -> Self {
+        return self;
+    }
+}
+
+impl Fingerprint for u16 {
+    const THREE_WISE: FilterKind = FilterKind::ThreeWiseBinaryFuse16;
+    const FOUR_WISE: FilterKind = FilterKind::FourWiseBinaryFuse16;
+
+    fn from_hash(hash: u32) -> Self {
+        return hash as u16;
+    }
+
+    fn to_native(self) -> Self {
+        return u16::from_le(self);
+    }
+}
+
+impl Fingerprint for u32 {
+    const THREE_WISE: FilterKind = FilterKind::ThreeWiseBinaryFuse32;
+    const FOUR_WISE: FilterKind = FilterKind::FourWiseBinaryFuse32;
+
+    fn from_hash(hash: u32) -> Self {
+        return hash;
+    }
+
+    fn to_native(self) -> Self {
+        return u32::from_le(self);
+    }
+}
+
+impl XorFingerprint for u8 {
+    const XOR: FilterKind = FilterKind::Xor8;
+}
+
+impl XorFingerprint for u32 {
+    const XOR: FilterKind = FilterKind::Xor32;
+}
+
+// the body of a file, checked against its checksum unless the caller trusts it.
+fn body(bytes: &[u8], kind: FilterKind, hasher: HasherKind, verify: bool) -> Result<&[u8], SerializeError> {
+    if verify {
+        return check_file(bytes, kind, hasher);
+    }
+    return file_body(bytes, kind, hasher);
+}
+
+// borrow an array of fingerprints in place.
+fn fingerprints<'a, T: Fingerprint>(dec: &mut Decoder<'a>) -> Result<&'a [T], SerializeError> {
+    let (_, bytes) = dec.get_array_bytes(std::mem::size_of::<T>())?;
+    return bytemuck::try_cast_slice(bytes).map_err(|_| SerializeError::Misaligned);
+}
+
+/*
+Xor filters.
+ */
+
+pub struct XorFilterRef<'a, T, H = MultiplyShift> {
+    fingerprints: &'a [T],
+    hashes: Vec<H>,
+    seed: u64,
+    size: u64,
+    l: u32,
+}
+
+pub type XorFilter8Ref<'a, H = MultiplyShift> = XorFilterRef<'a, u8, H>;
+pub type XorFilter32Ref<'a, H = MultiplyShift> = XorFilterRef<'a, u32, H>;
+
+impl<'a, T: XorFingerprint, H: SerializableHasher> XorFilterRef<'a, T, H> {
+    // a view of a file written by XorFilter8 or XorFilter32. checks everything, including the checksum.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, SerializeError> {
+        return Self::parse(bytes, true);
+    }
+
+    // the same, but skips the checksum, which would otherwise read the whole file.
+    // the layout is still checked, so a bad file gives wrong answers rather than a crash.
+    pub fn from_bytes_skip_checksum(bytes: &'a [u8]) -> Result<Self, SerializeError> {
+        return Self::parse(bytes, false);
+    }
+
+    fn parse(bytes: &'a [u8], verify: bool) -> Result<Self, SerializeError> {
+        let mut dec = Decoder::new(body(bytes, T::XOR, H::KIND, verify)?);
+        let seed = dec.get_u64()?;
+        let size = dec.get_u64()?;
+        let l = dec.get_u32()?;
+        let hashes = dec.get_hashers(Some(3))?;
+        let fingerprints = fingerprints(&mut dec)?;
+        dec.finish()?;
+        check_xor(size, l, fingerprints.len())?;
+        return Ok(XorFilterRef {
+            fingerprints,
+            hashes,
+            seed,
+            size,
+            l,
+        });
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    fn fingerprint(&self, key: u64) -> T {
+        return T::from_hash(self.hashes[0].hash_bits(key, self.l));
+    }
+
+    fn hash(&self, i: usize, key: u64) -> usize {
+        let bound = self.size / 3;
+        let res = self.hashes[i].hash_bits(key, self.l) % bound as u32;
+        return (i as u64 * bound + res as u64) as usize;
+    }
+}
+
+impl<'a, T: XorFingerprint, H: SerializableHasher> Filter for XorFilterRef<'a, T, H> {
+    fn member(&self, key: u64) -> bool {
+        let f = self.fingerprint(key);
+        let stored = self.fingerprints[self.hash(0, key)].to_native()
+            ^ self.fingerprints[self.hash(1, key)].to_native()
+            ^ self.fingerprints[self.hash(2, key)].to_native();
+        return f == stored;
+    }
+}
+
+/*
+Binary fuse filters. BinaryFuseRef reads the three wise filters and FourWiseBinaryFuseRef the four wise ones.
+ */
+
+pub struct BinaryFuseRef<'a, T, H = MultiplyShift> {
+    fingerprints: &'a [T],
+    hashes: Vec<H>,
+    seed: u64,
+    segment_length: u32,
+    l: u32,
+    log_segment: u32,
+    num_segments: u64,
+}
+
+pub type BinaryFuse8Ref<'a, H = MultiplyShift> = BinaryFuseRef<'a, u8, H>;
+pub type BinaryFuse16Ref<'a, H = MultiplyShift> = BinaryFuseRef<'a, u16, H>;
+pub type BinaryFuse32Ref<'a, H = MultiplyShift> = BinaryFuseRef<'a, u32, H>;
+
+pub struct FourWiseBinaryFuseRef<'a, T, H = MultiplyShift> {
+    fingerprints: &'a [T],
+    hashes: Vec<H>,
+    seed: u64,
+    segment_length: u32,
+    l: u32,
+    log_segment: u32,
+    num_segments: u64,
+}
+
+pub type FourWiseBinaryFuse8Ref<'a, H = MultiplyShift> = FourWiseBinaryFuseRef<'a, u8, H>;
+pub type FourWiseBinaryFuse16Ref<'a, H = MultiplyShift> = FourWiseBinaryFuseRef<'a, u16, H>;
+pub type FourWiseBinaryFuse32Ref<'a, H = MultiplyShift> = FourWiseBinaryFuseRef<'a, u32, H>;
+
+impl<'a, T: Fingerprint, H: SerializableHasher> BinaryFuseRef<'a, T, H> {
+    // a view of a file written by one of the ThreeWiseBinaryFuseFilters. checks everything, including the checksum.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, SerializeError> {
+        return Self::parse(bytes, true);
+    }
+
+    // the same, but skips the checksum, which would otherwise read the whole file.
+    pub fn from_bytes_skip_checksum(bytes: &'a [u8]) -> Result<Self, SerializeError> {
+        return Self::parse(bytes, false);
+    }
+
+    fn parse(bytes: &'a [u8], verify: bool) -> Result<Self, SerializeError> {
+        let mut dec = Decoder::new(body(bytes, T::THREE_WISE, H::KIND, verify)?);
+        let seed = dec.get_u64()?;
+        let size = dec.get_u64()?;
+        let segment_length = dec.get_u32()?;
+        let l = dec.get_u32()?;
+        let log_segment = dec.get_u32()?;
+        let num_segments = dec.get_u64()?;
+        let hashes = dec.get_hashers(Some(4))?;
+        let fingerprints = fingerprints(&mut dec)?;
+        dec.finish()?;
+        check_binary_fuse(3, size, segment_length, l, log_segment, num_segments, fingerprints.len())?;
+        return Ok(BinaryFuseRef {
+            fingerprints,
+            hashes,
+            seed,
+            segment_length,
+            l,
+            log_segment,
+            num_segments,
+        });
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    fn segmented_hash(&self, key: u64) -> (u32, u32, u32) {
+        let s_length = self.segment_length;
+        let segment_id = self.hashes[3].hash_bits(key, self.l) % (self.num_segments - 2) as u32;
+        let h0 = self.hashes[0].hash_bits(key, self.log_segment) + segment_id * s_length;
+        let h1 = self.hashes[1].hash_bits(key, self.log_segment) + (segment_id + 1) * s_length;
+        let h2 = self.hashes[2].hash_bits(key, self.log_segment) + (segment_id + 2) * s_length;
+        return (h0, h1, h2);
+    }
+}
+
+impl<'a, T: Fingerprint, H: SerializableHasher> Filter for BinaryFuseRef<'a, T, H> {
+    fn member(&self, key: u64) -> bool {
+        let (h0, h1, h2) = self.segmented_hash(key);
+        let f = T::from_hash(self.hashes[0].hash_bits(key, self.l));
+        let stored = self.fingerprints[h0 as usize].to_native()
+            ^ self.fingerprints[h1 as usize].to_native()
+            ^ self.fingerprints[h2 as usize].to_native();
+        return f == stored;
+    }
+}
+
+impl<'a, T: Fingerprint, H: SerializableHasher> FourWiseBinaryFuseRef<'a, T, H> {
+    // a view of a file written by one of the FourWiseBinaryFuseFilters. checks everything, including the checksum.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, SerializeError> {
+        return Self::parse(bytes, true);
+    }
+
+    // the same, but skips the checksum, which would otherwise read the whole file.
+    pub fn from_bytes_skip_checksum(bytes: &'a [u8]) -> Result<Self, SerializeError> {
+        return Self::parse(bytes, false);
+    }
+
+    fn parse(bytes: &'a [u8], verify: bool) -> Result<Self, SerializeError> {
+        let mut dec = Decoder::new(body(bytes, T::FOUR_WISE, H::KIND, verify)?);
+        let seed = dec.get_u64()?;
+        let size = dec.get_u64()?;
+        let segment_length = dec.get_u32()?;
+        let l = dec.get_u32()?;
+        let log_segment = dec.get_u32()?;
+        let num_segments = dec.get_u64()?;
+        let hashes = dec.get_hashers(Some(5))?;
+        let fingerprints = fingerprints(&mut dec)?;
+        dec.finish()?;
+        check_binary_fuse(4, size, segment_length, l, log_segment, num_segments, fingerprints.len())?;
+        return Ok(FourWiseBinaryFuseRef {
+            fingerprints,
+            hashes,
+            seed,
+            segment_length,
+            l,
+            log_segment,
+            num_segments,
+        });
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    fn segmented_hash(&self, key: u64) -> (u32, u32, u32, u32) {
+        let s_length = self.segment_length;
+        let segment_id = self.hashes[4].hash_bits(key, self.l) % (self.num_segments - 3) as u32;
+        let h0 = self.hashes[0].hash_bits(key, self.log_segment) + segment_id * s_length;
+        let h1 = self.hashes[1].hash_bits(key, self.log_segment) + (segment_id + 1) * s_length;
+        let h2 = self.hashes[2].hash_bits(key, self.log_segment) + (segment_id + 2) * s_length;
+        let h3 = self.hashes[3].hash_bits(key, self.l) % s_length + (segment_id + 3) * s_length;
+        return (h0, h1, h2, h3);
+    }
+}
+
+impl<'a, T: Fingerprint, H: SerializableHasher> Filter for FourWiseBinaryFuseRef<'a, T, H> {
+    fn member(&self, key: u64) -> bool {
+        let (h0, h1, h2, h3) = self.segmented_hash(key);
+        let f = T::from_hash(self.hashes[0].hash_bits(key, self.l));
+        let stored = self.fingerprints[h0 as usize].to_native()
+            ^ self.fingerprints[h1 as usize].to_native()
+            ^ self.fingerprints[h2 as usize].to_native()
+            ^ self.fingerprints[h3 as usize].to_native();
+        return f == stored;
+    }
+}
@@ -1,15 +1,20 @@
-use rand::Rng;
-use std::collections::{HashSet, VecDeque};
+use std::collections::VecDeque;
 
 
-#[path = "utils.rs"]
-mod utils;
-use utils::hash;
+use crate::utils;
 use utils::log_base;
 
-pub struct FourWiseBinaryFuseFilter16 {
+use rand_chacha::ChaCha8Rng;
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::filter::{Filter, FilterStats, StaticFilter};
+use crate::serialize::{check_binary_fuse, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FourWiseBinaryFuseFilter16<H = MultiplyShift> {
     fingerprints: Vec<u16>,
-    hashes: Vec<(u64,u64,u64)>,
+    hashes: Vec<H>,
+    seed: u64,
+    #[cfg_attr(feature = "serde", serde(skip))]
     sigma: Vec<(u64, usize)>,
     size: u64,
     segment_length: u32,
@@ -18,10 +23,24 @@ pub struct FourWiseBinaryFuseFilter16 {
     num_segments: u64
 }
 impl FourWiseBinaryFuseFilter16 {
-    pub fn new(keys: &Vec<u64>) -> FourWiseBinaryFuseFilter16 {
+    pub fn new(keys: &[u64]) -> FourWiseBinaryFuseFilter16 {
+        return Self::with_hasher(keys);
+    }
+}
+
+impl<H: KeyHasher> FourWiseBinaryFuseFilter16<H> {
+    // same as new, but draws the hash functions from H instead of multiply-shift.
+    pub fn with_hasher(keys: &[u64]) -> Self {
+        return Self::with_seed(keys, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    pub fn with_seed(keys: &[u64], seed: u64) -> Self {
+        let mut rng = utils::seeded_rng(seed);
         let mut filter = FourWiseBinaryFuseFilter16 {
             fingerprints: vec![],
             hashes: vec![],
+            seed,
             sigma: vec![],
             size: 0,
             segment_length: 0,
@@ -36,31 +55,26 @@ impl FourWiseBinaryFuseFilter16 {
         filter.segment_length = 2u32.pow(exp);
         filter.log_segment = log_base(filter.segment_length as f64, 2f64) as u32;
         filter.num_segments = filter.size / filter.segment_length as u64;
-        if (filter.num_segments < 4 ) {
+        if filter.num_segments < 4  {
             filter.size = (filter.segment_length * 4) as u64;
             filter.num_segments = 4;
             filter.l =  log_base(filter.size as f64, 2f64) as u32;
         }
-        filter.construct(keys);
+        filter.construct(keys, &mut rng);
         return filter;
     }
-    pub fn member(&self, k: u64) -> bool {
-        let (h0,h1,h2,h3) = self.segmented_hash(k);
-        let f = self.fingerprint(k);
-        return f == (self.fingerprints[h0 as usize] ^ self.fingerprints[h1 as usize] ^ self.fingerprints[h2 as usize] ^
-        self.fingerprints[h3 as usize]);
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
     }
-    fn construct(&mut self, keys: &Vec<u64>) {
+
+    fn construct(&mut self, keys: &[u64], rng: &mut ChaCha8Rng) {
         let mut finished = false;
         while !finished {
-            let mut rng = rand::thread_rng();
             let mut hash_functions = Vec::new();
 
             for _ in 0..=4 {
-                let a1: u64 = rng.gen_range(1..=u64::MAX );
-                let a2: u64 = rng.gen_range(1..=u64::MAX);
-                let b: u64 = rng.gen_range(1..=u64::MAX);
-                hash_functions.push((a1,a2,b));
+                hash_functions.push(H::from_rng(rng));
             }
             self.hashes = hash_functions;
             if self.mapping(keys) {
@@ -74,7 +88,7 @@ impl FourWiseBinaryFuseFilter16 {
             }
         }
     }
-    fn mapping(&mut self, keys: &Vec<u64>) -> bool {
+    fn mapping(&mut self, keys: &[u64]) -> bool {
         let c: u64 = self.size;
         let mut h: Vec<(u64,usize)> = vec![(0,0); c as usize];
         let mut sorted : Vec<Vec<u64>> = vec![vec![]; self.num_segments as usize];
@@ -152,7 +166,7 @@ impl FourWiseBinaryFuseFilter16 {
     }
 
     pub(crate) fn fingerprint(&self, key: u64) -> u16 {
-        return hash(key,self.l, self.hashes[0].0, self.hashes[0].1, self.hashes[0].2) as u16;
+        return self.hashes[0].hash_bits(key, self.l) as u16;
     }
 
     fn segmented_hash(&self, key: u64) -> (u32, u32, u32,u32) {
@@ -160,21 +174,125 @@ impl FourWiseBinaryFuseFilter16 {
         // hash in segment range. using the three hash functions.
         let s_length = self.segment_length;
         // hash this to the correct range. then complete.
-        let segment_id = hash(key,self.l, self.hashes[4].0, self.hashes[4].1, self.hashes[4].2)
+        let segment_id = self.hashes[4].hash_bits(key, self.l)
             % (self.num_segments-3) as u32;
-        let h0 = (hash(key,self.log_segment, self.hashes[0].0, self.hashes[0].1, self.hashes[0].2) ) +
+        let h0 = (self.hashes[0].hash_bits(key, self.log_segment) ) +
             (segment_id * s_length);
-        let h1 = (hash(key,self.log_segment, self.hashes[1].0, self.hashes[1].1, self.hashes[1].2)) +
+        let h1 = (self.hashes[1].hash_bits(key, self.log_segment)) +
             ((segment_id+1) * s_length);
-        let h2 = (hash(key,self.log_segment, self.hashes[2].0, self.hashes[2].1, self.hashes[2].2)) +
+        let h2 = (self.hashes[2].hash_bits(key, self.log_segment)) +
             ((segment_id+2) * s_length);
 
-        let h3 = (hash(key,self.l, self.hashes[3].0, self.hashes[3].1, self.hashes[3].2) % s_length) +
+        let h3 = (self.hashes[3].hash_bits(key, self.l) % s_length) +
             ((segment_id+3) * s_length);
         return (h0,h1,h2,h3);
     }
 
-    fn get_segment(&self, key: u64) -> (u32) {
-        return hash(key,self.l, self.hashes[4].0, self.hashes[4].1, self.hashes[4].2) % (self.num_segments-3) as u32;
+    fn get_segment(&self, key: u64) -> u32  {
+        return self.hashes[4].hash_bits(key, self.l) % (self.num_segments-3) as u32;
+    }
+}
+
+impl<H: KeyHasher> Filter for FourWiseBinaryFuseFilter16<H> {
+    fn member(&self, k: u64) -> bool {
+        let (h0,h1,h2,h3) = self.segmented_hash(k);
+        let f = self.fingerprint(k);
+        return f == (self.fingerprints[h0 as usize] ^ self.fingerprints[h1 as usize] ^ self.fingerprints[h2 as usize] ^
+        self.fingerprints[h3 as usize]);
+    }
+
+    // hash a batch of keys and prefetch their four fingerprints, then check them.
+    fn member_batch(&self, keys: &[u64], out: &mut [bool]) {
+        assert_eq!(keys.len(), out.len(), "member_batch needs one output per key");
+        let mut slots = [(0u32, 0u32, 0u32, 0u32); utils::BATCH];
+        for (keys, out) in keys.chunks(utils::BATCH).zip(out.chunks_mut(utils::BATCH)) {
+            for i in 0..keys.len() {
+                slots[i] = self.segmented_hash(keys[i]);
+                utils::prefetch(&self.fingerprints[slots[i].0 as usize]);
+                utils::prefetch(&self.fingerprints[slots[i].1 as usize]);
+                utils::prefetch(&self.fingerprints[slots[i].2 as usize]);
+                utils::prefetch(&self.fingerprints[slots[i].3 as usize]);
+            }
+            for i in 0..keys.len() {
+                let (h0, h1, h2, h3) = slots[i];
+                out[i] = self.fingerprint(keys[i]) == (self.fingerprints[h0 as usize] ^ self.fingerprints[h1 as usize] ^
+                    self.fingerprints[h2 as usize] ^ self.fingerprints[h3 as usize]);
+            }
+        }
+    }
+}
+
+impl<H: KeyHasher> StaticFilter for FourWiseBinaryFuseFilter16<H> {
+    fn from_keys(keys: &[u64]) -> Self {
+        return Self::with_hasher(keys);
+    }
+}
+
+impl<H: SerializableHasher> SerializableFilter for FourWiseBinaryFuseFilter16<H> {
+    const KIND: FilterKind = FilterKind::FourWiseBinaryFuse16;
+    const HASHER: HasherKind = H::KIND;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_u64(self.size);
+        enc.put_u64(self.segment_length as u64);
+        enc.put_u64(self.l as u64);
+        enc.put_u64(self.log_segment as u64);
+        enc.put_u64(self.num_segments);
+        enc.put_hashers(&self.hashes);
+        enc.put_u16s(&self.fingerprints);
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let size = dec.get_u64()?;
+        let segment_length = dec.get_u32()?;
+        let l = dec.get_u32()?;
+        let log_segment = dec.get_u32()?;
+        let num_segments = dec.get_u64()?;
+        let hashes = dec.get_hashers(Some(5))?;
+        let fingerprints = dec.get_u16s()?;
+        check_binary_fuse(4, size, segment_length, l, log_segment, num_segments, fingerprints.len())?;
+        return Ok(FourWiseBinaryFuseFilter16 {
+            fingerprints,
+            hashes,
+            seed,
+            sigma: vec![],
+            size,
+            segment_length,
+            l,
+            log_segment,
+            num_segments,
+        });
+    }
+}
+
+impl<H: KeyHasher> FilterStats for FourWiseBinaryFuseFilter16<H> {
+    // the table was sized to 1.075n + 32 slots for n keys, so this is the n it was built from, give or take
+    // rounding. a filter too small for the minimum number of segments was given more slots, and overcounts.
+    fn len(&self) -> u64 {
+        return (self.size.saturating_sub(32) as f64 / 1.075).round() as u64;
+    }
+
+    fn capacity(&self) -> u64 {
+        return self.len();
+    }
+
+    fn slots(&self) -> u64 {
+        return self.size;
+    }
+
+    // building assigns every key a slot of its own.
+    fn occupied(&self) -> u64 {
+        return self.len();
+    }
+
+    fn size_in_bits(&self) -> usize {
+        return self.fingerprints.len() * 16;
     }
-}
\ No newline at end of file
+
+    // fingerprints are l bit hashes, cut to 16 bits.
+    fn false_positive_rate(&self) -> f64 {
+        return 0.5f64.powi(self.l.min(16) as i32);
+    }
+}
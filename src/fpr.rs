@@ -1,310 +1,201 @@
-use std::collections::{binary_heap, HashSet};
-use rand::{random, Rng};
-use crate::blockedbloomfilter::BlockedBloomFilter;
-use crate::bloomfilter::BloomFilter;
-use crate::countingbloomfilter::CountingBloomFilter;
-use crate::cuckoofilter::CuckooFilter;
-use crate::registeralignedbloomfilter::RegisterAlignedBloomFilter;
-use crate::{fourwisebinaryfusefilter8, threewisebinaryfusefilter32, threewisebinaryfusefilter8, xorfilter, XorFilter8};
-use crate::mortonfilter::MortonFilter;
-use crate::quotientfilter::QuotientFilter;
-use crate::registeralignedlarger::RegisterAlignedBloomFilterLarger;
-
-pub(crate) fn bloom_filter_fpr(size: u64, fpr:f64, keys: &Vec<u64>, lookup_keys: &Vec<u64>) {
-    let mut bloom_filter = BloomFilter::new(size, fpr);
-    for key in keys {
-        bloom_filter.insert(*key);
-    }
-    let mut count: f64 = 0f64;
+use std::collections::HashSet;
+use rand::Rng;
+use crate::binaryfuse::{FourWiseBinaryFuseFilter8, ThreeWiseBinaryFuseFilter32, ThreeWiseBinaryFuseFilter8};
+use chrono::{DateTime, Duration, Utc};
+use crate::bloom::{AgePartitionedBloomFilter, BlockedBloomFilter, BloomFilter, CountingBloomFilter, DLeftCountingFilter,
+                   OneHashingBloomFilter, ParquetBloomFilter, PartitionedBloomFilter, RegisterAlignedBloomFilter,
+                   RegisterAlignedBloomFilterLarger, ScalableBloomFilter, SimdBlockedBloomFilter, StableBloomFilter, Window};
+use crate::cuckoo::{AdaptiveCuckooFilter, CountingCuckooFilter, CuckooFilter, DynamicCuckooFilter};
+use crate::filter::{DynamicFilter, Filter, FilterStats, StaticFilter};
+use crate::hasher::KeyHasher;
+use crate::morton::MortonFilter;
+use crate::quotient::QuotientFilter;
+use crate::ribbon::{BumpedRibbonFilter, RibbonFilter};
+use crate::utils;
+use crate::xor::{XorFilter32, XorFilter8};
+
+// fraction of the lookup keys that the filter claims to contain.
+// lookup keys are expected to be disjoint from the inserted keys, so every hit is a false positive.
+pub fn false_positive_rate<F: Filter + ?Sized>(filter: &F, lookup_keys: &[u64]) -> f64 {
     let mut fp: f64 = 0f64;
-    for i in lookup_keys {
-        count += 1.0f64;
-        if (bloom_filter.member(*i)) {
+    for key in lookup_keys {
+        if filter.member(*key) {
             fp += 1.0f64;
         }
     }
-    let fpr = fp/count;
-    println!("Bloom filter fpr: '{}'", fpr);
+    return fp / lookup_keys.len() as f64;
 }
 
-pub(crate) fn counting_bloom_filter_fpr(size: u64, fpr:f64, keys: &Vec<u64>, lookup_keys: &Vec<u64>) {
-    let mut counting_bloom_filter = CountingBloomFilter::new(size, fpr);
+// insert the keys into an empty dynamic filter and report its fpr.
+pub fn dynamic_filter_fpr<F: DynamicFilter>(name: &str, mut filter: F, keys: &[u64], lookup_keys: &[u64]) {
     for key in keys {
-        counting_bloom_filter.insert(*key);
-    }
-    let mut count: f64 = 0f64;
-    let mut fp: f64 = 0f64;
-    for i in lookup_keys {
-        count += 1.0f64;
-        if (counting_bloom_filter.member(*i)) {
-            fp += 1.0f64;
-        }
+        filter.insert(*key);
     }
-    let fpr = fp/count;
-    println!("Counting Bloom filter fpr: '{}'", fpr);
+    println!("{} fpr: '{}'", name, false_positive_rate(&filter, lookup_keys));
 }
 
-//TODO update params
-pub(crate) fn cuckoo_filter_fpr(size: u64, fpr:f64, keys: &Vec<u64>, lookup_keys: &Vec<u64>) {
-    let mut cuckoo_filter = CuckooFilter::new(size as usize, 1000, 8);
-    for key in keys {
-        cuckoo_filter.insert(*key);
-    }
-    let mut count: f64 = 0f64;
-
-    let mut sum = 0f64;
-    for j in 0..100 {
-        let mut fp: f64 = 0f64;
-        for i in lookup_keys {
-            count += 1.0f64;
-            if (cuckoo_filter.member(*i)) {
-                fp += 1.0f64;
-            }
-        }
-        sum += fp/count;
-    }
-    println!("Cuckoo filter fpr: '{}'", sum/100.0);
+// build a static filter from the keys and report its fpr.
+pub fn static_filter_fpr<F: StaticFilter>(name: &str, keys: &[u64], lookup_keys: &[u64]) {
+    let filter = F::from_keys(keys);
+    println!("{} fpr: '{}'", name, false_positive_rate(&filter, lookup_keys));
 }
 
-pub(crate) fn xor_filter_fpr(keys: &Vec<u64>, lookup_keys: &Vec<u64>) {
-    let mut sum: f64 = 0f64;
-    for i in 0..1 {
-        let mut xor_filter = xorfilter::XorFilter::new(keys.clone());
-        let mut count: f64 = 0f64;
-        let mut fp: f64 = 0f64;
-        for i in lookup_keys {
-            count += 1.0f64;
-            if (xor_filter.member(*i)) {
-                fp += 1.0f64;
-            }
-        }
-        sum += fp/count;
-    }
-
-    println!("Xor filter fpr: '{}'", sum);
+// report the fpr of a static filter that has already been built.
+pub fn built_filter_fpr<F: Filter>(name: &str, filter: F, lookup_keys: &[u64]) {
+    println!("{} fpr: '{}'", name, false_positive_rate(&filter, lookup_keys));
 }
 
-pub(crate) fn xor_filter_8_fpr(keys: &Vec<u64>, lookup_keys: &Vec<u64>) {
-    let mut sum: f64 = 0f64;
-    for i in 0..1 {
-        let mut xor_filter = XorFilter8::XorFilter::new(keys.clone());
-        let mut count: f64 = 0f64;
-        let mut fp: f64 = 0f64;
-        for i in lookup_keys {
-            count += 1.0f64;
-            if (xor_filter.member(*i)) {
-                fp += 1.0f64;
-            }
-        }
-        sum += fp/count;
+// as dynamic_filter_fpr, for the adaptive cuckoo filter, whose key store is kept in a Vec here.
+pub fn adaptive_filter_fpr<H: KeyHasher>(name: &str, mut filter: AdaptiveCuckooFilter<H>, keys: &[u64], lookup_keys: &[u64]) {
+    let mut store = vec![0; filter.slots() as usize];
+    for key in keys {
+        filter.insert(*key, &mut store);
     }
-    println!("Xor filter 8 bit fpr: '{}'", sum);
+    println!("{} fpr: '{}'", name, false_positive_rate(&filter, lookup_keys));
 }
 
-
-pub(crate) fn binary_fuse_filter_fpr(keys: &Vec<u64>, lookup_keys: &Vec<u64>) {
-    let mut binary_fuse_filter = threewisebinaryfusefilter32::ThreeWiseBinaryFuseFilter32::new(keys.clone());
-    let mut count: f64 = 0f64;
-    let mut fp: f64 = 0f64;
-    for i in lookup_keys {
-        count += 1.0f64;
-        if (binary_fuse_filter.member(*i)) {
-            fp += 1.0f64;
-        }
-    }
-    let fpr = fp/count;
-    println!("Binary Fuse filter fpr: '{}'", fpr);
+// fpr of every filter over sequential keys, using hash functions drawn from H.
+// every filter is built from seed, so a run can be repeated exactly.
+pub fn run_fpr_tests<H: KeyHasher>(size: u64, seed: u64) {
+    println!("{} sequential", std::any::type_name::<H>());
+    println!("{}", size);
+    let keys: Vec<u64> = (0..=size).collect();
+    let lookup_keys: Vec<u64> = (size+1..(size as f64*4f64) as u64).collect();
+    let fpr = 0.01;
+
+    dynamic_filter_fpr("Bloom filter", BloomFilter::<H>::with_seed(size, fpr, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("Partitioned Bloom filter", PartitionedBloomFilter::<H>::with_seed(size, fpr, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("One-hashing Bloom filter", OneHashingBloomFilter::<H>::with_seed(size, fpr, seed), &keys, &lookup_keys);
+    // sized for a sixteenth of the keys, so that it has to grow.
+    dynamic_filter_fpr("Scalable Bloom filter", ScalableBloomFilter::<BloomFilter<H>>::with_seed((size / 16).max(1), fpr, seed), &keys, &lookup_keys);
+    // a window as long as the keys, so that none are forgotten.
+    dynamic_filter_fpr("Age-partitioned Bloom filter", AgePartitionedBloomFilter::<H>::with_seed(Window::Inserts(keys.len() as u64), fpr, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("Stable Bloom filter", StableBloomFilter::<H>::with_seed(Window::Inserts(keys.len() as u64), fpr, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("Counting Bloom filter", CountingBloomFilter::<H>::with_seed(size, fpr, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("d-left Counting Bloom filter", DLeftCountingFilter::<H>::with_seed(size, fpr, seed), &keys, &lookup_keys);
+    //TODO update params
+    dynamic_filter_fpr("Cuckoo filter", CuckooFilter::<H>::with_seed(size as usize, 1000, 8, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("Cuckoo filter (sized for fpr)", CuckooFilter::<H>::with_fpr_seed(size as usize, fpr, seed), &keys, &lookup_keys);
+    // sized for a sixteenth of the keys, so that it has to grow.
+    dynamic_filter_fpr("Dynamic Cuckoo filter", DynamicCuckooFilter::<H>::with_seed((size as usize / 16).max(1), fpr, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("Counting Cuckoo filter", CountingCuckooFilter::<H>::with_seed(size as usize, fpr, seed), &keys, &lookup_keys);
+    adaptive_filter_fpr("Adaptive Cuckoo filter", AdaptiveCuckooFilter::<H>::with_seed(size as usize, fpr, seed), &keys, &lookup_keys);
+    built_filter_fpr("Binary Fuse filter 8 bit", ThreeWiseBinaryFuseFilter8::<H>::with_seed(&keys, seed), &lookup_keys);
+    built_filter_fpr("Xor filter", XorFilter32::<H>::with_seed(&keys, seed), &lookup_keys);
+    built_filter_fpr("Xor filter 8 bit", XorFilter8::<H>::with_seed(&keys, seed), &lookup_keys);
+    built_filter_fpr("Ribbon filter 8 bit", RibbonFilter::<H>::with_seed(&keys, 64, 8, seed), &lookup_keys);
+    built_filter_fpr("Bumped Ribbon filter 8 bit", BumpedRibbonFilter::<H>::with_seed(&keys, 64, 8, seed), &lookup_keys);
+    built_filter_fpr("Binary Fuse filter", ThreeWiseBinaryFuseFilter32::<H>::with_seed(&keys, seed), &lookup_keys);
+    built_filter_fpr("Binary Fuse filter 4 wise 8 bit", FourWiseBinaryFuseFilter8::<H>::with_seed(&keys, seed), &lookup_keys);
+    dynamic_filter_fpr("Blocked Bloom filter", BlockedBloomFilter::<H>::with_seed(size, 512, fpr, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("SIMD Blocked Bloom filter", SimdBlockedBloomFilter::<H>::with_seed(size, fpr, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("Parquet Bloom filter", ParquetBloomFilter::new(size, fpr), &keys, &lookup_keys);
+    dynamic_filter_fpr("Register Aligned Bloom filter", RegisterAlignedBloomFilter::<H>::with_seed(size, 64, fpr, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("Morton filter", MortonFilter::<H>::with_seed(size, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("Quotient filter", QuotientFilter::<H>::with_seed(size, seed), &keys, &lookup_keys);
 }
 
-pub(crate) fn binary_fuse_filter_8_fpr(keys: &Vec<u64>, lookup_keys: &Vec<u64>) {
-    let mut binary_fuse_filter = threewisebinaryfusefilter8::ThreeWiseBinaryFuseFilter8::new(keys.clone());
-    let mut count: f64 = 0f64;
-    let mut fp: f64 = 0f64;
-    for i in lookup_keys {
-        count += 1.0f64;
-        if (binary_fuse_filter.member(*i)) {
-            fp += 1.0f64;
-        }
-    }
-    let fpr = fp/count;
-    println!("Binary Fuse filter 8 bit fpr: '{}'", fpr);
-}
+// as above, over random keys. the keys are drawn from seed as well.
+pub fn run_randomised_fpr_tests<H: KeyHasher>(size: u64, seed: u64) {
+    println!("{} random", std::any::type_name::<H>());
+    println!("{}", size);
+    let mut set_keys = HashSet::new();
+    let mut rng = utils::seeded_rng(seed);
 
-pub(crate) fn binary_fuse4_filter_8_fpr(keys: &Vec<u64>, lookup_keys: &Vec<u64>) {
-    let mut binary_fuse_filter = fourwisebinaryfusefilter8::FourWiseBinaryFuseFilter8::new(keys);
-    let mut count: f64 = 0f64;
-    let mut fp: f64 = 0f64;
-    for i in lookup_keys {
-        count += 1.0f64;
-        if (binary_fuse_filter.member(*i)) {
-            fp += 1.0f64;
+    // keys are kept in the order they were drawn, since iterating a HashSet is not repeatable.
+    let mut keys: Vec<u64> = Vec::new();
+    while keys.len() < size as usize {
+        let random_value: u64 = rng.gen_range(0..(size as f64*4f64) as u64);
+        if set_keys.insert(random_value) {
+            keys.push(random_value);
         }
     }
-    let fpr = fp/count;
-    println!("Binary Fuse filter 4 wise 8 bit fpr: '{}'", fpr);
-}
-
-
-pub(crate) fn blocked_bloom_filter_fpr(size: u64, fpr:f64, keys: &Vec<u64>, lookups : &Vec<u64>) {
-    let mut blocked_bloom_filter = BlockedBloomFilter::new(size, 512, fpr);
-    for key in keys {
-        blocked_bloom_filter.insert(*key);
-    }
-    let mut count: f64 = 0f64;
 
-    let mut sum = 0f64;
-    for j in 0..100 {
-        let mut fp: f64 = 0f64;
-        for i in lookups {
-            count += 1.0f64;
-            if (blocked_bloom_filter.member(*i)) {
-                fp += 1.0f64;
-            }
+    let mut lookup_set = HashSet::new();
+    let mut lookup_keys: Vec<u64> = Vec::new();
+    while lookup_keys.len() < size as usize {
+        let random_value: u64 = rng.gen_range(0..(size as f64*4f64) as u64);
+        if !set_keys.contains(&random_value) && lookup_set.insert(random_value) {
+            lookup_keys.push(random_value);
         }
-        sum += fp/count;
-    }
-    println!("Blocked Bloom filter fpr: '{}'", sum/100.0);
-}
-
-pub(crate) fn morton_filter_fpr(size: u64, fpr:f64, keys: &Vec<u64>, lookups : &Vec<u64>) {
-    let mut morton_filter = MortonFilter::new(size, fpr);
-    for key in keys {
-        morton_filter.insert(*key);
     }
-    let mut count: f64 = 0f64;
 
-    let mut sum = 0f64;
-
-    let mut fp: f64 = 0f64;
-    for i in lookups {
-        count += 1.0f64;
-        if (morton_filter.member(*i)) {
-            fp += 1.0f64;
-        }
-    }
-    sum += fp/count;
-    println!("Morton filter fpr: '{}'", sum);
+    let fpr = 0.01;
+
+    dynamic_filter_fpr("Blocked Bloom filter", BlockedBloomFilter::<H>::with_seed(size, 512, fpr, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("SIMD Blocked Bloom filter", SimdBlockedBloomFilter::<H>::with_seed(size, fpr, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("Parquet Bloom filter", ParquetBloomFilter::new(size, fpr), &keys, &lookup_keys);
+    dynamic_filter_fpr("Bloom filter", BloomFilter::<H>::with_seed(size, fpr, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("Partitioned Bloom filter", PartitionedBloomFilter::<H>::with_seed(size, fpr, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("One-hashing Bloom filter", OneHashingBloomFilter::<H>::with_seed(size, fpr, seed), &keys, &lookup_keys);
+    // sized for a sixteenth of the keys, so that it has to grow.
+    dynamic_filter_fpr("Scalable Bloom filter", ScalableBloomFilter::<BloomFilter<H>>::with_seed((size / 16).max(1), fpr, seed), &keys, &lookup_keys);
+    // a window as long as the keys, so that none are forgotten.
+    dynamic_filter_fpr("Age-partitioned Bloom filter", AgePartitionedBloomFilter::<H>::with_seed(Window::Inserts(keys.len() as u64), fpr, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("Stable Bloom filter", StableBloomFilter::<H>::with_seed(Window::Inserts(keys.len() as u64), fpr, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("Counting Bloom filter", CountingBloomFilter::<H>::with_seed(size, fpr, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("d-left Counting Bloom filter", DLeftCountingFilter::<H>::with_seed(size, fpr, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("Cuckoo filter", CuckooFilter::<H>::with_seed(size as usize, 1000, 8, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("Cuckoo filter (sized for fpr)", CuckooFilter::<H>::with_fpr_seed(size as usize, fpr, seed), &keys, &lookup_keys);
+    // sized for a sixteenth of the keys, so that it has to grow.
+    dynamic_filter_fpr("Dynamic Cuckoo filter", DynamicCuckooFilter::<H>::with_seed((size as usize / 16).max(1), fpr, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("Counting Cuckoo filter", CountingCuckooFilter::<H>::with_seed(size as usize, fpr, seed), &keys, &lookup_keys);
+    adaptive_filter_fpr("Adaptive Cuckoo filter", AdaptiveCuckooFilter::<H>::with_seed(size as usize, fpr, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("Register Aligned Bloom filter", RegisterAlignedBloomFilter::<H>::with_seed(size, 64, fpr, seed), &keys, &lookup_keys);
+    dynamic_filter_fpr("Register Aligned Bloom filter Large", RegisterAlignedBloomFilterLarger::<H>::with_seed(size, 64, fpr, seed), &keys, &lookup_keys);
 }
 
-pub(crate) fn quotient_filter_fpr(size: u64, fpr:f64, keys: &Vec<u64>, lookups : &Vec<u64>) {
-    let mut quotient_filter = QuotientFilter::new(size);
-    for key in keys {
-        quotient_filter.insert(*key);
-    }
-    let mut count: f64 = 0f64;
-
-    let mut sum = 0f64;
-
-    let mut fp: f64 = 0f64;
-    for i in lookups {
-        count += 1.0f64;
-        if (quotient_filter.member(*i)) {
-            fp += 1.0f64;
-        }
-    }
-    sum += fp/count;
-    println!("Quotient filter fpr: '{}'", sum);
+// a filter over a sliding window of keys, after all of them have gone in. every key in the last window has to be a
+// member, and keys from long before it should only be members as often as a false positive. panics if either fails,
+// allowing old keys up to twice the configured fpr, since the stable filter's settles right at it.
+pub fn window_filter_expiry<F: Filter>(name: &str, filter: &F, window: usize, keys: &[u64], fpr: f64) {
+    let recent = &keys[keys.len() - window..];
+    let false_negatives = recent.iter().filter(|key| !filter.member(**key)).count();
+    // both filters forget a key within two windows.
+    let old = &keys[..keys.len() - 2 * window];
+    let old_rate = false_positive_rate(filter, old);
+    println!("{} false negatives in the window: '{}', old keys still members: '{}'", name, false_negatives, old_rate);
+    assert_eq!(false_negatives, 0, "{} forgot keys inside the window", name);
+    assert!(old_rate < 2.0 * fpr, "{} still holds old keys at rate {}", name, old_rate);
 }
 
-
-
-
-pub(crate) fn register_aligned_bloom_filter_fpr(size: u64, fpr:f64, keys: &Vec<u64>, lookup_keys: &Vec<u64>) {
-    let mut register_aligned_bloom_filter = RegisterAlignedBloomFilter::new(size, 64, fpr);
-    for key in keys {
-        register_aligned_bloom_filter.insert(*key);
-    }
-    let mut count: f64 = 0f64;
-    let mut fp: f64 = 0f64;
-    for i in lookup_keys {
-        count += 1.0f64;
-        if (register_aligned_bloom_filter.member(*i)) {
-            fp += 1.0f64;
-        }
-    }
-    let fpr = fp / count;
-    println!("Register Aligned Bloom filter fpr: '{}'", fpr);
-}
-pub(crate) fn register_aligned_bloom_filter_larger_fpr(size: u64, fpr:f64, keys: &Vec<u64>, lookup_keys: &Vec<u64>) {
-    let mut register_aligned_bloom_filter_larger = RegisterAlignedBloomFilterLarger::new(size, 64, fpr);
-    for key in keys {
-        register_aligned_bloom_filter_larger.insert(*key);
-    }
-    let mut count: f64 = 0f64;
-    let mut fp: f64 = 0f64;
-    for i in lookup_keys {
-        count += 1.0f64;
-        if (register_aligned_bloom_filter_larger.member(*i)) {
-            fp += 1.0f64;
-        }
-    }
-    let fpr = fp / count;
-    println!("Register Aligned Bloom filter Large fpr: '{}'", fpr);
+// the forgetful filters over eight windows of random keys, with the window counted in inserts and then in time, one
+// key a millisecond. the keys are drawn from seed.
+pub fn run_window_tests<H: KeyHasher>(window: usize, seed: u64) {
+    println!("{} window", std::any::type_name::<H>());
+    println!("{}", window);
+    let mut rng = utils::seeded_rng(seed);
+    let keys: Vec<u64> = (0..window * 8).map(|_| rng.gen()).collect();
+    let fpr = 0.01;
+    let inserts = Window::Inserts(window as u64);
+    let time = Window::Time { duration: Duration::milliseconds(window as i64), expected_inserts: window as u64 };
+    let at = |i: usize| DateTime::<Utc>::UNIX_EPOCH + Duration::milliseconds(i as i64);
+
+    let mut age_partitioned = AgePartitionedBloomFilter::<H>::with_seed(inserts, fpr, seed);
+    let mut stable = StableBloomFilter::<H>::with_seed(inserts, fpr, seed);
+    for key in &keys {
+        age_partitioned.insert(*key);
+        stable.insert(*key);
+    }
+    window_filter_expiry("Age-partitioned Bloom filter", &age_partitioned, window, &keys, fpr);
+    window_filter_expiry("Stable Bloom filter", &stable, window, &keys, fpr);
+
+    let mut age_partitioned = AgePartitionedBloomFilter::<H>::with_seed(time, fpr, seed);
+    let mut stable = StableBloomFilter::<H>::with_seed(time, fpr, seed);
+    for (i, key) in keys.iter().enumerate() {
+        age_partitioned.insert_at(*key, at(i));
+        stable.insert_at(*key, at(i));
+    }
+    window_filter_expiry("Age-partitioned Bloom filter (timed)", &age_partitioned, window, &keys, fpr);
+    window_filter_expiry("Stable Bloom filter (timed)", &stable, window, &keys, fpr);
 }
 
+#[cfg(test)]
+mod tests {
+    use crate::hasher::MultiplyShift;
 
-pub(crate) fn run_fpr_tests(size: u64) {
-    println!("norm hashing normal");
-    println!("{}", size);
-    let mut keys = Vec::new();
-    for i in 0..=size {
-        keys.push(i);
-        // println!("'{}', '{}'",hasher.tabulation_hashing(i), i);
+    #[test]
+    fn window_filters_expire() {
+        super::run_window_tests::<MultiplyShift>(10_000, 0);
     }
-    let mut lookup_keys: Vec<u64> = Vec::new();
-    for i in size+1..(size as f64*4f64) as u64 {
-        lookup_keys.push(i);
-    }
-
-    bloom_filter_fpr(size, 0.01, &keys,&lookup_keys);
-    counting_bloom_filter_fpr(size,0.01,&keys,&lookup_keys);
-    cuckoo_filter_fpr(size, 0.01, &keys,&lookup_keys);
-    binary_fuse_filter_8_fpr(&keys,&lookup_keys);
-    xor_filter_fpr(&keys,&lookup_keys);
-    xor_filter_8_fpr(&keys,&lookup_keys);
-    binary_fuse_filter_fpr(&keys,&lookup_keys);
-    binary_fuse_filter_8_fpr(&keys, &lookup_keys);
-    blocked_bloom_filter_fpr(size,0.01,&keys,&lookup_keys);
-    register_aligned_bloom_filter_fpr(size,0.01,&keys,&lookup_keys);
-    morton_filter_fpr(size, 0.01, &keys,&lookup_keys);
-    quotient_filter_fpr(size, 0.01, &keys,&lookup_keys);
-
-
 }
-
-pub(crate) fn run_randomised_fpr_tests(size: u64) {
-    println!("norm hash random");
-    println!("{}", size);
-    //let mut keys = Vec::new();
-    let mut set_keys = HashSet::new();
-    let mut rng = rand::thread_rng();
-
-    while set_keys.len() < size as usize {
-        let random_value: u64 = rng.gen_range(0..(size as f64*4f64) as u64);
-        if !set_keys.contains(&random_value) {
-            set_keys.insert(random_value);
-        }
-    }
-
-    let keys: Vec<u64> = set_keys.iter().copied().collect();
-
-    let mut lookup_keys = HashSet::new();
-    while lookup_keys.len() < size as usize {
-        let random_value: u64 = rng.gen_range(0..(size as f64*4f64) as u64);
-        if !set_keys.contains(&random_value) && !lookup_keys.contains(&random_value) {
-            lookup_keys.insert(random_value);
-        }
-    }
-
-    let lookup_keys: Vec<u64>  = lookup_keys.iter().copied().collect();
-
-    blocked_bloom_filter_fpr(size,0.01,&keys,&lookup_keys);
-    bloom_filter_fpr(size, 0.01, &keys,&lookup_keys);
-    counting_bloom_filter_fpr(size,0.01,&keys,&lookup_keys);
-    cuckoo_filter_fpr(size, 0.01, &keys,&lookup_keys);
-    // binary_fuse_filter_8_fpr(&keys,&lookup_keys);
-    // xor_filter_8_fpr(&keys,&lookup_keys);
-    blocked_bloom_filter_fpr(size,0.01,&keys,&lookup_keys);
-    register_aligned_bloom_filter_fpr(size,0.01,&keys,&lookup_keys);
-    register_aligned_bloom_filter_larger_fpr(size,0.01,&keys,&lookup_keys);
-}
\ No newline at end of file
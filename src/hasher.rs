@@ -0,0 +1,300 @@
+use std::hash::{Hash, Hasher};
+use rand::Rng;
+use fnv::FnvHasher;
+
+use crate::serialize::{Decoder, Encoder, HasherKind, SerializableHasher, SerializeError};
+
+pub use crate::tabulationhashing::TabulationHashing;
+
+/*
+The hash functions a filter can be built with. Every filter is generic over a KeyHasher and draws as many
+independent functions from it as it needs, so the hash can be picked per deployment without touching the filter.
+Filters default to MultiplyShift.
+ */
+pub trait KeyHasher {
+    // draw a hash function from the family using rng. filters pass a seeded rng, so this has to be deterministic.
+    fn from_rng<R: Rng + ?Sized>(rng: &mut R) -> Self;
+
+    // 64 bit hash of the key.
+    fn hash(&self, key: u64) -> u64;
+
+    // top l bits of the hash, for indexing into a table with 2^l entries.
+    fn hash_bits(&self, key: u64, l: u32) -> u32 {
+        return self.hash(key).checked_shr(64 - l).unwrap_or(0) as u32;
+    }
+}
+
+// multiply-shift. a1,a2,b random u64s.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultiplyShift {
+    a1: u64,
+    a2: u64,
+    b: u64,
+}
+
+impl KeyHasher for MultiplyShift {
+    fn from_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        MultiplyShift {
+            a1: rng.gen_range(1..=u64::MAX),
+            a2: rng.gen_range(1..=u64::MAX),
+            b: rng.gen_range(1..=u64::MAX),
+        }
+    }
+
+    fn hash(&self, key: u64) -> u64 {
+        //return ((a1 + x) * (a2 + (x >> 32)) + b)
+        return (self.a1.wrapping_add(key)).wrapping_mul(self.a2.wrapping_add(key >> 32)).wrapping_add(self.b);
+    }
+}
+
+impl SerializableHasher for MultiplyShift {
+    const KIND: HasherKind = HasherKind::MultiplyShift;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.a1);
+        enc.put_u64(self.a2);
+        enc.put_u64(self.b);
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        return Ok(MultiplyShift {
+            a1: dec.get_u64()?,
+            a2: dec.get_u64()?,
+            b: dec.get_u64()?,
+        });
+    }
+}
+
+// simple tabulation over the 16 hex digits of the key.
+impl KeyHasher for TabulationHashing {
+    fn from_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        return TabulationHashing::from_rng(rng);
+    }
+
+    fn hash(&self, key: u64) -> u64 {
+        return self.tabulation_hashing(key);
+    }
+}
+
+impl SerializableHasher for TabulationHashing {
+    const KIND: HasherKind = HasherKind::Tabulation;
+
+    fn encode(&self, enc: &mut Encoder) {
+        for lookup in &self.lookups {
+            for x in lookup {
+                enc.put_u64(*x);
+            }
+        }
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let mut lookups = vec![];
+        for _ in 0..16 {
+            let mut lookup = vec![];
+            for _ in 0..16 {
+                lookup.push(dec.get_u64()?);
+            }
+            lookups.push(lookup);
+        }
+        return Ok(TabulationHashing {
+            lookups
+        });
+    }
+}
+
+// murmur3 x64 128, truncated to 64 bits.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Murmur3 {
+    seed: u64,
+}
+
+impl KeyHasher for Murmur3 {
+    fn from_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Murmur3 {
+            seed: rng.gen(),
+        }
+    }
+
+    fn hash(&self, key: u64) -> u64 {
+        return fastmurmur3::murmur3_x64_128(&key.to_le_bytes(), self.seed) as u64;
+    }
+}
+
+impl SerializableHasher for Murmur3 {
+    const KIND: HasherKind = HasherKind::Murmur3;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        return Ok(Murmur3 {
+            seed: dec.get_u64()?,
+        });
+    }
+}
+
+// aHash output depends on the CPU features it was compiled for, so a seeded filter using it is only reproducible
+// on the same kind of machine. the same goes for a saved one.
+#[derive(Clone)]
+pub struct AHash {
+    // kept so the function can be saved, RandomState does not give them back.
+    seeds: [u64; 4],
+    state: ahash::RandomState,
+}
+
+impl AHash {
+    fn from_seeds(seeds: [u64; 4]) -> AHash {
+        AHash {
+            seeds,
+            state: ahash::RandomState::with_seeds(seeds[0], seeds[1], seeds[2], seeds[3]),
+        }
+    }
+}
+
+// serialized as its seeds, the state is rebuilt from them.
+#[cfg(feature = "serde")]
+impl serde::Serialize for AHash {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return self.seeds.serialize(serializer);
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for AHash {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        return Ok(AHash::from_seeds(<[u64; 4]>::deserialize(deserializer)?));
+    }
+}
+
+impl KeyHasher for AHash {
+    fn from_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        return AHash::from_seeds([rng.gen(), rng.gen(), rng.gen(), rng.gen()]);
+    }
+
+    fn hash(&self, key: u64) -> u64 {
+        return self.state.hash_one(key);
+    }
+}
+
+impl SerializableHasher for AHash {
+    const KIND: HasherKind = HasherKind::AHash;
+
+    fn encode(&self, enc: &mut Encoder) {
+        for seed in self.seeds {
+            enc.put_u64(seed);
+        }
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        return Ok(AHash::from_seeds([dec.get_u64()?, dec.get_u64()?, dec.get_u64()?, dec.get_u64()?]));
+    }
+}
+
+// 64 bit FNV-1a, started from a random offset basis.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fnv {
+    key: u64,
+}
+
+impl KeyHasher for Fnv {
+    fn from_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Fnv {
+            key: rng.gen(),
+        }
+    }
+
+    fn hash(&self, key: u64) -> u64 {
+        let mut hasher = FnvHasher::with_key(self.key);
+        hasher.write_u64(key);
+        return hasher.finish();
+    }
+}
+
+impl SerializableHasher for Fnv {
+    const KIND: HasherKind = HasherKind::Fnv;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.key);
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        return Ok(Fnv {
+            key: dec.get_u64()?,
+        });
+    }
+}
+
+/*
+Reducing arbitrary keys to the u64 the filters work on. This runs before the filter's own KeyHasher, and has to give
+the same answer on every machine, so it uses fixed constants and writes integers little endian.
+ */
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+// FNV-1a over everything the key writes, except that a key which is a single u64 passes through unchanged.
+// that keeps the generic entry points consistent with the u64 ones, and free for u64 keys.
+struct KeyReducer {
+    state: u64,
+    written: bool,
+}
+
+impl Hasher for KeyReducer {
+    fn finish(&self) -> u64 {
+        return self.state;
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.written = true;
+        for byte in bytes {
+            self.state ^= *byte as u64;
+            self.state = self.state.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        if !self.written {
+            self.written = true;
+            self.state = i;
+            return;
+        }
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    // lengths are written as usize, which would otherwise depend on the platform.
+    fn write_usize(&mut self, i: usize) {
+        self.write(&(i as u64).to_le_bytes());
+    }
+}
+
+// the u64 a filter stores for any Hash key.
+pub fn reduce_key<K: Hash + ?Sized>(key: &K) -> u64 {
+    let mut reducer = KeyReducer {
+        state: FNV_OFFSET_BASIS,
+        written: false,
+    };
+    key.hash(&mut reducer);
+    return reducer.finish();
+}
+
+// the u64 a filter stores for a byte string key. skips the Hash machinery, so this is not the same value
+// reduce_key gives for the same bytes; a filter should be queried the same way it was built.
+pub fn reduce_bytes(bytes: &[u8]) -> u64 {
+    return fastmurmur3::murmur3_x64_128(bytes, 0) as u64;
+}
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 use std::fs::File;
-use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::io::{BufRead, BufReader, BufWriter, Write};
 use rand::prelude::SliceRandom;
 use rand::Rng;
 pub static SAMPLE_SIZE: u64 = 1000000000;
@@ -79,10 +79,31 @@ impl KeyGenerator {
         }
         return (list,queries)
     }
-    fn generate_disjoint_keys(size:u64) -> (Vec<u64>, Vec<u64>) {
+    fn generate_disjoint_keys(_size:u64) -> (Vec<u64>, Vec<u64>) {
         return ((0..SAMPLE_SIZE).collect(),(SAMPLE_SIZE..2*SAMPLE_SIZE).collect());
     }
 
+    // a stream of count lookups drawn from the first distinct keys of the disjoint lookup set, so none of them are
+    // members. the i-th key comes up with probability proportional to 1 / i^exponent (a zipf distribution), so a few
+    // keys are asked for over and over, as in a cache.
+    pub fn skewed_queries(&self, count: usize, distinct: usize, exponent: f64) -> Vec<u64> {
+        let lookup_keys = &self.disjoint.1[..distinct.min(self.disjoint.1.len())];
+        let mut cumulative = Vec::with_capacity(lookup_keys.len());
+        let mut total = 0.0;
+        for i in 1..=lookup_keys.len() {
+            total += 1.0 / (i as f64).powf(exponent);
+            cumulative.push(total);
+        }
+        let mut rng = rand::thread_rng();
+        let mut queries = Vec::with_capacity(count);
+        for _ in 0..count {
+            let x = rng.gen_range(0.0..total);
+            let i = cumulative.partition_point(|c| *c <= x).min(lookup_keys.len() - 1);
+            queries.push(lookup_keys[i]);
+        }
+        return queries;
+    }
+
     pub fn write_to_file(&self) -> Result<(), std::io::Error> {
         let file = File::create("random_keys")?;
         let mut writer = BufWriter::new(file);
@@ -0,0 +1,103 @@
+/*
+A library of Approximate Membership Query filters.
+Every filter implements the traits in `filter`, and is exposed through one of the public modules below
+grouped by family, e.g. `diss::bloom::BloomFilter` or `diss::xor::XorFilter8`.
+ */
+
+pub mod filter;
+pub mod bitvector;
+pub mod hasher;
+pub mod keygenerator;
+pub mod fpr;
+pub mod serialize;
+pub(crate) mod utils;
+mod window;
+mod tabulationhashing;
+
+mod bloomfilter;
+mod countingbloomfilter;
+mod dleftcountingfilter;
+mod blockedbloomfilter;
+mod simdblockedbloomfilter;
+mod parquetbloomfilter;
+mod scalablebloomfilter;
+mod partitionedbloomfilter;
+mod onehashingbloomfilter;
+mod agepartitionedbloomfilter;
+mod stablebloomfilter;
+mod registeralignedbloomfilter;
+mod registeralignedlarger;
+mod cuckoofilter;
+mod dynamiccuckoofilter;
+mod countingcuckoofilter;
+mod adaptivecuckoofilter;
+mod semisort;
+mod xorfilter;
+mod xorfilter8;
+mod threewisebinaryfusefilter8;
+mod threewisebinaryfusefilter16;
+mod threewisebinaryfusefilter32;
+mod fourwisebinaryfusefilter8;
+mod fourwisebinaryfusefilter16;
+mod fourwisebinaryfusefilter32;
+mod quotientfilter;
+mod quotientinfo;
+mod mortonfilter;
+mod mortonblock;
+mod ribbonfilter;
+mod bumpedribbonfilter;
+mod filterview;
+
+pub mod bloom {
+    pub use crate::bloomfilter::BloomFilter;
+    pub use crate::countingbloomfilter::CountingBloomFilter;
+    pub use crate::dleftcountingfilter::DLeftCountingFilter;
+    pub use crate::blockedbloomfilter::BlockedBloomFilter;
+    pub use crate::simdblockedbloomfilter::SimdBlockedBloomFilter;
+    pub use crate::parquetbloomfilter::ParquetBloomFilter;
+    pub use crate::scalablebloomfilter::{BloomStage, ScalableBloomFilter};
+    pub use crate::partitionedbloomfilter::PartitionedBloomFilter;
+    pub use crate::onehashingbloomfilter::OneHashingBloomFilter;
+    pub use crate::agepartitionedbloomfilter::AgePartitionedBloomFilter;
+    pub use crate::stablebloomfilter::StableBloomFilter;
+    pub use crate::window::Window;
+    pub use crate::registeralignedbloomfilter::RegisterAlignedBloomFilter;
+    pub use crate::registeralignedlarger::RegisterAlignedBloomFilterLarger;
+}
+
+pub mod cuckoo {
+    pub use crate::cuckoofilter::CuckooFilter;
+    pub use crate::dynamiccuckoofilter::DynamicCuckooFilter;
+    pub use crate::countingcuckoofilter::CountingCuckooFilter;
+    pub use crate::adaptivecuckoofilter::{AdaptiveCuckooFilter, KeyStore};
+}
+
+pub mod xor {
+    pub use crate::xorfilter8::XorFilter8;
+    pub use crate::xorfilter::XorFilter32;
+    pub use crate::filterview::{XorFilterRef, XorFilter8Ref, XorFilter32Ref};
+}
+
+pub mod binaryfuse {
+    pub use crate::threewisebinaryfusefilter8::ThreeWiseBinaryFuseFilter8;
+    pub use crate::threewisebinaryfusefilter16::ThreeWiseBinaryFuseFilter16;
+    pub use crate::threewisebinaryfusefilter32::ThreeWiseBinaryFuseFilter32;
+    pub use crate::fourwisebinaryfusefilter8::FourWiseBinaryFuseFilter8;
+    pub use crate::fourwisebinaryfusefilter16::FourWiseBinaryFuseFilter16;
+    pub use crate::fourwisebinaryfusefilter32::FourWiseBinaryFuseFilter32;
+    pub use crate::filterview::{BinaryFuseRef, BinaryFuse8Ref, BinaryFuse16Ref, BinaryFuse32Ref};
+    pub use crate::filterview::{FourWiseBinaryFuseRef, FourWiseBinaryFuse8Ref, FourWiseBinaryFuse16Ref, FourWiseBinaryFuse32Ref};
+}
+
+pub mod quotient {
+    pub use crate::quotientfilter::QuotientFilter;
+}
+
+pub mod morton {
+    pub use crate::mortonfilter::MortonFilter;
+}
+
+pub mod ribbon {
+    pub use crate::ribbonfilter::RibbonFilter;
+    pub use crate::bumpedribbonfilter::BumpedRibbonFilter;
+}
@@ -0,0 +1,119 @@
+// one 512 bit block, the size of a cache line: the fca in words 0 and 1, the ota in the low 16 bits of word 2, and the
+// fsa from bit 144 on. fingerprints start on a byte boundary, so none straddles two words.
+pub(crate) const BUCKETS_PER_BLOCK: usize = 64;
+pub(crate) const SLOTS_PER_BLOCK: usize = 46;
+pub(crate) const OTA_BITS: usize = 16;
+// the most fingerprints a 2 bit fca counter can count.
+pub(crate) const FCA_MAX: u32 = 3;
+const FSA_START: usize = 2 * 64 + OTA_BITS;
+
+const LOW_BITS: u64 = 0x5555_5555_5555_5555;
+
+#[derive(Debug, Clone, Copy)]
+#[repr(align(64))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MortonBlock {
+    pub(crate) words: [u64; 8],
+}
+
+impl MortonBlock {
+    pub(crate) fn new() -> MortonBlock {
+        return MortonBlock {
+            words: [0; 8],
+        }
+    }
+
+    // the number of fingerprints in a bucket.
+    pub(crate) fn count(&self, bucket: usize) -> u32 {
+        return (self.words[bucket / 32] >> (2 * (bucket % 32)) & 3) as u32;
+    }
+
+    fn set_count(&mut self, bucket: usize, count: u32) {
+        let shift = 2 * (bucket % 32);
+        let word = &mut self.words[bucket / 32];
+        *word = (*word & !(3 << shift)) | (count as u64) << shift;
+    }
+
+    // the sum of the 2 bit counters in a word, from the popcounts of their low and high bits.
+    fn sum_counts(word: u64) -> usize {
+        return (word & LOW_BITS).count_ones() as usize + 2 * (word >> 1 & LOW_BITS).count_ones() as usize;
+    }
+
+    // where a bucket's fingerprints start in the fsa: the number of fingerprints in the buckets before it.
+    pub(crate) fn offset(&self, bucket: usize) -> usize {
+        let (word, shift) = (bucket / 32, 2 * (bucket % 32));
+        let below = if shift == 0 { 0 } else { self.words[word] & (u64::MAX >> (64 - shift)) };
+        let before = if word == 1 { Self::sum_counts(self.words[0]) } else { 0 };
+        return before + Self::sum_counts(below);
+    }
+
+    // the number of fingerprints in the whole block.
+    pub(crate) fn len(&self) -> usize {
+        return Self::sum_counts(self.words[0]) + Self::sum_counts(self.words[1]);
+    }
+
+    pub(crate) fn ota(&self, bit: usize) -> bool {
+        return self.words[2] >> bit & 1 == 1;
+    }
+
+    pub(crate) fn set_ota(&mut self, bit: usize) {
+        self.words[2] |= 1 << bit;
+    }
+
+    pub(crate) fn fingerprint(&self, slot: usize) -> u8 {
+        let bit = FSA_START + 8 * slot;
+        return (self.words[bit / 64] >> (bit % 64)) as u8;
+    }
+
+    fn set_fingerprint(&mut self, slot: usize, f: u8) {
+        let bit = FSA_START + 8 * slot;
+        let word = &mut self.words[bit / 64];
+        *word = (*word & !(0xff << (bit % 64))) | (f as u64) << (bit % 64);
+    }
+
+    // the fsa slot of f in the bucket, if it is there.
+    pub(crate) fn find(&self, bucket: usize, f: u8) -> Option<usize> {
+        let offset = self.offset(bucket);
+        return (offset..offset + self.count(bucket) as usize).find(|slot| self.fingerprint(*slot) == f);
+    }
+
+    // whether the bucket can take another fingerprint: its counter is not full, and neither is the fsa.
+    pub(crate) fn has_room(&self, bucket: usize) -> bool {
+        return self.count(bucket) < FCA_MAX && self.len() < SLOTS_PER_BLOCK;
+    }
+
+    // add f to the end of the bucket, moving the fingerprints of the later buckets up one slot.
+    pub(crate) fn insert(&mut self, bucket: usize, f: u8) {
+        let count = self.count(bucket);
+        let slot = self.offset(bucket) + count as usize;
+        for i in (slot..self.len()).rev() {
+            self.set_fingerprint(i + 1, self.fingerprint(i));
+        }
+        self.set_fingerprint(slot, f);
+        self.set_count(bucket, count + 1);
+    }
+
+    // take the fingerprint in the slot out of the bucket, moving the later ones down to close the gap.
+    pub(crate) fn remove(&mut self, bucket: usize, slot: usize) -> u8 {
+        let f = self.fingerprint(slot);
+        let len = self.len();
+        for i in slot..len - 1 {
+            self.set_fingerprint(i, self.fingerprint(i + 1));
+        }
+        self.set_fingerprint(len - 1, 0);
+        self.set_count(bucket, self.count(bucket) - 1);
+        return f;
+    }
+
+    // the bucket that the fingerprint in an fsa slot belongs to.
+    pub(crate) fn bucket_of(&self, slot: usize) -> usize {
+        let mut end = 0;
+        for bucket in 0..BUCKETS_PER_BLOCK {
+            end += self.count(bucket) as usize;
+            if slot < end {
+                return bucket;
+            }
+        }
+        unreachable!("slot past the last fingerprint");
+    }
+}
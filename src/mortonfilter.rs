@@ -1,37 +1,86 @@
-use rand::Rng;
-
-#[path = "MortonBlock.rs"]
-mod mortonblock;
-use mortonblock::MortonBlock;
-#[path = "utils.rs"]
-mod utils;
-use utils::{hash};
-
-const BUCKETS_PER_BLOCK: u32 = 46;
+use rand::{Rng, SeedableRng};
+
+use crate::mortonblock;
+use mortonblock::{MortonBlock, BUCKETS_PER_BLOCK, OTA_BITS, SLOTS_PER_BLOCK};
+use crate::utils;
+
+use rand_chacha::ChaCha8Rng;
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::filter::{self, DeletableFilter, DynamicFilter, Filter, FilterStats};
+use crate::serialize::{ensure, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
+
+/*
+Morton filter (Breslow and Jayasena, 2018).
+A cuckoo filter compressed into 512 bit blocks. Each block has 64 logical buckets but only 46 fingerprint slots, in a
+fingerprint storage array (fsa) shared by all of them. A fullness counter array (fca) of 2 bit counters says how many
+fingerprints each bucket has, and a bucket's fingerprints start after those of the buckets before it, so most of
+the space goes on fingerprints rather than empty slots. See mortonblock.
+A key goes in bucket h1, or h2 = h1 +- offset(f) when that is full, the sign following the parity of h1 so that h2
+of h2 is h1 again. Keys in h2 set a bit of h1's overflow tracking array (ota), and a query only reads the second
+bucket if that bit is set, so most negative queries touch a single block. When both buckets are full fingerprints
+are kicked to their other bucket as in the cuckoo filter, with a victim stash of one.
+Ota bits are never cleared, since other keys may share them, so after many deletes some queries read two buckets
+that need not.
+The block layout fixes fingerprints at 8 bits, so the false positive rate is not a parameter.
+ */
+
+// a little under the load at which inserts start to fail, about 0.93 to 0.95 for blocks of 46 slots.
+const LOAD: f64 = 0.9;
 const OFF_RANGE: u32 = 256;
-const FCA_MAX_VAL: u8 = 3;
+const MAX_KICKS: usize = 500;
 
-pub struct MortonFilter {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MortonFilter<H = MultiplyShift> {
     pub(crate) block_store: Vec<MortonBlock>,
-    hashes: Vec<(u64,u64,u64)>,
-    cache_size: usize,
+    // the fingerprint hash function, then the bucket hash function.
+    hashes: Vec<H>,
+    seed: u64,
+    // the number of buckets, 64 per block.
     pub(crate) size: u64,
-    l: u32,
+    max_kicks: usize,
+    // picks the fingerprint to kick, so that inserts are reproducible from the seed too.
+    rng: ChaCha8Rng,
+    // the bucket and fingerprint left over when kicking ran out.
+    victim: Option<(u64, u8)>,
 }
 
 impl MortonFilter {
-    pub fn new(size : u64, fpr: f64) -> MortonFilter {
-        let length = size as u64;
-        let n = length * BUCKETS_PER_BLOCK as u64;
+    pub fn new(size : u64) -> MortonFilter {
+        return Self::with_hasher(size);
+    }
+}
+
+impl<H: KeyHasher> MortonFilter<H> {
+    // same as new, but draws the hash functions from H instead of multiply-shift.
+    pub fn with_hasher(size : u64) -> Self {
+        return Self::with_seed(size, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    // size is the number of keys, and the blocks are filled to LOAD.
+    pub fn with_seed(size : u64, seed: u64) -> Self {
+        let mut rng = utils::seeded_rng(seed);
+        let length = ((size as f64 / (SLOTS_PER_BLOCK as f64 * LOAD)).ceil() as u64).max(1);
         return MortonFilter {
             block_store: Self::generate_block_store(length),
-            hashes: Self::generate_hash_functions(4),
-            cache_size: 512,
-            size: n,
-            l: utils::log_base(length as f64, 2f64) as u32
+            hashes: Self::generate_hash_functions(2, &mut rng),
+            seed,
+            size: length * BUCKETS_PER_BLOCK as u64,
+            max_kicks: MAX_KICKS,
+            rng,
+            victim: None,
         }
     }
 
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    // whether an insert ran out of kicks, so that the victim stash is in use.
+    pub fn is_full(&self) -> bool {
+        return self.victim.is_some();
+    }
+
     fn generate_block_store(length : u64) -> Vec<MortonBlock> {
         let mut bs = Vec::new();
         for _ in 0..length {
@@ -40,377 +89,308 @@ impl MortonFilter {
         return bs;
     }
 
-    fn generate_hash_functions(n: usize) -> Vec<(u64, u64,u64)> {
-        let mut rng = rand::thread_rng();
+    fn generate_hash_functions(n: usize, rng: &mut ChaCha8Rng) -> Vec<H> {
         let mut hash_functions = Vec::new();
         for _ in 0..n {
-            let a1: u64 = rng.gen_range(1..=u64::MAX );
-            let a2: u64 = rng.gen_range(1..=u64::MAX);
-            let b: u64 = rng.gen_range(1..=u64::MAX);
-            hash_functions.push((a1,a2,b));
+            hash_functions.push(H::from_rng(rng));
         }
         return hash_functions;
     }
 
-    pub fn insert(&mut self, x: u64) -> bool {
-        // obtain indices
-        let f = self.fingerprint(x);
-        let glbi1 = self.hash1(x);
-        let mut block1: &mut MortonBlock = &mut self.block_store[(glbi1/BUCKETS_PER_BLOCK) as usize];
-        let lbi1 = glbi1 % BUCKETS_PER_BLOCK;
-
-        // calculate offset.
-        let mut off = 0;
-        for i in 0..lbi1 {
-            off += block1.fca[i as usize];
-        }
-        //overflow condition
-        // calculate FCA value. must be less than 3
-        let overflow_check = block1.fca[lbi1 as usize];
-        if off + overflow_check >= BUCKETS_PER_BLOCK as u8 || overflow_check >= FCA_MAX_VAL {
-            // set OTA
-            let index = utils::map(lbi1 as u64, 16);
-            let mut ota = &mut block1.ota;
-            ota[index as usize] = 1;
-            let glbi2 = self.hash2(glbi1 + lbi1, f);
-            let mut block2: &mut MortonBlock  = &mut self.block_store[(glbi2/BUCKETS_PER_BLOCK) as usize];
-            let lbi2 = glbi2 % BUCKETS_PER_BLOCK;
-
-            // calculate offset
-            // perform lookup and see if exists.
-            let mut off2 = 0;
-            for i in 0..lbi2 {
-                off2 += block2.fca[i as usize];
-            }
-            //check FCA isn't full
-            let overflow_check = block2.fca[lbi2 as usize];
-            if off2 + overflow_check >= BUCKETS_PER_BLOCK as u8 || overflow_check >= FCA_MAX_VAL {
-                return false;
-                // perform eviction from block 1
-                let mut fingerprint = f;
-                let mut global_index = glbi1;
-                let mut block: &mut MortonBlock = &mut self.block_store[(glbi1/BUCKETS_PER_BLOCK) as usize];
-                let mut local_index = global_index % BUCKETS_PER_BLOCK;
-
-                // calculate offset.
-                let mut off = 0;
-                for i in 0..local_index {
-                    off += block.fca[i as usize];
-                }
-                let mut num_kicks = 0;
-                while num_kicks < 1000 {
-                    num_kicks += 1;
-                    let overflow_check = block.fca[local_index as usize];
-                    let mut rng = rand::thread_rng();
-                    let random_index = rng.gen_range(off..=off+overflow_check);
-                    let kicked_key = block.fsa[random_index as usize];
-                    block.fsa[random_index as usize] = fingerprint;
-
-                    // map evicted key to alternate bucket
-                    let alternate = self.hash_prime(global_index as usize, kicked_key);
-                    let mut alternate_block: &mut MortonBlock = &mut self.block_store[(alternate/BUCKETS_PER_BLOCK) as usize];
-                    let mut alternate_local = alternate % BUCKETS_PER_BLOCK;
-                    let mut offa = 0;
-                    for i in 0..alternate_local {
-                        offa += alternate_block.fca[i as usize];
-                    }
-
-                    let overflow_check = alternate_block.fca[local_index as usize];
-                    if offa + overflow_check >= BUCKETS_PER_BLOCK as u8 || overflow_check >= FCA_MAX_VAL {
-                        println!("need to evict again");
-                        // another round set variables appropriately
-                        fingerprint = kicked_key;
-                        global_index = alternate;
-                        block = alternate_block;
-                        local_index = alternate_local;
-                        off = offa;
-                    }
-                    else {
-                        // insert key and move on
-                        println!("done evicting");
-                        alternate_block.fsa[(offa + overflow_check) as usize] = kicked_key;
-                        alternate_block.fca[alternate_local as usize] += 1;
-                        return true;
-                    }
+    pub(crate) fn fingerprint(&self, key: u64) -> u8 {
+        return self.hashes[0].hash_bits(key, 8) as u8;
+    }
 
-                }
+    pub(crate) fn hash1(&self, key: u64) -> u64 {
+        return ((self.hashes[1].hash(key) as u128 * self.size as u128) >> 64) as u64;
+    }
 
-                return false;
-            }
-            else {
-                // insert into fsa
-                let mut fsa: &mut Vec<u8> = &mut block2.fsa;
-                fsa.insert((off2 + overflow_check) as usize, f);
-                block2.fca[lbi2 as usize] += 1;
-                return true;
-            }
-        }
-        else {
-            // insert into fsa
-            let mut fsa= &mut block1.fsa;
-            fsa.insert((off+overflow_check) as usize, f);
-            block1.fca[lbi1 as usize] += 1;
-            return true;
+    // h1 + offset(f) from an even bucket and h1 - offset(f) from an odd one. the offset is odd and the bucket count
+    // even, so the parity flips and applying it twice gives back the bucket it started from.
+    pub(crate) fn alternate(&self, bucket: u64, fingerprint: u8) -> u64 {
+        let offset = self.offset(fingerprint) as u64 % self.size;
+        if bucket & 1 == 0 {
+            return (bucket + offset) % self.size;
         }
-        return false;
+        return (bucket + self.size - offset) % self.size;
     }
 
+    // at least a block away, so that the two buckets are usually in different blocks.
+    pub(crate) fn offset(&self, fingerprint: u8) -> u32 {
+        return (BUCKETS_PER_BLOCK as u32 + (fingerprint as u32 % OFF_RANGE)) | 1u32;
+    }
 
+    fn block(&self, bucket: u64) -> (&MortonBlock, usize) {
+        let bucket = bucket as usize;
+        return (&self.block_store[bucket / BUCKETS_PER_BLOCK], bucket % BUCKETS_PER_BLOCK);
+    }
 
+    fn block_mut(&mut self, bucket: u64) -> (&mut MortonBlock, usize) {
+        let bucket = bucket as usize;
+        return (&mut self.block_store[bucket / BUCKETS_PER_BLOCK], bucket % BUCKETS_PER_BLOCK);
+    }
 
+    fn try_insert(&mut self, bucket: u64, f: u8) -> bool {
+        let (block, local) = self.block_mut(bucket);
+        if !block.has_room(local) {
+            return false;
+        }
+        block.insert(local, f);
+        return true;
+    }
 
+    // a fingerprint in bucket is leaving it for its other bucket, which may be the key's second. setting the ota bit
+    // either way is safe, and only costs a lookup.
+    fn mark_overflow(&mut self, bucket: u64) {
+        let (block, local) = self.block_mut(bucket);
+        block.set_ota(local % OTA_BITS);
+    }
 
+    // put f in the full bucket by kicking out one of its fingerprints, or if the bucket itself has room but its
+    // block does not, any fingerprint in the block. the kicked fingerprint goes to its other bucket, and so on.
+    // whatever is left after max_kicks becomes the victim.
+    fn kick(&mut self, mut bucket: u64, mut f: u8) {
+        for _ in 0..self.max_kicks {
+            let (block, local) = self.block(bucket);
+            let (bucket_full, offset) = (block.count(local) == mortonblock::FCA_MAX, block.offset(local));
+            let slot = if bucket_full {
+                offset + self.rng.gen_range(0..mortonblock::FCA_MAX as usize)
+            } else {
+                self.rng.gen_range(0..SLOTS_PER_BLOCK)
+            };
+            let kicked_local = self.block(bucket).0.bucket_of(slot);
+            let kicked_bucket = bucket - local as u64 + kicked_local as u64;
+            let (block, _) = self.block_mut(bucket);
+            let kicked = block.remove(kicked_local, slot);
+            block.insert(local, f);
+            self.mark_overflow(kicked_bucket);
+            f = kicked;
+            bucket = self.alternate(kicked_bucket, f);
+            if self.try_insert(bucket, f) {
+                return;
+            }
+        }
+        self.victim = Some((bucket, f));
+    }
 
+    fn victim_matches(&self, h1: u64, h2: u64, f: u8) -> bool {
+        return matches!(self.victim, Some((bucket, victim)) if victim == f && (bucket == h1 || bucket == h2));
+    }
+}
 
-
-
-
-
-    /*
-
-                // println!("eviction needed, {}", x);
-                // return false;
-                // initialise mutable variables to be used on each iteration. evict from bucket1.
-                let mut num_kicks = 0;
-                let mut finished = false;
-                let mut offset = off;
-                let mut block: &mut MortonBlock = &mut self.block_store[(glbi1/BUCKETS_PER_BLOCK) as usize];
-                let mut f = f;
-                let mut local_index = lbi1;
-                let mut remapped_index = glbi1;
-                // assert that we are retrieving the correct h1 for the evicted key.
-
-                while !finished && num_kicks < 1000 {
-                    num_kicks += 1;
-                    // select a key to evict and update fsa with new key.
-                    let mut fsa: &mut Vec<u8> = &mut block.fsa;
-                    let evicted_key = fsa[offset as usize];
-                    // println!("{}", evicted_key);
-                    //println!("eviction needed, {}, {}", x, evicted_key);
-
-                    fsa[offset as usize] = f;
-                    f = evicted_key;
-
-                    // update ota to mark eviction
-                    let index = utils::map(local_index as u64, 16);
-                    let mut ota = &mut block.ota;
-                    ota[index as usize] = 1;
-
-                    //remap evicted key using hash_prime
-                    // let r = self.hash_prime(remapped_index as usize, evicted_key);
-                    // let rr = self.hash_prime(r as usize, evicted_key);
-                    // let rrr = self.hash_prime(rr as usize, evicted_key);
-                    //
-                    // println!("{},{},{},{}",remapped_index,r,rr,rrr);
-                    let r = remapped_index;
-                    remapped_index = self.hash_prime(remapped_index as usize, evicted_key);
-                    // println!("{} {}", r, remapped_index);
-                    // insert key in correct position,
-                    block = &mut self.block_store[(remapped_index/BUCKETS_PER_BLOCK) as usize];
-                    local_index = remapped_index % BUCKETS_PER_BLOCK;
-
-                    // calculate offset
-                    offset = 0;
-                    for i in 0..local_index {
-                        offset += block.fca[i as usize];
-                    }
-                    //check FCA isn't full
-                    let check = block.fca[local_index as usize];
-                    if offset + check >= BUCKETS_PER_BLOCK as u8 || check >= FCA_MAX_VAL {
-                        println!("need to evict again");
-                    }
-                    else {
-                        // println!("evicted successfully");
-                        // insert into fsa
-                        let mut fsa= &mut block.fsa;
-                        fsa.insert((offset + check) as usize, f);
-
-                        // update fca
-                        let mut fca = &mut block.fca;
-                        fca[local_index as usize] += 1;
-                        finished = true;
-                        break;
-                    }
-                }
-     */
-
-
-
-
-
-
-
-    pub fn member(&self, x: u64) -> bool {
-        // obtain indices
+impl<H: KeyHasher> Filter for MortonFilter<H> {
+    fn member(&self, x: u64) -> bool {
         let f = self.fingerprint(x);
-        let glbi1 = self.hash1(x);
-        let mut block1 = &self.block_store[(glbi1/BUCKETS_PER_BLOCK) as usize];
-        let lbi1 = glbi1 % BUCKETS_PER_BLOCK;
-
-        // calculate offset.
-        let mut off = 0;
-        for i in 0..lbi1 {
-            off += block1.fca[i as usize];
+        let h1 = self.hash1(x);
+        let (block1, lbi1) = self.block(h1);
+        if block1.find(lbi1, f).is_some() {
+            return true;
         }
-        // println!("{}", off);
-        //
-        let mut num_in_bucket = block1.fca[lbi1 as usize];
-        for j in off as usize..=(off+num_in_bucket) as usize {
-            if block1.fsa[j] == f {
-                return true;
-            }
+        let h2 = self.alternate(h1, f);
+        if self.victim_matches(h1, h2, f) {
+            return true;
         }
-
-        // we haven't matched yet. check if overflow bit is set.
-        if block1.ota[utils::map(lbi1 as u64, 16) as usize]  < 1 {
+        // we haven't matched yet. the key can only be in its other bucket if the overflow bit is set.
+        if !block1.ota(lbi1 % OTA_BITS) {
             return false;
         }
+        let (block2, lbi2) = self.block(h2);
+        return block2.find(lbi2, f).is_some();
+    }
+}
 
-        // fingerprint might be in other bucket
-
-        let glbi2 = self.hash2(glbi1 + lbi1, f);
-        let mut block2 = &self.block_store[(glbi2/BUCKETS_PER_BLOCK) as usize];
-        let lbi2 = glbi2 % BUCKETS_PER_BLOCK;
-
-        // calculate offset
-        // perform lookup and see if exists.
-        let mut off2 = 0;
-        let mut i = 0;
-        for i in 0..lbi2 {
-            off2 += block2.fca[i as usize];
+impl<H: KeyHasher> DynamicFilter for MortonFilter<H> {
+    // returns false if both of the key's buckets are full and the victim stash is in use already.
+    fn insert(&mut self, x: u64) -> bool {
+        let f = self.fingerprint(x);
+        let h1 = self.hash1(x);
+        if self.try_insert(h1, f) {
+            return true;
         }
-        let mut num_in_bucket = block2.fca[lbi2 as usize];
+        // from here on the key may end up in h2, so h1's overflow bit has to be set.
+        self.mark_overflow(h1);
+        let h2 = self.alternate(h1, f);
+        if self.try_insert(h2, f) {
+            return true;
+        }
+        if self.victim.is_some() {
+            return false;
+        }
+        let bucket = if self.rng.gen() { h1 } else { h2 };
+        self.kick(bucket, f);
+        return true;
+    }
+}
 
-        for j in off2..=off2+num_in_bucket {
-            if block2.fsa[j as usize] == f {
+impl<H: KeyHasher> DeletableFilter for MortonFilter<H> {
+    // only delete keys that were inserted, or another key with the same fingerprint and buckets goes missing.
+    fn delete(&mut self, x: u64) -> bool {
+        let f = self.fingerprint(x);
+        let h1 = self.hash1(x);
+        let h2 = self.alternate(h1, f);
+        if self.victim_matches(h1, h2, f) {
+            self.victim = None;
+            return true;
+        }
+        let (block1, lbi1) = self.block(h1);
+        let check_h2 = block1.ota(lbi1 % OTA_BITS);
+        for bucket in [h1, h2] {
+            if bucket == h2 && !check_h2 {
+                break;
+            }
+            let (block, local) = self.block_mut(bucket);
+            if let Some(slot) = block.find(local, f) {
+                block.remove(local, slot);
+                // a delete makes room, so the victim, if there is one, gets another go at finding a slot.
+                if let Some((bucket, victim)) = self.victim.take() {
+                    let other = self.alternate(bucket, victim);
+                    if self.try_insert(bucket, victim) {
+                        return true;
+                    }
+                    // as in insert, the victim may be leaving its first bucket for its second.
+                    self.mark_overflow(bucket);
+                    if !self.try_insert(other, victim) {
+                        self.kick(bucket, victim);
+                    }
+                }
                 return true;
             }
         }
         return false;
     }
+}
 
-    // pub fn delete(&mut self, x: u64) -> bool {
-    //     // obtain indices
-    //     let f = self.fingerprint(x);
-    //     let glbi1 = self.hash1(x);
-    //     let mut block1 = &mut self.block_store[(glbi1/BUCKETS_PER_BLOCK) as usize];
-    //     let lbi1 = glbi1 % BUCKETS_PER_BLOCK;
-    //
-    //     // calculate offset.
-    //     let mut off: usize = 0;
-    //     let mut i: usize = 0;
-    //     loop {
-    //         if i > (2 * lbi1) as usize {
-    //             break;
-    //         }
-    //         off += ((block1.fca.member(i as u64) as u32 & 1u32) + (2* (block1.fca.member((i + 1) as u64) as u32 & 1u32))) as usize;
-    //         i+=2;
-    //     }
-    //     let mut num_in_bucket = block1.fca.member(2 * lbi1 as u64) as u32 & 1u32 + 2 * (block1.fca.member(((2 * lbi1) + 1) as u64) as u32 & 1u32);
-    //     off = off.saturating_sub(2);
-    //     off = off.saturating_sub(num_in_bucket as usize);
-    //     let mut fsa = &mut block1.fsa;
-    //     // println!("{}", f);
-    //     for j in off..=off+num_in_bucket as usize +2 {
-    //         if fsa[j] == f {
-    //             // println!("deleted!!! : {}", f);
-    //             fsa.remove(j);
-    //             // decrement FCA counter.
-    //             let mut fca = &mut block1.fca;
-    //             if fca.member((2 * lbi1) as u64) {
-    //                 if fca.member((2u64 * lbi1 as u64) + 1) {
-    //                     fca.delete(2 * lbi1 as u64);
-    //                 }
-    //                 else {
-    //                     fca.delete((2 * lbi1) as u64);
-    //                 }
-    //             }
-    //             else {
-    //                 if fca.member((2u64 * lbi1 as u64) + 1) {
-    //                     fca.delete((2u64 * lbi1 as u64) + 1);
-    //                     fca.insert((2 * lbi1) as u64);
-    //                 }
-    //             }
-    //             return true;
-    //         }
-    //     }
-    //
-    //     // fingerprint might be in other bucket
-    //
-    //     let glbi2 = self.hash2(glbi1 + lbi1, f);
-    //     let mut block2 = &mut self.block_store[(glbi2/BUCKETS_PER_BLOCK) as usize];
-    //     let lbi2 = glbi2 % BUCKETS_PER_BLOCK;
-    //
-    //     // calculate offset
-    //     let mut off2: usize = 0;
-    //     let mut i = 0;
-    //     loop {
-    //         if i > 2 * lbi2 {
-    //             break;
-    //         }
-    //         off2 += ((block2.fca.member(i as u64) as u32 & 1u32) + (2* (block2.fca.member((i + 1) as u64) as u32 & 1u32))) as usize;
-    //         i+=2
-    //     }
-    //     let mut num_in_bucket = (block2.fca.member(2 * (lbi2) as u64) as u32 & 1u32 + 2 * (block2.fca.member(((2 * (lbi2)) + 1) as u64) as u32 & 1u32)) as usize;
-    //     off2 = off2.saturating_sub(2);
-    //     off2 = off2.saturating_sub(num_in_bucket);
-    //
-    //     let mut fsa = &mut block2.fsa;
-    //     for j in off2..=off2+num_in_bucket+2 {
-    //         // println!("deleted!!! : {}", f);
-    //         fsa.remove(j);
-    //         // decrement FCA counter.
-    //         let mut fca = &mut block2.fca;
-    //         if fca.member((2 * lbi1) as u64) {
-    //             if fca.member((2u64 * lbi1 as u64) + 1) {
-    //                 // cannot happen
-    //                 fca.delete(2 * lbi1 as u64);
-    //             }
-    //             else {
-    //                 fca.delete((2 * lbi1) as u64);
-    //             }
-    //         }
-    //         else {
-    //             if fca.member((2u64 * lbi1 as u64) + 1) {
-    //                 fca.delete((2u64 * lbi1 as u64) + 1);
-    //                 fca.insert((2 * lbi1) as u64);
-    //             }
-    //         }
-    //         return true;
-    //     }
-    //     //element not here, return false as deletion unsuccessful.
-    //     return false;
-    // }
+impl<H: SerializableHasher> SerializableFilter for MortonFilter<H> {
+    const KIND: FilterKind = FilterKind::Morton;
+    const HASHER: HasherKind = H::KIND;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_u64(self.size);
+        enc.put_u64(self.max_kicks as u64);
+        enc.put_u64(self.victim.is_some() as u64);
+        let (bucket, victim) = self.victim.unwrap_or((0, 0));
+        enc.put_u64(bucket);
+        enc.put_u64(victim as u64);
+        // the eviction rng is saved mid stream, so a loaded filter kicks the same fingerprints as the original.
+        enc.put_u8s(&self.rng.get_seed());
+        enc.put_u64(self.rng.get_stream());
+        let word_pos = self.rng.get_word_pos();
+        enc.put_u64(word_pos as u64);
+        enc.put_u64((word_pos >> 64) as u64);
+        enc.put_hashers(&self.hashes);
+        let words: Vec<u64> = self.block_store.iter().flat_map(|block| block.words).collect();
+        enc.put_u64s(&words);
+    }
 
-    pub(crate) fn fingerprint(&self, key: u64) -> u8 {
-        return hash(key,8, self.hashes[0].0, self.hashes[0].1, self.hashes[0].2) as u8;
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let size = dec.get_u64()?;
+        let max_kicks = dec.get_usize()?;
+        let has_victim = dec.get_bool()?;
+        let victim_bucket = dec.get_u64()?;
+        let victim = dec.get_u64()?;
+        let rng_seed: [u8; 32] = dec.get_u8s()?.try_into()
+            .map_err(|_| SerializeError::Corrupt("rng seed is not 32 bytes"))?;
+        let stream = dec.get_u64()?;
+        let word_pos = dec.get_u64()? as u128 | (dec.get_u64()? as u128) << 64;
+        let hashes = dec.get_hashers(Some(2))?;
+        let words = dec.get_u64s()?;
+        ensure(words.len().is_multiple_of(8), "blocks are not whole")?;
+        let length = (words.len() / 8) as u64;
+        ensure(length > 0 && length.checked_mul(BUCKETS_PER_BLOCK as u64) == Some(size), "blocks do not match the size")?;
+        ensure(!has_victim || (victim_bucket < size && victim <= u8::MAX as u64), "victim out of range")?;
+        let block_store: Vec<MortonBlock> = words.chunks_exact(8).map(|block| {
+            return MortonBlock {
+                words: block.try_into().unwrap(),
+            };
+        }).collect();
+        ensure(block_store.iter().all(|block| block.len() <= SLOTS_PER_BLOCK), "block holds more fingerprints than slots")?;
+        let mut rng = ChaCha8Rng::from_seed(rng_seed);
+        rng.set_stream(stream);
+        rng.set_word_pos(word_pos);
+        return Ok(MortonFilter {
+            block_store,
+            hashes,
+            seed,
+            size,
+            max_kicks,
+            rng,
+            victim: if has_victim { Some((victim_bucket, victim as u8)) } else { None },
+        });
     }
+}
 
-    fn base_hash(&self, key: u64) -> u32 {
-        return hash(key,self.l, self.hashes[1].0, self.hashes[1].1, self.hashes[1].2);
+impl<H: KeyHasher> FilterStats for MortonFilter<H> {
+    // the fingerprint counts of every bucket, which the blocks keep up to date, and the victim.
+    fn len(&self) -> u64 {
+        return self.occupied() + self.victim.is_some() as u64;
     }
 
-    pub(crate) fn hash1(&self, key: u64) -> u32 {
-        return utils::map(self.base_hash(key) as u64, self.size) as u32;
+    fn capacity(&self) -> u64 {
+        return self.slots();
     }
 
-    pub(crate) fn hash2(&self, h1: u32, fingerprint: u8) -> u32 {
-        let y = -1_i32;
-        // return self.hash_prime(h1 as usize,fingerprint);
-        return utils::map_neg((h1 as i32 + (y.pow(h1 & 1) * self.offset(fingerprint) as i32)), self.size) as u32;
+    fn slots(&self) -> u64 {
+        return self.block_store.len() as u64 * SLOTS_PER_BLOCK as u64;
     }
 
-    pub(crate) fn hash_prime(&self, beta: usize, fingerprint: u8) -> u32 {
-        let y = -1_i32;
-        return utils::map_neg((beta as i32 + (y.pow((beta & 1) as u32) * self.offset(fingerprint) as i32)), self.size) as u32;
+    fn occupied(&self) -> u64 {
+        return self.block_store.iter().map(|block| block.len() as u64).sum();
     }
 
-    pub(crate) fn offset(&self, fingerprint: u8) -> u32 {
-        return (BUCKETS_PER_BLOCK + (fingerprint as u32 % OFF_RANGE)) | 1u32;
+    fn size_in_bits(&self) -> usize {
+        return self.block_store.len() * 512;
     }
 
-    fn hash_ota(&self, key: u64) -> usize {
-        return (hash(key, 4, self.hashes[3].0, self.hashes[3].1, self.hashes[3].2) % 16) as usize;
+    // a query compares its 8 bit fingerprint against the fingerprints in up to two buckets.
+    fn false_positive_rate(&self) -> f64 {
+        return filter::fingerprint_false_positive_rate(2.0 * self.len() as f64 / self.size as f64, 8);
     }
+}
 
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // once a delete frees a slot in the victim's other bucket, the victim goes straight there without any kicks.
+    #[test]
+    fn delete_places_victim_in_its_other_bucket() {
+        let mut tested = 0;
+        for seed in 0..200 {
+            let mut filter = MortonFilter::<MultiplyShift>::with_seed(1000, seed);
+            let mut keys = Vec::new();
+            let mut key = 1;
+            while !filter.is_full() {
+                filter.insert(key);
+                keys.push(key);
+                key += 1;
+            }
+            let (bucket, f) = filter.victim.unwrap();
+            let other = filter.alternate(bucket, f);
+            // a key whose delete takes its fingerprint out of other.
+            let in_other = keys.iter().copied().find(|&key| {
+                let (fk, h1) = (filter.fingerprint(key), filter.hash1(key));
+                let (block1, lbi1) = filter.block(h1);
+                let h2 = filter.alternate(h1, fk);
+                let (block2, lbi2) = filter.block(h2);
+                return if h1 == other {
+                    block1.find(lbi1, fk).is_some()
+                } else {
+                    h2 == other && block1.find(lbi1, fk).is_none() && block1.ota(lbi1 % OTA_BITS)
+                        && block2.find(lbi2, fk).is_some()
+                };
+            });
+            let Some(deleted) = in_other else { continue };
+            tested += 1;
+            let word_pos = filter.rng.get_word_pos();
+            assert!(filter.delete(deleted));
+            assert!(filter.victim.is_none());
+            assert_eq!(filter.rng.get_word_pos(), word_pos);
+            for &key in keys.iter().filter(|&&key| key != deleted) {
+                assert!(filter.member(key));
+            }
+        }
+        assert!(tested > 0);
+    }
+}
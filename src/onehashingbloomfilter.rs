@@ -0,0 +1,186 @@
+use crate::bitvector;
+
+use crate::utils;
+
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::filter::{self, DynamicFilter, Filter, FilterStats};
+use crate::serialize::{ensure, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
+
+/*
+One-hashing Bloom filter (Lu, Wan, Gao, Ji and Zhang, 2015).
+Like the partitioned Bloom filter the bits are split into k partitions, but there is only one hash function.
+Partition i has a prime number of bits p_i, all different, and the key sets bit h(x) mod p_i in it. By the Chinese
+remainder theorem the k residues of h(x) behave close to k independent hashes, so a key costs one hash instead of k.
+The primes are the largest k below m / k, so the filter is a little smaller than m.
+ */
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OneHashingBloomFilter<H = MultiplyShift> {
+    bit_array: bitvector::BitVector,
+    hasher: H,
+    seed: u64,
+    // the size of each partition, a prime, and the bit it starts at.
+    partitions: Vec<u64>,
+    offsets: Vec<u64>,
+}
+
+impl OneHashingBloomFilter {
+    pub fn new(expected_inserts: u64, false_positive_rate: f64) -> OneHashingBloomFilter {
+        return Self::with_hasher(expected_inserts, false_positive_rate);
+    }
+}
+
+impl<H: KeyHasher> OneHashingBloomFilter<H> {
+    // same as new, but draws the hash function from H instead of multiply-shift.
+    pub fn with_hasher(expected_inserts: u64, false_positive_rate: f64) -> Self {
+        return Self::with_seed(expected_inserts, false_positive_rate, rand::random());
+    }
+
+    // the same seed gives the same hash function, and so the same filter, on any machine.
+    pub fn with_seed(expected_inserts: u64, false_positive_rate: f64, seed: u64) -> Self {
+        let mut rng = utils::seeded_rng(seed);
+        let size: u64 = ((-1.44 * (expected_inserts as f64)).ceil()
+            * false_positive_rate.log2() + 0.5) as u64;
+        let num_partitions = ((-false_positive_rate.log2() + 0.5) as usize).max(1);
+        let partitions = Self::primes(size / num_partitions as u64, num_partitions);
+        let offsets = Self::offsets(&partitions);
+        OneHashingBloomFilter {
+            bit_array: bitvector::BitVector::new(partitions.iter().sum()),
+            hasher: H::from_rng(&mut rng),
+            seed,
+            partitions,
+            offsets,
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    // the largest count primes no bigger than limit, or the smallest above it if there are not enough below.
+    fn primes(limit: u64, count: usize) -> Vec<u64> {
+        let mut primes = Vec::new();
+        let mut candidate = limit;
+        while primes.len() < count && candidate >= 2 {
+            if utils::is_prime(candidate) {
+                primes.push(candidate);
+            }
+            candidate -= 1;
+        }
+        candidate = limit + 1;
+        while primes.len() < count {
+            if utils::is_prime(candidate) {
+                primes.push(candidate);
+            }
+            candidate += 1;
+        }
+        return primes;
+    }
+
+    fn offsets(partitions: &[u64]) -> Vec<u64> {
+        let mut offsets = Vec::new();
+        let mut offset = 0;
+        for partition in partitions {
+            offsets.push(offset);
+            offset += partition;
+        }
+        return offsets;
+    }
+
+    fn partition_bits_set(&self, partition: usize) -> u64 {
+        let start = self.offsets[partition];
+        let end = start + self.partitions[partition];
+        return (start..end).filter(|index| self.bit_array.member(*index)).count() as u64;
+    }
+}
+
+impl<H: KeyHasher> DynamicFilter for OneHashingBloomFilter<H> {
+    fn insert(&mut self, key: u64) -> bool {
+        let hash = self.hasher.hash(key);
+        for i in 0..self.partitions.len() {
+            self.bit_array.insert(self.offsets[i] + hash % self.partitions[i]);
+        }
+        return true;
+    }
+}
+
+impl<H: KeyHasher> Filter for OneHashingBloomFilter<H> {
+    fn member(&self, key: u64) -> bool {
+        let hash = self.hasher.hash(key);
+        for i in 0..self.partitions.len() {
+            if !self.bit_array.member(self.offsets[i] + hash % self.partitions[i]) {
+                return false;
+            }
+        }
+        return true;
+    }
+}
+
+impl<H: KeyHasher> FilterStats for OneHashingBloomFilter<H> {
+    // every key sets one bit in each partition, so each partition gives an estimate of its own. this is their mean.
+    fn len(&self) -> u64 {
+        let mut n = 0.0;
+        for i in 0..self.partitions.len() {
+            n += filter::swamidass_baldi(self.partition_bits_set(i), self.partitions[i], 1);
+        }
+        return (n / self.partitions.len() as f64).round() as u64;
+    }
+
+    // the smallest partition is the first to fill.
+    fn capacity(&self) -> u64 {
+        return filter::half_full(*self.partitions.iter().min().unwrap(), 1);
+    }
+
+    fn slots(&self) -> u64 {
+        return self.partitions.iter().sum();
+    }
+
+    fn occupied(&self) -> u64 {
+        return self.bit_array.count_ones();
+    }
+
+    fn size_in_bits(&self) -> usize {
+        return self.bit_array.array.len() * 8;
+    }
+
+    // a key not in the filter is a false positive if its bit is set in every partition.
+    fn false_positive_rate(&self) -> f64 {
+        let mut rate = 1.0;
+        for i in 0..self.partitions.len() {
+            rate *= self.partition_bits_set(i) as f64 / self.partitions[i] as f64;
+        }
+        return rate;
+    }
+}
+
+impl<H: SerializableHasher> SerializableFilter for OneHashingBloomFilter<H> {
+    const KIND: FilterKind = FilterKind::OneHashingBloom;
+    const HASHER: HasherKind = H::KIND;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_hashers(std::slice::from_ref(&self.hasher));
+        enc.put_u64s(&self.partitions);
+        enc.put_u8s(&self.bit_array.array);
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let hasher = dec.get_hashers(Some(1))?.pop().unwrap();
+        let partitions = dec.get_u64s()?;
+        let array = dec.get_u8s()?;
+        ensure(!partitions.is_empty(), "no partitions")?;
+        ensure(partitions.iter().all(|partition| *partition > 0), "empty partition")?;
+        let bits = partitions.iter().try_fold(0u64, |bits, partition| bits.checked_add(*partition));
+        ensure(bits.map(|bits| bits.div_ceil(8)) == Some(array.len() as u64), "bit array does not match the partitions")?;
+        let offsets = Self::offsets(&partitions);
+        return Ok(OneHashingBloomFilter {
+            bit_array: bitvector::BitVector { array },
+            hasher,
+            seed,
+            partitions,
+            offsets,
+        });
+    }
+}
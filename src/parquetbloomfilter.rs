@@ -0,0 +1,417 @@
+use std::io::{self, Write};
+
+use xxhash_rust::xxh64::xxh64;
+
+use crate::filter::{self, DynamicFilter, Filter, FilterStats};
+use crate::serialize::{ensure, SerializeError};
+use crate::simdblockedbloomfilter::{self, Block, Kernel};
+
+/*
+The split block Bloom filter from the Parquet format spec (parquet-format BloomFilter.md), bit for bit.
+It is the same filter as SimdBlockedBloomFilter, and shares its kernels, but the hash is fixed by the spec: xxHash64
+with seed 0 over the plain encoding of the value. The top 32 bits of the hash pick the block, the low 32 bits set the
+lanes. So this filter has no seed and is not generic over a KeyHasher.
+A u64 key is hashed as a Parquet INT64 (its 8 little endian bytes), and a byte string key as a BYTE_ARRAY (the bytes
+themselves). Values of any other physical type can be hashed by the caller and passed to insert_hash.
+The generic insert_key and contains do not know the Parquet type: they reduce the key to a u64 first and look that up
+as an INT64. So contains(&"abc") does not find a string added with insert_bytes(b"abc"), or one written by another
+Parquet writer, and checking a string column that way gives false negatives. Use contains_bytes for BYTE_ARRAY values.
+
+In a Parquet file the filter is a BloomFilterHeader, thrift compact encoded, followed by the bitset: the blocks in
+order, each as eight little endian u32s. The bitset is a power of two bytes long, between 32 bytes and 128 MiB.
+
+Test vectors, checked against the parquet crate from arrow-rs, and by the tests at the end of this file:
+    xxHash64 with seed 0 of "" is 0xef46db3751d8e999, of "a" 0xd24ec4f1a98c6e5b, of "abc" 0x44bc2cf5ad770999.
+    the INT64 1 hashes to 0x9f29cb17a2a49995.
+    a 32 byte filter holding only "abc" has the lanes
+        00002000 00000800 00800000 00200000 00000040 00004000 20000000 20000000.
+    the header of a 1024 byte filter is 15 80 10 1c 1c 00 00 1c 1c 00 00 1c 1c 00 00 00.
+ */
+
+pub const BITSET_MIN_BYTES: usize = 32;
+pub const BITSET_MAX_BYTES: usize = 128 * 1024 * 1024;
+
+// thrift compact types.
+const TYPE_I32: u8 = 5;
+const TYPE_STRUCT: u8 = 12;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParquetBloomFilter {
+    pub(crate) blocks: Vec<Block>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    kernel: Kernel,
+}
+
+impl ParquetBloomFilter {
+    // sized the way the Parquet writers size it, m = -8n / ln(1 - p^(1/8)) bits, rounded up to a power of two bytes.
+    pub fn new(expected_inserts: u64, false_positive_rate: f64) -> ParquetBloomFilter {
+        let bits = -8.0 * expected_inserts as f64 / (1.0 - false_positive_rate.powf(1.0 / 8.0)).ln();
+        return Self::with_num_bytes(bits as usize / 8);
+    }
+
+    // num_bytes is rounded up to a power of two, and kept within what the spec allows.
+    pub fn with_num_bytes(num_bytes: usize) -> ParquetBloomFilter {
+        let num_bytes = num_bytes.clamp(BITSET_MIN_BYTES, BITSET_MAX_BYTES).next_power_of_two();
+        return ParquetBloomFilter {
+            blocks: vec![Block::default(); num_bytes / 32],
+            kernel: Kernel::detect(),
+        };
+    }
+
+    // the filter stored in a bitset read from a Parquet file.
+    pub fn from_bitset(bitset: &[u8]) -> Result<ParquetBloomFilter, SerializeError> {
+        ensure(bitset.len().is_power_of_two() && (BITSET_MIN_BYTES..=BITSET_MAX_BYTES).contains(&bitset.len()),
+               "bitset length is not a power of two between 32 bytes and 128 MiB")?;
+        let mut blocks = Vec::new();
+        for chunk in bitset.chunks_exact(32) {
+            let mut block = Block::default();
+            for i in 0..8 {
+                block.0[i] = u32::from_le_bytes(chunk[i * 4..i * 4 + 4].try_into().unwrap());
+            }
+            blocks.push(block);
+        }
+        return Ok(ParquetBloomFilter {
+            blocks,
+            kernel: Kernel::detect(),
+        });
+    }
+
+    // a filter as stored at a column chunk's bloom_filter_offset: the header, then the bitset. returns the filter
+    // and the number of bytes it took up.
+    pub fn from_parquet_bytes(bytes: &[u8]) -> Result<(ParquetBloomFilter, usize), SerializeError> {
+        let mut reader = ThriftReader {
+            bytes,
+            position: 0,
+        };
+        let num_bytes = reader.header()?;
+        let end = reader.position + num_bytes;
+        if end > bytes.len() {
+            return Err(SerializeError::Truncated);
+        }
+        let filter = Self::from_bitset(&bytes[reader.position..end])?;
+        return Ok((filter, end));
+    }
+
+    // the hash the spec gives a value, from its plain encoding.
+    pub fn hash_bytes(bytes: &[u8]) -> u64 {
+        return xxh64(bytes, 0);
+    }
+
+    pub fn insert_hash(&mut self, hash: u64) {
+        let block = self.block_index(hash);
+        self.kernel.insert(&mut self.blocks[block], hash as u32);
+    }
+
+    pub fn contains_hash(&self, hash: u64) -> bool {
+        return self.kernel.check(&self.blocks[self.block_index(hash)], hash as u32);
+    }
+
+    pub fn num_bytes(&self) -> usize {
+        return self.blocks.len() * 32;
+    }
+
+    // the bitset as Parquet stores it.
+    pub fn bitset(&self) -> Vec<u8> {
+        let mut bitset = Vec::with_capacity(self.num_bytes());
+        for block in &self.blocks {
+            for lane in block.0 {
+                bitset.extend_from_slice(&lane.to_le_bytes());
+            }
+        }
+        return bitset;
+    }
+
+    // the BloomFilterHeader, thrift compact encoded. fields 2, 3 and 4 are unions, each set to its first variant,
+    // an empty struct: BLOCK, XXHASH and UNCOMPRESSED.
+    pub fn header(&self) -> Vec<u8> {
+        let mut header = vec![1 << 4 | TYPE_I32];
+        let mut n = ((self.num_bytes() as i32) << 1) as u32;
+        while n >= 0x80 {
+            header.push(n as u8 | 0x80);
+            n >>= 7;
+        }
+        header.push(n as u8);
+        for _ in 2..=4 {
+            header.extend_from_slice(&[1 << 4 | TYPE_STRUCT, 1 << 4 | TYPE_STRUCT, 0, 0]);
+        }
+        header.push(0);
+        return header;
+    }
+
+    // the header and then the bitset, ready to be written into a Parquet file.
+    pub fn to_parquet_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.header();
+        bytes.extend(self.bitset());
+        return bytes;
+    }
+
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.header())?;
+        return writer.write_all(&self.bitset());
+    }
+
+    fn block_index(&self, hash: u64) -> usize {
+        return (((hash >> 32) * self.blocks.len() as u64) >> 32) as usize;
+    }
+}
+
+impl DynamicFilter for ParquetBloomFilter {
+    // the key as a Parquet INT64.
+    fn insert(&mut self, key: u64) -> bool {
+        self.insert_hash(Self::hash_bytes(&key.to_le_bytes()));
+        return true;
+    }
+
+    // the key as a Parquet BYTE_ARRAY, rather than reduced to a u64 first.
+    fn insert_bytes(&mut self, key: &[u8]) -> bool {
+        self.insert_hash(Self::hash_bytes(key));
+        return true;
+    }
+}
+
+// contains is left as the trait has it, over the reduced key, so it only agrees with insert_key. see above.
+impl Filter for ParquetBloomFilter {
+    fn member(&self, key: u64) -> bool {
+        return self.contains_hash(Self::hash_bytes(&key.to_le_bytes()));
+    }
+
+    fn contains_bytes(&self, key: &[u8]) -> bool {
+        return self.contains_hash(Self::hash_bytes(key));
+    }
+}
+
+/*
+Just enough of the thrift compact protocol to read a BloomFilterHeader. Fields this crate does not know are skipped,
+so a header written by a newer writer still reads, as long as the algorithm, hash and compression are ones we support.
+ */
+struct ThriftReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl ThriftReader<'_> {
+    // the num_bytes field of the header, after checking the rest of it describes a filter this crate can read.
+    fn header(&mut self) -> Result<usize, SerializeError> {
+        let mut num_bytes = None;
+        let mut last_id = 0;
+        while let Some((id, kind)) = self.field(&mut last_id)? {
+            match (id, kind) {
+                (1, TYPE_I32) => num_bytes = Some(self.varint()?),
+                (2, TYPE_STRUCT) => ensure(self.union()? == 1, "bloom filter algorithm is not split block")?,
+                (3, TYPE_STRUCT) => ensure(self.union()? == 1, "bloom filter hash is not xxHash64")?,
+                (4, TYPE_STRUCT) => ensure(self.union()? == 1, "bloom filter is compressed")?,
+                _ => self.skip(kind, 0)?,
+            }
+        }
+        let num_bytes = num_bytes.ok_or(SerializeError::Corrupt("bloom filter header has no length"))?;
+        // zigzag decode.
+        let num_bytes = (num_bytes >> 1) as i64 ^ -((num_bytes & 1) as i64);
+        ensure(num_bytes > 0 && num_bytes as usize <= BITSET_MAX_BYTES, "bloom filter length out of range")?;
+        return Ok(num_bytes as usize);
+    }
+
+    // the id of the variant a union holds. its value is skipped.
+    fn union(&mut self) -> Result<i16, SerializeError> {
+        let mut last_id = 0;
+        let (id, kind) = self.field(&mut last_id)?.ok_or(SerializeError::Corrupt("empty union"))?;
+        self.skip(kind, 0)?;
+        ensure(self.field(&mut last_id)?.is_none(), "union has more than one field")?;
+        return Ok(id);
+    }
+
+    // the next field's id and type, or None at the end of the struct.
+    fn field(&mut self, last_id: &mut i16) -> Result<Option<(i16, u8)>, SerializeError> {
+        let byte = self.byte()?;
+        if byte == 0 {
+            return Ok(None);
+        }
+        let delta = byte >> 4;
+        let id = if delta == 0 {
+            let n = self.varint()?;
+            ((n >> 1) as i64 ^ -((n & 1) as i64)) as i16
+        } else {
+            last_id.wrapping_add(delta as i16)
+        };
+        *last_id = id;
+        return Ok(Some((id, byte & 0x0f)));
+    }
+
+    fn skip(&mut self, kind: u8, depth: u32) -> Result<(), SerializeError> {
+        ensure(depth < 64, "thrift nesting too deep")?;
+        match kind {
+            // booleans carry their value in the type.
+            1 | 2 => {}
+            3 => {
+                self.byte()?;
+            }
+            4..=6 => {
+                self.varint()?;
+            }
+            7 => self.advance(8)?,
+            8 => {
+                let len = self.varint()? as usize;
+                self.advance(len)?;
+            }
+            9 | 10 => {
+                let byte = self.byte()?;
+                let mut len = (byte >> 4) as u64;
+                if len == 15 {
+                    len = self.varint()?;
+                }
+                self.check_count(len)?;
+                for _ in 0..len {
+                    self.skip_element(byte & 0x0f, depth + 1)?;
+                }
+            }
+            11 => {
+                let len = self.varint()?;
+                self.check_count(len)?;
+                if len > 0 {
+                    let types = self.byte()?;
+                    for _ in 0..len {
+                        self.skip_element(types >> 4, depth + 1)?;
+                        self.skip_element(types & 0x0f, depth + 1)?;
+                    }
+                }
+            }
+            TYPE_STRUCT => {
+                let mut last_id = 0;
+                while let Some((_, kind)) = self.field(&mut last_id)? {
+                    self.skip(kind, depth + 1)?;
+                }
+            }
+            _ => return Err(SerializeError::Corrupt("unknown thrift type")),
+        }
+        return Ok(());
+    }
+
+    // inside a list, set or map a boolean is a byte of its own, rather than part of a field's type.
+    fn skip_element(&mut self, kind: u8, depth: u32) -> Result<(), SerializeError> {
+        if kind == 1 || kind == 2 {
+            self.byte()?;
+            return Ok(());
+        }
+        return self.skip(kind, depth);
+    }
+
+    // every element takes at least a byte, so a container cannot hold more elements than there are bytes left. this
+    // stops a forged count from keeping the reader busy.
+    fn check_count(&self, len: u64) -> Result<(), SerializeError> {
+        return ensure(len <= (self.bytes.len() - self.position) as u64, "thrift container longer than the input");
+    }
+
+    fn varint(&mut self) -> Result<u64, SerializeError> {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        return Err(SerializeError::Corrupt("varint too long"));
+    }
+
+    fn byte(&mut self) -> Result<u8, SerializeError> {
+        let byte = *self.bytes.get(self.position).ok_or(SerializeError::Truncated)?;
+        self.position += 1;
+        return Ok(byte);
+    }
+
+    fn advance(&mut self, n: usize) -> Result<(), SerializeError> {
+        if self.bytes.len() - self.position < n {
+            return Err(SerializeError::Truncated);
+        }
+        self.position += n;
+        return Ok(());
+    }
+}
+
+impl FilterStats for ParquetBloomFilter {
+    fn len(&self) -> u64 {
+        return simdblockedbloomfilter::estimate_keys(&self.blocks).round() as u64;
+    }
+
+    fn capacity(&self) -> u64 {
+        return filter::half_full(self.slots(), 8);
+    }
+
+    fn slots(&self) -> u64 {
+        return self.blocks.len() as u64 * 256;
+    }
+
+    fn occupied(&self) -> u64 {
+        return simdblockedbloomfilter::bits_set(&self.blocks);
+    }
+
+    fn size_in_bits(&self) -> usize {
+        return self.blocks.len() * 256;
+    }
+
+    fn false_positive_rate(&self) -> f64 {
+        return simdblockedbloomfilter::current_false_positive_rate(&self.blocks);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xxhash_vectors() {
+        assert_eq!(ParquetBloomFilter::hash_bytes(b""), 0xef46db3751d8e999);
+        assert_eq!(ParquetBloomFilter::hash_bytes(b"a"), 0xd24ec4f1a98c6e5b);
+        assert_eq!(ParquetBloomFilter::hash_bytes(b"abc"), 0x44bc2cf5ad770999);
+        assert_eq!(ParquetBloomFilter::hash_bytes(&1u64.to_le_bytes()), 0x9f29cb17a2a49995);
+    }
+
+    #[test]
+    fn abc_lanes() {
+        let mut filter = ParquetBloomFilter::with_num_bytes(32);
+        filter.insert_bytes(b"abc");
+        assert_eq!(filter.blocks[0].0, [0x00002000, 0x00000800, 0x00800000, 0x00200000, 0x00000040, 0x00004000,
+            0x20000000, 0x20000000]);
+        assert!(filter.contains_bytes(b"abc"));
+    }
+
+    #[test]
+    fn header_vector() {
+        let filter = ParquetBloomFilter::with_num_bytes(1024);
+        assert_eq!(filter.header(), [0x15, 0x80, 0x10, 0x1c, 0x1c, 0x00, 0x00, 0x1c, 0x1c, 0x00, 0x00, 0x1c, 0x1c, 0x00,
+            0x00, 0x00]);
+    }
+
+    // a list of booleans whose count is far more than the bytes left.
+    #[test]
+    fn forged_list_count_is_rejected() {
+        let bytes = [0x15, 0x40, 0x49, 0xf1, 0x80, 0x80, 0x80, 0x80, 0x80, 0x20, 0x00];
+        assert!(ParquetBloomFilter::from_parquet_bytes(&bytes).is_err());
+    }
+
+    // an unknown field 5, a list of two booleans, is skipped a byte per boolean.
+    #[test]
+    fn unknown_bool_list_is_skipped() {
+        let mut bytes = ParquetBloomFilter::with_num_bytes(32).header();
+        let stop = bytes.pop();
+        bytes.extend_from_slice(&[0x19, 0x21, 0x01, 0x02]);
+        bytes.extend(stop);
+        bytes.extend_from_slice(&[0; 32]);
+        let (filter, len) = ParquetBloomFilter::from_parquet_bytes(&bytes).unwrap();
+        assert_eq!((filter.num_bytes(), len), (32, bytes.len()));
+    }
+
+    #[test]
+    fn parquet_bytes_round_trip() {
+        let mut filter = ParquetBloomFilter::new(1000, 0.01);
+        for key in 0..1000 {
+            filter.insert(key);
+        }
+        filter.insert_bytes(b"abc");
+        let bytes = filter.to_parquet_bytes();
+        let (read, len) = ParquetBloomFilter::from_parquet_bytes(&bytes).unwrap();
+        assert_eq!(len, bytes.len());
+        assert_eq!(read.bitset(), filter.bitset());
+        assert!((0..1000).all(|key| read.member(key)));
+        assert!(read.contains_bytes(b"abc"));
+    }
+}
@@ -0,0 +1,165 @@
+use rand_chacha::ChaCha8Rng;
+
+use crate::bitvector;
+
+use crate::utils;
+
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::filter::{self, DynamicFilter, Filter, FilterStats};
+use crate::serialize::{ensure, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
+
+/*
+Partitioned Bloom filter (Chang, Feng and Li, 2004; used as the stages of the scalable Bloom filter by Almeida et al.).
+The m bits are split into k slices of m / k bits, and hash function i sets one bit in slice i only. Every key so sets
+exactly k distinct bits, where a standard Bloom filter sets k bits anywhere and some of them may coincide.
+Its false positive rate is a little higher than a standard Bloom filter of the same size, but each slice fills
+independently, which makes the fill of the filter and its rate easy to reason about.
+ */
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PartitionedBloomFilter<H = MultiplyShift> {
+    bit_array: bitvector::BitVector,
+    // one per slice.
+    hash_functions: Vec<H>,
+    seed: u64,
+    slice_size: u64,
+}
+
+impl PartitionedBloomFilter {
+    pub fn new(expected_inserts: u64, false_positive_rate: f64) -> PartitionedBloomFilter {
+        return Self::with_hasher(expected_inserts, false_positive_rate);
+    }
+}
+
+impl<H: KeyHasher> PartitionedBloomFilter<H> {
+    // same as new, but draws the hash functions from H instead of multiply-shift.
+    pub fn with_hasher(expected_inserts: u64, false_positive_rate: f64) -> Self {
+        return Self::with_seed(expected_inserts, false_positive_rate, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    pub fn with_seed(expected_inserts: u64, false_positive_rate: f64, seed: u64) -> Self {
+        let mut rng = utils::seeded_rng(seed);
+        let size: u64 = ((-1.44 * (expected_inserts as f64)).ceil()
+            * false_positive_rate.log2() + 0.5) as u64;
+        let num_slices = ((-false_positive_rate.log2() + 0.5) as u64).max(1);
+        let slice_size = size.div_ceil(num_slices).max(1);
+        PartitionedBloomFilter {
+            bit_array: bitvector::BitVector::new(slice_size * num_slices),
+            hash_functions: Self::generate_hash_functions(num_slices as usize, &mut rng),
+            seed,
+            slice_size,
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    fn generate_hash_functions(n: usize, rng: &mut ChaCha8Rng) -> Vec<H> {
+        let mut hash_functions = Vec::new();
+        for _ in 0..n {
+            hash_functions.push(H::from_rng(rng));
+        }
+        return hash_functions;
+    }
+
+    // the bit the key sets in the slice. the 64 bit hash is scaled to the slice, rather than taken modulo its size, in
+    // 128 bits so that a slice of more than 2^32 bits neither overflows nor leaves bits that no key can reach.
+    fn index(&self, key: u64, slice: usize) -> u64 {
+        let hash = self.hash_functions[slice].hash(key) as u128;
+        return slice as u64 * self.slice_size + ((hash * self.slice_size as u128) >> 64) as u64;
+    }
+
+    fn slice_bits_set(&self, slice: usize) -> u64 {
+        let start = slice as u64 * self.slice_size;
+        return (start..start + self.slice_size).filter(|index| self.bit_array.member(*index)).count() as u64;
+    }
+}
+
+impl<H: KeyHasher> DynamicFilter for PartitionedBloomFilter<H> {
+    fn insert(&mut self, key: u64) -> bool {
+        for slice in 0..self.hash_functions.len() {
+            let index = self.index(key, slice);
+            self.bit_array.insert(index);
+        }
+        return true;
+    }
+}
+
+impl<H: KeyHasher> Filter for PartitionedBloomFilter<H> {
+    fn member(&self, key: u64) -> bool {
+        for slice in 0..self.hash_functions.len() {
+            if !self.bit_array.member(self.index(key, slice)) {
+                return false;
+            }
+        }
+        return true;
+    }
+}
+
+impl<H: KeyHasher> FilterStats for PartitionedBloomFilter<H> {
+    // every key sets one bit in each slice, so each slice gives an estimate of its own. this is their mean.
+    fn len(&self) -> u64 {
+        let mut n = 0.0;
+        for slice in 0..self.hash_functions.len() {
+            n += filter::swamidass_baldi(self.slice_bits_set(slice), self.slice_size, 1);
+        }
+        return (n / self.hash_functions.len() as f64).round() as u64;
+    }
+
+    fn capacity(&self) -> u64 {
+        return filter::half_full(self.slice_size, 1);
+    }
+
+    fn slots(&self) -> u64 {
+        return self.slice_size * self.hash_functions.len() as u64;
+    }
+
+    fn occupied(&self) -> u64 {
+        return self.bit_array.count_ones();
+    }
+
+    fn size_in_bits(&self) -> usize {
+        return self.bit_array.array.len() * 8;
+    }
+
+    // a key not in the filter is a false positive if its bit is set in every slice.
+    fn false_positive_rate(&self) -> f64 {
+        let mut rate = 1.0;
+        for slice in 0..self.hash_functions.len() {
+            rate *= self.slice_bits_set(slice) as f64 / self.slice_size as f64;
+        }
+        return rate;
+    }
+}
+
+impl<H: SerializableHasher> SerializableFilter for PartitionedBloomFilter<H> {
+    const KIND: FilterKind = FilterKind::PartitionedBloom;
+    const HASHER: HasherKind = H::KIND;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_u64(self.slice_size);
+        enc.put_hashers(&self.hash_functions);
+        enc.put_u8s(&self.bit_array.array);
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let slice_size = dec.get_u64()?;
+        let hash_functions = dec.get_hashers(None)?;
+        let array = dec.get_u8s()?;
+        ensure(slice_size > 0 && slice_size <= u32::MAX as u64, "slice size out of range")?;
+        ensure(!hash_functions.is_empty(), "no hash functions")?;
+        ensure(slice_size.checked_mul(hash_functions.len() as u64).map(|bits| bits.div_ceil(8)) == Some(array.len() as u64),
+               "bit array does not match the slices")?;
+        return Ok(PartitionedBloomFilter {
+            bit_array: bitvector::BitVector { array },
+            hash_functions,
+            seed,
+            slice_size,
+        });
+    }
+}
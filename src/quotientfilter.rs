@@ -1,53 +1,140 @@
-use rand::Rng;
-#[path = "quotientinfo.rs"]
-mod quotientinfo;
+use crate::quotientinfo;
 
-#[path = "utils.rs"]
-mod utils;
+use crate::utils;
 
 use quotientinfo::QuotientInfo;
-use utils::{hash};
 
-pub struct QuotientFilter {
+use rand_chacha::ChaCha8Rng;
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::filter::{self, DynamicFilter, Filter, FilterStats};
+use crate::serialize::{ensure, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuotientFilter<H = MultiplyShift> {
     pub(crate) buckets: Vec<u32>,
     bucket_info: Vec<QuotientInfo>,
-    hashes: Vec<(u64,u64,u64)>,
+    hashes: Vec<H>,
+    seed: u64,
     size: u64,
     r: u8
 }
 
 impl QuotientFilter {
     pub fn new(size: u64) -> QuotientFilter {
+        return Self::with_hasher(size);
+    }
+}
+
+impl<H: KeyHasher> QuotientFilter<H> {
+    // same as new, but draws the hash functions from H instead of multiply-shift.
+    pub fn with_hasher(size: u64) -> Self {
+        return Self::with_seed(size, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    pub fn with_seed(size: u64, seed: u64) -> Self {
+        let mut rng = utils::seeded_rng(seed);
         let length: usize = (size) as usize;
         return QuotientFilter {
             buckets: vec![0u32; length],
             bucket_info: Self::generate_info(length),
-            hashes: Self::generate_hash_functions(1),
+            hashes: Self::generate_hash_functions(1, &mut rng),
+            seed,
             size,
             r: utils::log_base(size as f64, 2f64) as u8
         };
     }
 
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
     fn generate_info(size: usize) -> Vec<QuotientInfo> {
         let mut infos = Vec::new();
-        for i in 0..size {
+        for _i in 0..size {
             infos.push(QuotientInfo::new());
         }
         return infos;
     }
 
-    fn generate_hash_functions(n: u64) -> Vec<(u64, u64,u64)> {
-        let mut rng = rand::thread_rng();
+    fn generate_hash_functions(n: u64, rng: &mut ChaCha8Rng) -> Vec<H> {
         let mut hash_functions = Vec::new();
         for _ in 0..n {
-            let a1: u64 = rng.gen_range(1..=u64::MAX );
-            let a2: u64 = rng.gen_range(1..=u64::MAX);
-            let b: u64 = rng.gen_range(1..=u64::MAX);
-            hash_functions.push((a1,a2,b));
+            hash_functions.push(H::from_rng(rng));
         }
         return hash_functions;
     }
 
+    fn fingerprint(&self, x: u64) -> u32 {
+        return self.hashes[0].hash_bits(x, 32);
+    }
+
+    fn get_quotient(&self, fingerprint: u32) -> u32 {
+        // get highest r bits
+        return (fingerprint << self.r) % self.size as u32;
+    }
+
+    fn get_remainder(&self, fingerprint: u32) -> u32 {
+        //get lowest r bits
+        return (fingerprint & ((1 << self.r) - 1)) % self.size as u32;
+    }
+}
+
+impl<H: KeyHasher> Filter for QuotientFilter<H> {
+    fn member(&self, x: u64) -> bool {
+        let f = self.fingerprint(x);
+        let q = self.get_quotient(f);
+        let r = self.get_remainder(f);
+
+        if !self.bucket_info[q as usize].is_occupied {
+            // there are no fingerprints in the filter that map to this bucket.
+            return false;
+        }
+        else if self.buckets[q as usize] == r {
+            // quick check. speeds up some queries
+            return true;
+        }
+        else if self.buckets[(q + 1) as usize] == r {
+            return true;
+        }
+
+
+        let mut b = q;
+        while self.bucket_info[b as usize].is_shifted && b > 0 {
+            b -= 1;
+        }
+
+        let mut s = b;
+        let mut i = 0;
+        while b != q {
+            if i > 10 {
+                break;
+            }
+            while !self.bucket_info[s as usize].is_continuation && (s as u64) < self.size - 1 {
+                s += 1;
+            }
+            while !self.bucket_info[b as usize].is_occupied && (b as u64) < self.size - 1{
+                b += 1;
+            }
+            i += 1;
+        }
+        // it is possible for this to take a while.
+        while !self.bucket_info[s as usize].is_continuation && (s as u64) < self.size - 1 {
+            // println!("{}, {}", self.buckets[s as usize], r);
+            if self.buckets[s as usize] == r {
+                return true;
+            }
+            s += 1;
+        }
+        if self.buckets[s as usize] == r {
+            return true;
+        }
+        // it's not in the filter.
+        return false;
+    }
+}
+
+impl<H: KeyHasher> DynamicFilter for QuotientFilter<H> {
     /*
     To insert/delete a fingerprint f,first we mark/unmark A[f_q] as occupied.
     Next, we search for f_r using the same algorithm as May-Contain to
@@ -57,7 +144,7 @@ impl QuotientFilter {
     as we reach an empty slot.
      */
 
-    pub fn insert(&mut self, x: u64) -> bool {
+    fn insert(&mut self, x: u64) -> bool {
         let fingerprint = self.fingerprint(x);
         let q = self.get_quotient(fingerprint);
         let r = self.get_remainder(fingerprint);
@@ -83,7 +170,7 @@ impl QuotientFilter {
             }
 
             // if insertion index is out of bounds, return false.
-            if !(ins < (self.size - 1) as u32) {
+            if ins >= (self.size - 1) as u32  {
                 return false;
             }
         }
@@ -114,7 +201,6 @@ impl QuotientFilter {
                 if self.bucket_info[i as usize].is_occupied {
                     self.buckets[i as usize] = self.buckets[(i + 1) as usize];
                     self.bucket_info[i as usize].is_shifted = true;
-                    self.bucket_info[i as usize].is_occupied = self.bucket_info[(i) as usize].is_occupied;
                     self.bucket_info[i as usize].is_continuation = self.bucket_info[(i + 1) as usize].is_continuation;
                 }
                 i += 1;
@@ -125,79 +211,82 @@ impl QuotientFilter {
             self.bucket_info[last_occupied_bucket as usize].is_occupied = true;
             self.bucket_info[last_occupied_bucket as usize].is_shifted = true;
             // TODO may need to improve condition on setting this.
-            self.bucket_info[last_occupied_bucket as usize].is_continuation = !(last_occupied_bucket == q);
+            self.bucket_info[last_occupied_bucket as usize].is_continuation = last_occupied_bucket != q ;
 
             return true;
         }
     }
+}
 
-    pub fn member(&self, x: u64) -> bool {
-        let f = self.fingerprint(x);
-        let q = self.get_quotient(f);
-        let r = self.get_remainder(f);
+impl<H: SerializableHasher> SerializableFilter for QuotientFilter<H> {
+    const KIND: FilterKind = FilterKind::Quotient;
+    const HASHER: HasherKind = H::KIND;
 
-        if !self.bucket_info[q as usize].is_occupied {
-            // there are no fingerprints in the filter that map to this bucket.
-            return false;
-        }
-        else if self.buckets[q as usize] == r {
-            // quick check. speeds up some queries
-            return true;
-        }
-        else if self.buckets[(q + 1) as usize] == r {
-            return true;
-        }
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_u64(self.size);
+        enc.put_u64(self.r as u64);
+        enc.put_hashers(&self.hashes);
+        enc.put_u32s(&self.buckets);
+        let metadata: Vec<u8> = self.bucket_info.iter().map(|info| {
+            return info.is_occupied as u8 | (info.is_shifted as u8) << 1 | (info.is_continuation as u8) << 2;
+        }).collect();
+        enc.put_u8s(&metadata);
+    }
 
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let size = dec.get_u64()?;
+        let r = dec.get_u64()?;
+        let hashes = dec.get_hashers(Some(1))?;
+        let buckets = dec.get_u32s()?;
+        let metadata = dec.get_u8s()?;
+        ensure(size as u32 != 0, "size is zero")?;
+        ensure(r < 32, "r out of range")?;
+        ensure(buckets.len() as u64 == size && metadata.len() as u64 == size, "buckets do not match the size")?;
+        ensure(metadata.iter().all(|m| *m < 8), "unknown metadata bits")?;
+        let bucket_info = metadata.iter().map(|m| {
+            return QuotientInfo {
+                is_occupied: m & 1 != 0,
+                is_shifted: m & 2 != 0,
+                is_continuation: m & 4 != 0,
+            };
+        }).collect();
+        return Ok(QuotientFilter {
+            buckets,
+            bucket_info,
+            hashes,
+            seed,
+            size,
+            r: r as u8,
+        });
+    }
+}
 
-        let mut b = q;
-        while self.bucket_info[b as usize].is_shifted && b > 0 {
-            b = b - 1;
-        }
+impl<H: KeyHasher> FilterStats for QuotientFilter<H> {
+    // every remainder stored takes one slot, marked occupied.
+    fn len(&self) -> u64 {
+        return self.occupied();
+    }
 
-        let mut s = b;
-        let mut i = 0;
-        while b != q {
-            if i > 10 {
-                break;
-            }
-            while !self.bucket_info[s as usize].is_continuation && (s as u64) < self.size - 1 {
-                s = s + 1;
-            }
-            while !self.bucket_info[b as usize].is_occupied && (b as u64) < self.size - 1{
-                b = b + 1;
-            }
-            i += 1;
-        }
-        // it is possible for this to take a while.
-        let mut j = 0;
-        while !self.bucket_info[s as usize].is_continuation && (s as u64) < self.size - 1 {
-            // println!("{}, {}", self.buckets[s as usize], r);
-            j = j + 1;
-            if self.buckets[s as usize] == r {
-                // println!("{}", j);
-                return true;
-            }
-            s = s + 1;
-        }
-        // println!("{}", j);
-        if self.buckets[s as usize] == r {
-            return true;
-        }
-        // it's not in the filter.
-        return false;
+    fn capacity(&self) -> u64 {
+        return self.size;
     }
 
-    fn fingerprint(&self, x: u64) -> u32 {
-        return utils::hash(x,32,self.hashes[0].0, self.hashes[0].1, self.hashes[0].2);
+    fn slots(&self) -> u64 {
+        return self.size;
     }
 
-    fn get_quotient(&self, fingerprint: u32) -> u32 {
-        // get highest r bits
-        return (fingerprint << self.r) % self.size as u32;
+    fn occupied(&self) -> u64 {
+        return self.bucket_info.iter().filter(|info| info.is_occupied).count() as u64;
     }
 
-    fn get_remainder(&self, fingerprint: u32) -> u32 {
-        //get lowest r bits
-        return (fingerprint & ((1 << self.r) - 1)) % self.size as u32;
+    fn size_in_bits(&self) -> usize {
+        return self.buckets.len() * 32 + self.bucket_info.len() * std::mem::size_of::<QuotientInfo>() * 8;
     }
-}
\ No newline at end of file
+
+    // a query matches if a key already stored shares its quotient and its r bit remainder.
+    fn false_positive_rate(&self) -> f64 {
+        return filter::fingerprint_false_positive_rate(self.fill_ratio(), self.r as u32);
+    }
+}
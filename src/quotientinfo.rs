@@ -1,4 +1,5 @@
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct QuotientInfo {
     pub(crate) is_occupied: bool,
     pub(crate) is_shifted: bool,
@@ -1,59 +1,69 @@
-#[path = "bloomfilter.rs"]
-mod bloomfilter;
 use std::f64;
-use rand::Rng;
 use slab::Slab;
 
-#[path = "utils.rs"]
-mod utils;
 
-pub struct RegisterAlignedBloomFilterLarger {
-    size: u64,
+use rand_chacha::ChaCha8Rng;
+use crate::utils;
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::filter::{self, DynamicFilter, Filter, FilterStats};
+use crate::serialize::{ensure, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
+
+pub struct RegisterAlignedBloomFilterLarger<H = MultiplyShift> {
     blocks: Slab<u64>,
     block_size: usize,
     num_blocks: u64,
     num_hashes: usize,
-    hash_functions: Vec<(u64,u64,u64)>,
+    hash_functions: Vec<H>,
+    seed: u64,
     binary_info: (u32,u32)
 }
 
 
 impl RegisterAlignedBloomFilterLarger {
     // block_size = size of register in bits.
-    pub fn new(expected_inserts : u64, block_size: usize, false_positive_rate: f64) -> Self {
-        let mut size: u64 = ((-1.44 * (expected_inserts as f64)).ceil()
+    pub fn new(expected_inserts : u64, block_size: usize, false_positive_rate: f64) -> RegisterAlignedBloomFilterLarger {
+        return Self::with_hasher(expected_inserts, block_size, false_positive_rate);
+    }
+}
+
+impl<H: KeyHasher> RegisterAlignedBloomFilterLarger<H> {
+    // same as new, but draws the hash functions from H instead of multiply-shift.
+    pub fn with_hasher(expected_inserts : u64, block_size: usize, false_positive_rate: f64) -> Self {
+        return Self::with_seed(expected_inserts, block_size, false_positive_rate, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    pub fn with_seed(expected_inserts : u64, block_size: usize, false_positive_rate: f64, seed: u64) -> Self {
+        let mut rng = utils::seeded_rng(seed);
+        let size: u64 = ((-1.44 * (expected_inserts as f64)).ceil()
             * false_positive_rate.log2() + 0.5) as u64 ;
         let num_hashes = (-false_positive_rate.log2() + 1.5) as usize;
-        let num_blocks = (size + (block_size - 1) as u64) / block_size as u64;
-        size = size * 5;
+        // at least one block, or no inserts expected would leave nothing to hash into.
+        let num_blocks = ((size + (block_size - 1) as u64) / block_size as u64).max(1);
 
-        let mut rng = rand::thread_rng();
-        let _a1 = rng.gen_range(1..=u64::MAX);
-        let _a2 = rng.gen_range(1..=u64::MAX);
-        let _b = rng.gen_range(1..=u64::MAX);
         let pair = (64 - (num_blocks - 1).leading_zeros(), 64 - (block_size - 1).leading_zeros());
         RegisterAlignedBloomFilterLarger {
-            size,
             blocks: Self::generate_blocks(num_blocks, block_size),
             block_size,
             num_blocks,
             num_hashes,
             // first hash function is always to find the block.
-            hash_functions: Self::generate_hash_functions(num_hashes),
+            hash_functions: Self::generate_hash_functions(num_hashes, &mut rng),
+            seed,
             binary_info: pair
         }
     }
 
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
 
-    fn generate_hash_functions(num_hashes: usize) -> Vec<(u64, u64,u64)> {
-        let mut rng = rand::thread_rng();
+
+    fn generate_hash_functions(num_hashes: usize, rng: &mut ChaCha8Rng) -> Vec<H> {
         let mut hash_functions = Vec::new();
 
         for _ in 0..num_hashes {
-            let a1: u64 = rng.gen_range(1..=u64::MAX );
-            let a2: u64 = rng.gen_range(1..=u64::MAX);
-            let b: u64 = rng.gen_range(1..=u64::MAX);
-            hash_functions.push((a1,a2,b));
+            hash_functions.push(H::from_rng(rng));
         }
         return hash_functions;
     }
@@ -69,40 +79,126 @@ impl RegisterAlignedBloomFilterLarger {
 
     fn get_block_id(&self, element: u64) -> usize {
         // need binary log of the number of blocks here.
-        return (utils::hash(element, self.binary_info.0 as u32, self.hash_functions[0].0, self.hash_functions[0].1,
-                            self.hash_functions[0].2) as usize ) % self.num_blocks as usize;
+        return (self.hash_functions[0].hash_bits(element, self.binary_info.0) as usize ) % self.num_blocks as usize;
     }
+}
 
-    // Add an element to the correct block.
-    pub fn insert(&mut self, element: u64) {
+impl<H: KeyHasher> Filter for RegisterAlignedBloomFilterLarger<H> {
+    // Check if an element is present in the correct block.
+    fn member(&self, element: u64) -> bool {
         let block_id = self.get_block_id(element);
-        let block = self.blocks.get_mut(block_id).unwrap();
-
+        let block = self.blocks.get(block_id).unwrap();
         // compute mask. So only one operation performed on register
         let mut mask: u64 = 0;
         for i in 1..self.num_hashes {
-            let hash_function = self.hash_functions[i];
-            let index : u64 = (utils::hash(element, self.binary_info.1, hash_function.0, hash_function.1,
-                                           hash_function.2) % self.block_size as u32) as u64;
-
+            let hash_function = &self.hash_functions[i];
+            let index : u64 = (hash_function.hash_bits(element, self.binary_info.1) % self.block_size as u32) as u64;
             mask |= 1 << index;
         }
-        *block |= mask;
+        return (*block & mask) == mask;
     }
+}
 
-    // Check if an element is present in the correct block.
-    pub fn member(&self, element: u64) -> bool {
+impl<H: KeyHasher> DynamicFilter for RegisterAlignedBloomFilterLarger<H> {
+    // Add an element to the correct block.
+    fn insert(&mut self, element: u64) -> bool {
         let block_id = self.get_block_id(element);
-        let block = self.blocks.get(block_id).unwrap();
+        let block = self.blocks.get_mut(block_id).unwrap();
+
         // compute mask. So only one operation performed on register
         let mut mask: u64 = 0;
         for i in 1..self.num_hashes {
-            let hash_function = self.hash_functions[i];
-            let index : u64 = (utils::hash(element, self.binary_info.1, hash_function.0, hash_function.1,
-                                           hash_function.2) % self.block_size as u32) as u64;
+            let hash_function = &self.hash_functions[i];
+            let index : u64 = (hash_function.hash_bits(element, self.binary_info.1) % self.block_size as u32) as u64;
+
             mask |= 1 << index;
         }
-        return (*block & mask) == mask;
+        *block |= mask;
+        return true;
     }
 }
 
+impl<H: SerializableHasher> SerializableFilter for RegisterAlignedBloomFilterLarger<H> {
+    const KIND: FilterKind = FilterKind::RegisterAlignedBloomLarger;
+    const HASHER: HasherKind = H::KIND;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_u64(self.block_size as u64);
+        enc.put_u64(self.num_blocks);
+        enc.put_u64(self.num_hashes as u64);
+        enc.put_u64(self.binary_info.0 as u64);
+        enc.put_u64(self.binary_info.1 as u64);
+        enc.put_hashers(&self.hash_functions);
+        // slab keys are 0..=num_blocks, and iteration is in key order.
+        let registers: Vec<u64> = self.blocks.iter().map(|(_, block)| *block).collect();
+        enc.put_u64s(&registers);
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let block_size = dec.get_usize()?;
+        let num_blocks = dec.get_u64()?;
+        let num_hashes = dec.get_usize()?;
+        let binary_info = (dec.get_u32()?, dec.get_u32()?);
+        let hash_functions = dec.get_hashers(Some(num_hashes))?;
+        let registers = dec.get_u64s()?;
+        ensure(block_size > 0 && block_size <= 64, "block size out of range")?;
+        ensure(num_blocks > 0, "no blocks")?;
+        ensure(num_hashes > 0, "no hash functions")?;
+        ensure(binary_info.0 <= 64 && binary_info.1 <= 64, "l out of range")?;
+        ensure(num_blocks.checked_add(1) == Some(registers.len() as u64), "blocks do not match the block count")?;
+        let mut blocks = Slab::with_capacity(registers.len());
+        for register in registers {
+            blocks.insert(register);
+        }
+        return Ok(RegisterAlignedBloomFilterLarger {
+            blocks,
+            block_size,
+            num_blocks,
+            num_hashes,
+            hash_functions,
+            seed,
+            binary_info,
+        });
+    }
+}
+
+// the slab has one register more than get_block_id can reach, which is left out.
+impl<H: KeyHasher> FilterStats for RegisterAlignedBloomFilterLarger<H> {
+    // estimated register by register, as each key sets all of its bits in one register.
+    fn len(&self) -> u64 {
+        let k = self.num_hashes.max(1) as u64 - 1;
+        let mut n = 0.0;
+        for (_, block) in self.blocks.iter() {
+            n += filter::swamidass_baldi(block.count_ones() as u64, self.block_size as u64, k);
+        }
+        return n.round() as u64;
+    }
+
+    fn capacity(&self) -> u64 {
+        return filter::half_full(self.slots(), self.num_hashes.max(1) as u64 - 1);
+    }
+
+    fn slots(&self) -> u64 {
+        return self.num_blocks * self.block_size as u64;
+    }
+
+    fn occupied(&self) -> u64 {
+        return self.blocks.iter().map(|(_, block)| block.count_ones() as u64).sum();
+    }
+
+    fn size_in_bits(&self) -> usize {
+        return self.blocks.len() * 64;
+    }
+
+    // a key picks a register at random, then needs all of its bits to be set in it.
+    fn false_positive_rate(&self) -> f64 {
+        let k = self.num_hashes.max(1) as i32 - 1;
+        let mut rate = 0.0;
+        for (_, block) in self.blocks.iter().take(self.num_blocks as usize) {
+            rate += (block.count_ones() as f64 / self.block_size as f64).powi(k);
+        }
+        return rate / self.num_blocks as f64;
+    }
+}
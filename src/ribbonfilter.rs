@@ -0,0 +1,356 @@
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::utils;
+
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::filter::{Filter, FilterStats, StaticFilter};
+use crate::serialize::{ensure, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
+
+/*
+Standard Ribbon filter (Dillinger and Walzer, 2021).
+Every key gives a row of a linear system over GF(2): a start slot s, a run of `ribbon_width` coefficients c beginning
+at s, and an r bit fingerprint. The filter stores a solution Z with one r bit value per slot, such that for every key
+the xor of Z[s + j] over the set bits j of c is its fingerprint. The rows all lie in a band of width w, so the system
+can be solved on the fly with banded Gaussian elimination, one key at a time, and then back substitution.
+
+Z is stored bit sliced: for every 64 slots there are r words, word k holding bit k of those 64 values. A query reads
+the w bits of each word starting at s and takes the parity of them and c, so it touches one or two cache lines.
+ */
+
+// the ribbon widths we support, and the space overhead each needs for construction to succeed most of the time.
+pub(crate) const RIBBON_WIDTHS: [(u32, f64); 3] = [(32, 0.11), (64, 0.06), (128, 0.03)];
+
+// construction is retried with new hash functions this many times before the table is grown.
+pub(crate) const RETRIES_BEFORE_GROWING: u32 = 4;
+
+pub(crate) struct Row {
+    pub(crate) start: usize,
+    coefficients: u128,
+    fingerprint: u32,
+}
+
+// the rows accepted so far, kept in echelon form: a row in slot i always has coefficient bit 0 set.
+pub(crate) struct Banding {
+    coefficients: Vec<u128>,
+    fingerprints: Vec<u32>,
+}
+
+pub(crate) enum Added {
+    // the row was stored in this slot.
+    Slot(usize),
+    // the row is a combination of earlier ones with a matching fingerprint, e.g. a duplicate key.
+    Redundant,
+    // the row contradicts earlier ones. the system has no solution.
+    Failed,
+}
+
+impl Banding {
+    pub(crate) fn new(slots: usize) -> Banding {
+        return Banding {
+            coefficients: vec![0; slots],
+            fingerprints: vec![0; slots],
+        };
+    }
+
+    pub(crate) fn add(&mut self, row: &Row) -> Added {
+        let mut start = row.start;
+        let mut c = row.coefficients;
+        let mut f = row.fingerprint;
+        loop {
+            if self.coefficients[start] == 0 {
+                self.coefficients[start] = c;
+                self.fingerprints[start] = f;
+                return Added::Slot(start);
+            }
+            c ^= self.coefficients[start];
+            f ^= self.fingerprints[start];
+            if c == 0 {
+                if f == 0 {
+                    return Added::Redundant;
+                }
+                return Added::Failed;
+            }
+            // move on to the first remaining coefficient.
+            let shift = c.trailing_zeros();
+            start += shift as usize;
+            c >>= shift;
+        }
+    }
+
+    // forget a row added earlier. rows never modify stored ones, so this undoes it completely.
+    pub(crate) fn clear(&mut self, slot: usize) {
+        self.coefficients[slot] = 0;
+        self.fingerprints[slot] = 0;
+    }
+}
+
+// one solved system: the hash functions that make the rows, and the solution.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Ribbon<H> {
+    pub(crate) hashes: Vec<H>,
+    pub(crate) slots: usize,
+    pub(crate) ribbon_width: u32,
+    pub(crate) fingerprint_bits: u32,
+    pub(crate) solution: Vec<u64>,
+}
+
+impl<H: KeyHasher> Ribbon<H> {
+    pub(crate) fn new(slots: usize, ribbon_width: u32, fingerprint_bits: u32, rng: &mut ChaCha8Rng) -> Ribbon<H> {
+        // starts, coefficients (two functions for 128 wide ribbons), fingerprints.
+        let mut hashes = Vec::new();
+        for _ in 0..4 {
+            hashes.push(H::from_rng(rng));
+        }
+        return Ribbon {
+            hashes,
+            slots,
+            ribbon_width,
+            fingerprint_bits,
+            solution: vec![],
+        };
+    }
+
+    // the number of u64s the solution takes. three extra groups so a window never reads past the end.
+    pub(crate) fn solution_len(slots: usize, fingerprint_bits: u32) -> usize {
+        return (slots / 64 + 3) * fingerprint_bits as usize;
+    }
+
+    pub(crate) fn row(&self, key: u64) -> Row {
+        let starts = (self.slots - self.ribbon_width as usize + 1) as u128;
+        let start = ((remix(self.hashes[0].hash(key)) as u128 * starts) >> 64) as usize;
+        let mut coefficients = remix(self.hashes[1].hash(key)) as u128;
+        if self.ribbon_width == 128 {
+            coefficients |= (remix(self.hashes[2].hash(key)) as u128) << 64;
+        }
+        else {
+            coefficients &= (1u128 << self.ribbon_width) - 1;
+        }
+        return Row {
+            start,
+            // the first coefficient is always set, so every row has somewhere to go.
+            coefficients: coefficients | 1,
+            fingerprint: self.hashes[3].hash_bits(key, self.fingerprint_bits),
+        };
+    }
+
+    // back substitution, from the last slot to the first. free slots get random values.
+    pub(crate) fn solve(&mut self, banding: &Banding, rng: &mut ChaCha8Rng) {
+        let r = self.fingerprint_bits as usize;
+        self.solution = vec![0; Self::solution_len(self.slots, self.fingerprint_bits)];
+        for i in (0..self.slots).rev() {
+            let c = banding.coefficients[i];
+            let value = if c == 0 {
+                rng.gen::<u32>()
+            }
+            else {
+                let mut value = banding.fingerprints[i];
+                for k in 0..r {
+                    value ^= self.parity(k, i, c) << k;
+                }
+                value
+            };
+            for k in 0..r {
+                self.solution[(i / 64) * r + k] |= (((value >> k) & 1) as u64) << (i % 64);
+            }
+        }
+    }
+
+    // bit k of the xor of the solution values picked out by c, starting at slot start.
+    fn parity(&self, k: usize, start: usize, c: u128) -> u32 {
+        let r = self.fingerprint_bits as usize;
+        let group = start / 64;
+        let offset = (start % 64) as u32;
+        let lo = self.solution[group * r + k] as u128 | (self.solution[(group + 1) * r + k] as u128) << 64;
+        let mut window = lo >> offset;
+        if offset > 0 {
+            window |= (self.solution[(group + 2) * r + k] as u128) << (128 - offset);
+        }
+        return (window & c).count_ones() & 1;
+    }
+
+    pub(crate) fn check(&self, row: &Row) -> bool {
+        for k in 0..self.fingerprint_bits as usize {
+            if self.parity(k, row.start, row.coefficients) != (row.fingerprint >> k) & 1 {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    pub(crate) fn encode(&self, enc: &mut Encoder) where H: SerializableHasher {
+        enc.put_u64(self.slots as u64);
+        enc.put_hashers(&self.hashes);
+        enc.put_u64s(&self.solution);
+    }
+
+    pub(crate) fn decode(dec: &mut Decoder, ribbon_width: u32, fingerprint_bits: u32) -> Result<Ribbon<H>, SerializeError>
+        where H: SerializableHasher {
+        let slots = dec.get_usize()?;
+        let hashes = dec.get_hashers(Some(4))?;
+        let solution = dec.get_u64s()?;
+        ensure(slots >= ribbon_width as usize, "fewer slots than the ribbon width")?;
+        ensure(solution.len() == Self::solution_len(slots, fingerprint_bits), "solution does not match the slots")?;
+        return Ok(Ribbon {
+            hashes,
+            slots,
+            ribbon_width,
+            fingerprint_bits,
+            solution,
+        });
+    }
+}
+
+// the murmur3 finaliser. multiply-shift only mixes well into its high bits, and the rows need all 64.
+pub(crate) fn remix(mut h: u64) -> u64 {
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xff51afd7ed558ccd);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xc4ceb9fe1a85ec53);
+    h ^= h >> 33;
+    return h;
+}
+
+pub(crate) fn overhead(ribbon_width: u32) -> f64 {
+    for (width, overhead) in RIBBON_WIDTHS {
+        if width == ribbon_width {
+            return overhead;
+        }
+    }
+    panic!("ribbon width must be 32, 64 or 128, not {}", ribbon_width);
+}
+
+pub(crate) fn check_params(ribbon_width: u32, fingerprint_bits: u32) -> Result<(), SerializeError> {
+    ensure(RIBBON_WIDTHS.iter().any(|(width, _)| *width == ribbon_width), "ribbon width must be 32, 64 or 128")?;
+    ensure((1..=32).contains(&fingerprint_bits), "fingerprint bits must be between 1 and 32")?;
+    return Ok(());
+}
+
+// the number of keys build makes a ribbon of this many slots for. a ribbon that needed more slots to solve overcounts.
+pub(crate) fn keys_for_slots(slots: usize, ribbon_width: u32) -> u64 {
+    return (slots.saturating_sub(ribbon_width as usize) as f64 / (1.0 + overhead(ribbon_width))).round() as u64;
+}
+
+// build a ribbon over all of the keys, retrying with new hash functions until it solves.
+pub(crate) fn build<H: KeyHasher>(keys: &[u64], ribbon_width: u32, fingerprint_bits: u32, rng: &mut ChaCha8Rng) -> Ribbon<H> {
+    let mut overhead = overhead(ribbon_width);
+    let mut attempts = 0;
+    loop {
+        let slots = (keys.len() as f64 * (1.0 + overhead)).ceil() as usize + ribbon_width as usize;
+        let mut ribbon = Ribbon::<H>::new(slots, ribbon_width, fingerprint_bits, rng);
+        let mut banding = Banding::new(slots);
+        let mut solved = true;
+        for key in keys {
+            if let Added::Failed = banding.add(&ribbon.row(*key)) {
+                solved = false;
+                break;
+            }
+        }
+        if solved {
+            ribbon.solve(&banding, rng);
+            return ribbon;
+        }
+        attempts += 1;
+        if attempts % RETRIES_BEFORE_GROWING == 0 {
+            overhead += 0.02;
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RibbonFilter<H = MultiplyShift> {
+    ribbon: Ribbon<H>,
+    seed: u64,
+}
+
+impl RibbonFilter {
+    // ribbon_width is 32, 64 or 128. wider ribbons need less space but are slower to query.
+    // the false positive rate is 2^-fingerprint_bits.
+    pub fn new(keys: &[u64], ribbon_width: u32, fingerprint_bits: u32) -> RibbonFilter {
+        return Self::with_hasher(keys, ribbon_width, fingerprint_bits);
+    }
+}
+
+impl<H: KeyHasher> RibbonFilter<H> {
+    // same as new, but draws the hash functions from H instead of multiply-shift.
+    pub fn with_hasher(keys: &[u64], ribbon_width: u32, fingerprint_bits: u32) -> Self {
+        return Self::with_seed(keys, ribbon_width, fingerprint_bits, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    pub fn with_seed(keys: &[u64], ribbon_width: u32, fingerprint_bits: u32, seed: u64) -> Self {
+        assert!((1..=32).contains(&fingerprint_bits), "fingerprint bits must be between 1 and 32");
+        let mut rng = utils::seeded_rng(seed);
+        return RibbonFilter {
+            ribbon: build(keys, ribbon_width, fingerprint_bits, &mut rng),
+            seed,
+        };
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+}
+
+impl<H: KeyHasher> Filter for RibbonFilter<H> {
+    fn member(&self, key: u64) -> bool {
+        return self.ribbon.check(&self.ribbon.row(key));
+    }
+}
+
+impl<H: KeyHasher> StaticFilter for RibbonFilter<H> {
+    fn from_keys(keys: &[u64]) -> Self {
+        return Self::with_hasher(keys, 64, 8);
+    }
+}
+
+impl<H: SerializableHasher> SerializableFilter for RibbonFilter<H> {
+    const KIND: FilterKind = FilterKind::Ribbon;
+    const HASHER: HasherKind = H::KIND;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_u64(self.ribbon.ribbon_width as u64);
+        enc.put_u64(self.ribbon.fingerprint_bits as u64);
+        self.ribbon.encode(enc);
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let ribbon_width = dec.get_u32()?;
+        let fingerprint_bits = dec.get_u32()?;
+        check_params(ribbon_width, fingerprint_bits)?;
+        return Ok(RibbonFilter {
+            ribbon: Ribbon::decode(dec, ribbon_width, fingerprint_bits)?,
+            seed,
+        });
+    }
+}
+
+impl<H: KeyHasher> FilterStats for RibbonFilter<H> {
+    fn len(&self) -> u64 {
+        return keys_for_slots(self.ribbon.slots, self.ribbon.ribbon_width);
+    }
+
+    fn capacity(&self) -> u64 {
+        return self.len();
+    }
+
+    fn slots(&self) -> u64 {
+        return self.ribbon.slots as u64;
+    }
+
+    // every key is solved for in a slot of its own.
+    fn occupied(&self) -> u64 {
+        return self.len();
+    }
+
+    // the size of the solution in bits.
+    fn size_in_bits(&self) -> usize {
+        return self.ribbon.slots * self.ribbon.fingerprint_bits as usize;
+    }
+
+    fn false_positive_rate(&self) -> f64 {
+        return 0.5f64.powi(self.ribbon.fingerprint_bits as i32);
+    }
+}
@@ -0,0 +1,247 @@
+use crate::hasher::KeyHasher;
+use crate::filter::{DynamicFilter, Filter, FilterStats};
+use crate::bloomfilter::BloomFilter;
+use crate::blockedbloomfilter::BlockedBloomFilter;
+use crate::serialize::{ensure, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializeError};
+
+/*
+Scalable Bloom filter (Almeida, Baquero, Preguiça and Hutchison, 2007).
+A Bloom filter has to be told how many keys it will hold, and past that its false positive rate climbs quickly.
+This filter starts with one stage sized for initial_capacity keys, and when a stage has had that many keys inserted it
+adds a new stage, growth times larger. Keys only go into the newest stage, and a query asks every stage.
+Stage i is built for a false positive rate of P (1 - r) r^i, where r is the tightening ratio, so however many stages
+there are the rate of the whole filter, 1 - (1 - P0)(1 - P1)..., stays under P0 + P1 + ... < P.
+ */
+
+// each stage is sized for this many times the keys of the one before.
+const GROWTH: u64 = 2;
+// each stage has this many times the false positive rate of the one before.
+const TIGHTENING: f64 = 0.85;
+
+// a filter that can be a stage of a ScalableBloomFilter.
+pub trait BloomStage: DynamicFilter + FilterStats + Sized {
+    // a filter for capacity keys at the false positive rate, with its hash functions drawn from seed.
+    fn stage(capacity: u64, false_positive_rate: f64, seed: u64) -> Self;
+}
+
+// a stage has to meet its rate, so its size is rounded up, where BloomFilter::new rounds to the closest power of two.
+impl<H: KeyHasher> BloomStage for BloomFilter<H> {
+    fn stage(capacity: u64, false_positive_rate: f64, seed: u64) -> Self {
+        return BloomFilter::with_size_rounded_up(capacity, false_positive_rate, seed);
+    }
+}
+
+impl<H: KeyHasher> BloomStage for BlockedBloomFilter<H> {
+    fn stage(capacity: u64, false_positive_rate: f64, seed: u64) -> Self {
+        return BlockedBloomFilter::with_seed(capacity, 512, false_positive_rate, seed);
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScalableBloomFilter<F = BloomFilter> {
+    stages: Vec<F>,
+    initial_capacity: u64,
+    false_positive_rate: f64,
+    growth: u64,
+    tightening: f64,
+    seed: u64,
+    // keys inserted into the newest stage, and into the filter as a whole.
+    stage_len: u64,
+    len: u64,
+}
+
+impl ScalableBloomFilter {
+    pub fn new(initial_capacity: u64, false_positive_rate: f64) -> ScalableBloomFilter {
+        return Self::with_stage(initial_capacity, false_positive_rate);
+    }
+}
+
+impl<F: BloomStage> ScalableBloomFilter<F> {
+    // same as new, but builds its stages from F instead of BloomFilter.
+    pub fn with_stage(initial_capacity: u64, false_positive_rate: f64) -> Self {
+        return Self::with_seed(initial_capacity, false_positive_rate, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    pub fn with_seed(initial_capacity: u64, false_positive_rate: f64, seed: u64) -> Self {
+        return Self::with_growth(initial_capacity, false_positive_rate, GROWTH, TIGHTENING, seed);
+    }
+
+    // growth is how many times larger each stage is than the last, and tightening how many times smaller its false
+    // positive rate. a larger growth means fewer stages to query, a smaller tightening fewer bits per key.
+    pub fn with_growth(initial_capacity: u64, false_positive_rate: f64, growth: u64, tightening: f64, seed: u64) -> Self {
+        assert!(initial_capacity > 0, "initial capacity must be at least 1");
+        assert!(false_positive_rate > 0.0 && false_positive_rate < 1.0, "false positive rate must be between 0 and 1");
+        assert!(growth >= 1, "growth must be at least 1");
+        assert!(tightening > 0.0 && tightening < 1.0, "tightening must be between 0 and 1");
+        let mut filter = ScalableBloomFilter {
+            stages: Vec::new(),
+            initial_capacity,
+            false_positive_rate,
+            growth,
+            tightening,
+            seed,
+            stage_len: 0,
+            len: 0,
+        };
+        filter.add_stage();
+        return filter;
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    pub fn num_stages(&self) -> usize {
+        return self.stages.len();
+    }
+
+    fn stage_capacity(&self, stage: usize) -> u64 {
+        return self.initial_capacity.saturating_mul(self.growth.saturating_pow(stage as u32));
+    }
+
+    fn stage_false_positive_rate(&self, stage: usize) -> f64 {
+        return self.false_positive_rate * (1.0 - self.tightening) * self.tightening.powi(stage as i32);
+    }
+
+    fn add_stage(&mut self) {
+        let stage = self.stages.len();
+        // each stage gets a seed of its own, so no two stages share hash functions.
+        let seed = self.seed.wrapping_add(stage as u64);
+        self.stages.push(F::stage(self.stage_capacity(stage), self.stage_false_positive_rate(stage), seed));
+        self.stage_len = 0;
+    }
+}
+
+impl<F: BloomStage> Filter for ScalableBloomFilter<F> {
+    fn member(&self, key: u64) -> bool {
+        return self.stages.iter().any(|stage| stage.member(key));
+    }
+}
+
+impl<F: BloomStage> DynamicFilter for ScalableBloomFilter<F> {
+    fn insert(&mut self, key: u64) -> bool {
+        // a key that is already a member, truly or not, would only fill the newest stage faster.
+        if self.member(key) {
+            return true;
+        }
+        if self.stage_len >= self.stage_capacity(self.stages.len() - 1) {
+            self.add_stage();
+        }
+        self.stages.last_mut().unwrap().insert(key);
+        self.stage_len += 1;
+        self.len += 1;
+        return true;
+    }
+}
+
+impl<F: BloomStage> FilterStats for ScalableBloomFilter<F> {
+    // keys inserted that were not members already.
+    fn len(&self) -> u64 {
+        return self.len;
+    }
+
+    // keys the stages built so far are sized for.
+    fn capacity(&self) -> u64 {
+        return (0..self.stages.len()).map(|stage| self.stage_capacity(stage)).fold(0, u64::saturating_add);
+    }
+
+    fn slots(&self) -> u64 {
+        return self.stages.iter().map(|stage| stage.slots()).sum();
+    }
+
+    fn occupied(&self) -> u64 {
+        return self.stages.iter().map(|stage| stage.occupied()).sum();
+    }
+
+    fn size_in_bits(&self) -> usize {
+        return self.stages.iter().map(|stage| stage.size_in_bits()).sum();
+    }
+
+    // a key not in the filter is a false positive if any stage says it is a member.
+    fn false_positive_rate(&self) -> f64 {
+        return 1.0 - self.stages.iter().map(|stage| 1.0 - stage.false_positive_rate()).product::<f64>();
+    }
+}
+
+impl<F: BloomStage + SerializableFilter> SerializableFilter for ScalableBloomFilter<F> {
+    const KIND: FilterKind = FilterKind::ScalableBloom;
+    const HASHER: HasherKind = F::HASHER;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_u64(self.initial_capacity);
+        enc.put_u64(self.false_positive_rate.to_bits());
+        enc.put_u64(self.growth);
+        enc.put_u64(self.tightening.to_bits());
+        enc.put_u64(self.stage_len);
+        enc.put_u64(self.len);
+        enc.put_u64(F::KIND as u64);
+        enc.put_u64(self.stages.len() as u64);
+        for stage in &self.stages {
+            stage.encode(enc);
+        }
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let initial_capacity = dec.get_u64()?;
+        let false_positive_rate = f64::from_bits(dec.get_u64()?);
+        let growth = dec.get_u64()?;
+        let tightening = f64::from_bits(dec.get_u64()?);
+        let stage_len = dec.get_u64()?;
+        let len = dec.get_u64()?;
+        let kind = dec.get_u64()?;
+        let num_stages = dec.get_usize()?;
+        ensure(initial_capacity > 0, "initial capacity is zero")?;
+        ensure(false_positive_rate > 0.0 && false_positive_rate < 1.0, "false positive rate out of range")?;
+        ensure(growth >= 1, "growth is zero")?;
+        ensure(tightening > 0.0 && tightening < 1.0, "tightening out of range")?;
+        ensure(stage_len <= len, "more keys in the last stage than in the filter")?;
+        ensure(kind == F::KIND as u64, "stages are a different kind of filter")?;
+        ensure(num_stages > 0, "no stages")?;
+        let mut stages = Vec::new();
+        for _ in 0..num_stages {
+            stages.push(F::decode(dec)?);
+        }
+        return Ok(ScalableBloomFilter {
+            stages,
+            initial_capacity,
+            false_positive_rate,
+            growth,
+            tightening,
+            seed,
+            stage_len,
+            len,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::fpr;
+    use crate::hasher::MultiplyShift;
+    use crate::utils;
+
+    // grow far past the initial capacity, then measure the rate of the whole filter on keys never inserted.
+    fn compound_rate<F: BloomStage>(initial_capacity: u64, inserts: usize) -> f64 {
+        let mut rng = utils::seeded_rng(1);
+        let mut filter = ScalableBloomFilter::<F>::with_seed(initial_capacity, 0.01, 2);
+        for _ in 0..inserts {
+            filter.insert(rng.gen());
+        }
+        let lookups: Vec<u64> = (0..200_000).map(|_| rng.gen()).collect();
+        return fpr::false_positive_rate(&filter, &lookups);
+    }
+
+    #[test]
+    fn compound_rate_stays_within_target() {
+        for (initial_capacity, inserts) in [(10, 10_000), (777, 5_000), (1_000, 100_000)] {
+            assert!(compound_rate::<BloomFilter<MultiplyShift>>(initial_capacity, inserts) < 0.01);
+            assert!(compound_rate::<BlockedBloomFilter<MultiplyShift>>(initial_capacity, inserts) < 0.01);
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use std::sync::OnceLock;
+
+/*
+Semi-sorted buckets for the cuckoo filter (Fan, Andersen, Kaminsky and Mitzenmacher, 2014, section 5.2).
+A bucket of four fingerprints keeps the top PREFIX_BITS of each as one code. There are only 3876 sorted tuples of four
+4 bit values, so a 12 bit code is enough for the 16 bits of the four prefixes.
+The tables are built the first time they are needed: decode lists the tuples in order, four nibbles to a u16 with the
+smallest in the low nibble, and encode maps each such u16 back to its position.
+ */
+
+pub(crate) const PREFIX_BITS: u32 = 4;
+pub(crate) const CODE_BITS: u32 = 12;
+// sorted tuples of four 4 bit values, (16 + 3) choose 4.
+pub(crate) const CODES: usize = 3876;
+
+struct Tables {
+    encode: Vec<u16>,
+    decode: Vec<u16>,
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    return TABLES.get_or_init(|| {
+        let mut encode = vec![0; 1 << 16];
+        let mut decode = Vec::with_capacity(CODES);
+        for a in 0..16u16 {
+            for b in a..16 {
+                for c in b..16 {
+                    for d in c..16 {
+                        let packed = a | b << 4 | c << 8 | d << 12;
+                        encode[packed as usize] = decode.len() as u16;
+                        decode.push(packed);
+                    }
+                }
+            }
+        }
+        return Tables { encode, decode };
+    });
+}
+
+// the code of four prefixes, which have to be sorted smallest first.
+pub(crate) fn encode(prefixes: [u8; 4]) -> u16 {
+    let packed = prefixes[0] as u16 | (prefixes[1] as u16) << 4 | (prefixes[2] as u16) << 8 | (prefixes[3] as u16) << 12;
+    return tables().encode[packed as usize];
+}
+
+// the four prefixes of a code, smallest first.
+pub(crate) fn decode(code: u16) -> [u8; 4] {
+    let packed = tables().decode[code as usize];
+    return [0, 4, 8, 12].map(|shift| (packed >> shift & 0xf) as u8);
+}
@@ -0,0 +1,540 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use crate::hasher::KeyHasher;
+
+/*
+On disk format shared by every filter. Everything is little endian, whatever the machine.
+
+    offset  size  field
+    0       4     magic, the bytes "DISS"
+    4       2     format version, currently 1
+    6       2     filter kind, see FilterKind
+    8       2     hasher kind, see HasherKind
+    10      6     reserved, zero
+    16      8     length of the body in bytes
+    24      8     checksum of the body, the low 64 bits of murmur3 x64 128 with seed 0
+    32            body
+
+The body is a sequence of fields, and every field is 8 byte aligned relative to the start of the file:
+- a scalar is a u64.
+- an array is its element count as a u64, then the elements, then zeros up to the next multiple of 8 bytes.
+- the hash functions are their count as a u64, then the parameters of each function in turn:
+  multiply-shift a1, a2, b; tabulation the 16 x 16 table row by row; murmur3 and fnv their seed;
+  ahash its four seeds.
+
+The body of each filter, in order (the seed it was built with always comes first):
+    Bloom                   seed, size, l, hash functions, bits (u8 array)
+    Counting Bloom          seed, size, l, counter bits, hash functions, counters (u64 array, packed 64 / counter
+                            bits to a word, low bits first)
+    Blocked Bloom           seed, block size, number of blocks, number of hashes, log2 blocks, log2 block size,
+                            hash functions, blocks (u8 array, number of blocks + 1 blocks of block size bytes)
+    Register aligned        seed, block size, number of blocks, number of hashes, log2 blocks, log2 block size,
+    (and the larger one)    hash functions, blocks (u64 array, number of blocks + 1 registers)
+    Cuckoo                  seed, bucket count, bucket size, fingerprint bits, semi-sorted, max kicks, has a victim,
+                            victim bucket, victim fingerprint, eviction rng (32 byte seed as a u8 array, stream, word
+                            position low and high), hash functions, table (u64 array, buckets packed low bits first,
+                            then one spare word; a semi-sorted bucket is a 12 bit code of the sorted 4 bit prefixes,
+                            then the rest of each fingerprint)
+    Xor 8 / 32              seed, size, l, hash functions, fingerprints (u8 / u32 array)
+    Binary fuse 3 / 4 wise  seed, size, segment length, l, log2 segment length, number of segments,
+                            hash functions, fingerprints (u8 / u16 / u32 array)
+    Quotient                seed, size, r, hash functions, remainders (u32 array),
+                            metadata (u8 array, bit 0 occupied, bit 1 shifted, bit 2 continuation)
+    Morton                  seed, number of buckets, max kicks, has victim, victim bucket, victim fingerprint,
+                            eviction rng (seed, stream, position), hash functions, blocks (u64 array, 8 words per
+                            block: 2 bit fca counters, 16 bit ota, then 46 8 bit fingerprints)
+    Ribbon                  seed, ribbon width, fingerprint bits, ribbon
+    Bumped ribbon           seed, ribbon width, fingerprint bits, number of bumping layers, then for each layer
+                            its ribbon and thresholds (u8 array, 2 bits per bucket), then the last ribbon
+    d-left counting         seed, buckets per sub-table, r, hash functions, permutation multipliers (u64 array),
+                            cells (u64 array, packed at r + 4 bits each, remainder above a 4 bit counter)
+    SIMD blocked Bloom      seed, hash functions, blocks (u32 array, eight lanes per 256 bit block)
+    Scalable Bloom          seed, initial capacity, false positive rate (f64 bits), growth, tightening (f64 bits),
+                            keys in the last stage, keys in all stages, filter kind of the stages, number of stages,
+                            then the body of each stage
+    Partitioned Bloom       seed, slice size, hash functions (one per slice), bits (u8 array)
+    One-hashing Bloom       seed, hash function, partition sizes (u64 array), bits (u8 array)
+    Age-partitioned Bloom   seed, k, slice size, youngest slice, expected inserts, clock, hash functions (one per
+                            slice), inserts in each slice's generation (u64 array), bits (u8 array)
+    Stable Bloom            seed, next cell to sweep, expected inserts, clock, hash functions, cells (u8 array)
+    Dynamic cuckoo          seed, table capacity, false positive rate (f64 bits), number of tables, then the body of
+                            each table
+    Counting cuckoo         seed, bucket count, bucket size, fingerprint bits, counter bits, max kicks, has victim,
+                            victim bucket, victim fingerprint, victim count, eviction rng (seed, stream, position),
+                            hash function, table (u64 array, cells packed at fingerprint + counter bits, fingerprint
+                            in the low bits), overflow buckets, fingerprints and counts (three u64 arrays)
+    Adaptive cuckoo         seed, bucket count, bucket size, fingerprint bits, max kicks, has victim, victim bucket,
+                            victim key, victim selector, eviction rng (seed, stream, position), hash functions (two
+                            for buckets, four for fingerprints), table (u64 array, cells packed at fingerprint + 2
+                            bits, fingerprint in the low bits, selector above it). the key store is the caller's
+where a ribbon is: slots, hash functions, solution (u64 array, slots / 64 + 3 groups of 64 slots, each group one
+word per fingerprint bit)
+and a clock is: window (inserts or microseconds), timed, started, start (microseconds since the unix epoch),
+ticks (inserts or microseconds since the start), steps taken, steps per window
+
+A file is rejected if the magic, version, kinds, length or checksum do not match, if a reserved byte is not zero,
+or if the body does not describe a filter that can be queried safely.
+ */
+
+pub const MAGIC: [u8; 4] = *b"DISS";
+pub const FORMAT_VERSION: u16 = 1;
+pub const HEADER_SIZE: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterKind {
+    Bloom = 1,
+    CountingBloom = 2,
+    BlockedBloom = 3,
+    RegisterAlignedBloom = 4,
+    RegisterAlignedBloomLarger = 5,
+    Cuckoo = 6,
+    Xor8 = 7,
+    Xor32 = 8,
+    ThreeWiseBinaryFuse8 = 9,
+    ThreeWiseBinaryFuse16 = 10,
+    ThreeWiseBinaryFuse32 = 11,
+    FourWiseBinaryFuse8 = 12,
+    FourWiseBinaryFuse16 = 13,
+    FourWiseBinaryFuse32 = 14,
+    Quotient = 15,
+    Morton = 16,
+    Ribbon = 17,
+    BumpedRibbon = 18,
+    DLeftCounting = 19,
+    SimdBlockedBloom = 20,
+    ScalableBloom = 21,
+    PartitionedBloom = 22,
+    OneHashingBloom = 23,
+    AgePartitionedBloom = 24,
+    StableBloom = 25,
+    DynamicCuckoo = 26,
+    CountingCuckoo = 27,
+    AdaptiveCuckoo = 28,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HasherKind {
+    MultiplyShift = 1,
+    Tabulation = 2,
+    Murmur3 = 3,
+    AHash = 4,
+    Fnv = 5,
+}
+
+#[derive(Debug)]
+pub enum SerializeError {
+    Io(io::Error),
+    // the file does not start with MAGIC.
+    BadMagic,
+    UnsupportedVersion(u16),
+    // the file holds a different filter, or the same filter built with a different hasher.
+    WrongFilter { expected: FilterKind, found: u16 },
+    WrongHasher { expected: HasherKind, found: u16 },
+    ChecksumMismatch,
+    // the file ends before the header or body does.
+    Truncated,
+    // the body passed the checksum but does not describe a valid filter.
+    Corrupt(&'static str),
+    // an array cannot be borrowed in place because it does not start on a multiple of its element size.
+    Misaligned,
+}
+
+impl fmt::Display for SerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SerializeError::Io(e) => write!(f, "io error: {}", e),
+            SerializeError::BadMagic => write!(f, "not a filter file"),
+            SerializeError::UnsupportedVersion(v) => write!(f, "unsupported format version {}", v),
+            SerializeError::WrongFilter { expected, found } => {
+                write!(f, "expected a {:?} filter, found filter kind {}", expected, found)
+            }
+            SerializeError::WrongHasher { expected, found } => {
+                write!(f, "expected hasher {:?}, found hasher kind {}", expected, found)
+            }
+            SerializeError::ChecksumMismatch => write!(f, "checksum mismatch"),
+            SerializeError::Truncated => write!(f, "file is truncated"),
+            SerializeError::Corrupt(reason) => write!(f, "corrupt filter: {}", reason),
+            SerializeError::Misaligned => write!(f, "filter arrays are not aligned in memory"),
+        }
+    }
+}
+
+impl std::error::Error for SerializeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SerializeError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SerializeError {
+    fn from(e: io::Error) -> Self {
+        if e.kind() == io::ErrorKind::UnexpectedEof {
+            return SerializeError::Truncated;
+        }
+        return SerializeError::Io(e);
+    }
+}
+
+// a hash function that can be written into a filter file and read back as the same function.
+pub trait SerializableHasher: KeyHasher + Sized {
+    const KIND: HasherKind;
+
+    fn encode(&self, enc: &mut Encoder);
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError>;
+}
+
+// a filter that can be written to and read from the format above.
+pub trait SerializableFilter: Sized {
+    const KIND: FilterKind;
+    const HASHER: HasherKind;
+
+    // write the body.
+    fn encode(&self, enc: &mut Encoder);
+
+    // read the body back, checking that the result can be queried.
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut enc = Encoder::new();
+        self.encode(&mut enc);
+        let body = enc.into_bytes();
+        let mut bytes = Header::new(Self::KIND, Self::HASHER, &body).to_bytes().to_vec();
+        bytes.extend_from_slice(&body);
+        return bytes;
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, SerializeError> {
+        let body = check_file(bytes, Self::KIND, Self::HASHER)?;
+        let mut dec = Decoder::new(body);
+        let filter = Self::decode(&mut dec)?;
+        dec.finish()?;
+        return Ok(filter);
+    }
+
+    fn write_to<W: Write>(&self, mut writer: W) -> Result<(), SerializeError> {
+        writer.write_all(&self.to_bytes())?;
+        return Ok(());
+    }
+
+    fn read_from<R: Read>(mut reader: R) -> Result<Self, SerializeError> {
+        let mut header_bytes = [0u8; HEADER_SIZE];
+        reader.read_exact(&mut header_bytes)?;
+        let header = Header::parse(&header_bytes)?;
+        header.expect(Self::KIND, Self::HASHER)?;
+
+        // read through take, so a bogus length cannot make us allocate it up front.
+        let mut body = Vec::new();
+        reader.take(header.body_len).read_to_end(&mut body)?;
+        if (body.len() as u64) < header.body_len {
+            return Err(SerializeError::Truncated);
+        }
+        if checksum(&body) != header.checksum {
+            return Err(SerializeError::ChecksumMismatch);
+        }
+        let mut dec = Decoder::new(&body);
+        let filter = Self::decode(&mut dec)?;
+        dec.finish()?;
+        return Ok(filter);
+    }
+}
+
+pub fn checksum(body: &[u8]) -> u64 {
+    return fastmurmur3::murmur3_x64_128(body, 0) as u64;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+    pub version: u16,
+    pub filter_kind: u16,
+    pub hasher_kind: u16,
+    pub body_len: u64,
+    pub checksum: u64,
+}
+
+impl Header {
+    fn new(filter: FilterKind, hasher: HasherKind, body: &[u8]) -> Header {
+        return Header {
+            version: FORMAT_VERSION,
+            filter_kind: filter as u16,
+            hasher_kind: hasher as u16,
+            body_len: body.len() as u64,
+            checksum: checksum(body),
+        };
+    }
+
+    pub fn to_bytes(&self) -> [u8; HEADER_SIZE] {
+        let mut bytes = [0u8; HEADER_SIZE];
+        bytes[0..4].copy_from_slice(&MAGIC);
+        bytes[4..6].copy_from_slice(&self.version.to_le_bytes());
+        bytes[6..8].copy_from_slice(&self.filter_kind.to_le_bytes());
+        bytes[8..10].copy_from_slice(&self.hasher_kind.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.body_len.to_le_bytes());
+        bytes[24..32].copy_from_slice(&self.checksum.to_le_bytes());
+        return bytes;
+    }
+
+    // checks the magic, version and reserved bytes only, the kinds are checked against what the caller expects.
+    pub fn parse(bytes: &[u8]) -> Result<Header, SerializeError> {
+        if bytes.len() < HEADER_SIZE {
+            return Err(SerializeError::Truncated);
+        }
+        if bytes[0..4] != MAGIC {
+            return Err(SerializeError::BadMagic);
+        }
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version != FORMAT_VERSION {
+            return Err(SerializeError::UnsupportedVersion(version));
+        }
+        if bytes[10..16].iter().any(|byte| *byte != 0) {
+            return Err(SerializeError::Corrupt("reserved header bytes are not zero"));
+        }
+        return Ok(Header {
+            version,
+            filter_kind: u16::from_le_bytes([bytes[6], bytes[7]]),
+            hasher_kind: u16::from_le_bytes([bytes[8], bytes[9]]),
+            body_len: u64::from_le_bytes(bytes[16..24].try_into().unwrap()),
+            checksum: u64::from_le_bytes(bytes[24..32].try_into().unwrap()),
+        });
+    }
+
+    pub fn expect(&self, filter: FilterKind, hasher: HasherKind) -> Result<(), SerializeError> {
+        if self.filter_kind != filter as u16 {
+            return Err(SerializeError::WrongFilter { expected: filter, found: self.filter_kind });
+        }
+        if self.hasher_kind != hasher as u16 {
+            return Err(SerializeError::WrongHasher { expected: hasher, found: self.hasher_kind });
+        }
+        return Ok(());
+    }
+}
+
+// validate a whole file held in memory and return its body.
+pub fn check_file(bytes: &[u8], filter: FilterKind, hasher: HasherKind) -> Result<&[u8], SerializeError> {
+    let body = file_body(bytes, filter, hasher)?;
+    if checksum(body) != Header::parse(bytes)?.checksum {
+        return Err(SerializeError::ChecksumMismatch);
+    }
+    return Ok(body);
+}
+
+// as check_file, but without reading the whole body to check the checksum.
+pub fn file_body(bytes: &[u8], filter: FilterKind, hasher: HasherKind) -> Result<&[u8], SerializeError> {
+    let header = Header::parse(bytes)?;
+    header.expect(filter, hasher)?;
+    let rest = &bytes[HEADER_SIZE..];
+    if (rest.len() as u64) < header.body_len {
+        return Err(SerializeError::Truncated);
+    }
+    return Ok(&rest[..header.body_len as usize]);
+}
+
+// writes the fields of a body.
+pub struct Encoder {
+    buf: Vec<u8>,
+}
+
+impl Default for Encoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Encoder {
+    pub fn new() -> Encoder {
+        return Encoder {
+            buf: Vec::new(),
+        };
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        return self.buf;
+    }
+
+    pub fn put_u64(&mut self, x: u64) {
+        self.buf.extend_from_slice(&x.to_le_bytes());
+    }
+
+    pub fn put_u8s(&mut self, xs: &[u8]) {
+        self.put_u64(xs.len() as u64);
+        self.buf.extend_from_slice(xs);
+        self.pad();
+    }
+
+    pub fn put_u16s(&mut self, xs: &[u16]) {
+        self.put_u64(xs.len() as u64);
+        for x in xs {
+            self.buf.extend_from_slice(&x.to_le_bytes());
+        }
+        self.pad();
+    }
+
+    pub fn put_u32s(&mut self, xs: &[u32]) {
+        self.put_u64(xs.len() as u64);
+        for x in xs {
+            self.buf.extend_from_slice(&x.to_le_bytes());
+        }
+        self.pad();
+    }
+
+    pub fn put_u64s(&mut self, xs: &[u64]) {
+        self.put_u64(xs.len() as u64);
+        for x in xs {
+            self.buf.extend_from_slice(&x.to_le_bytes());
+        }
+    }
+
+    pub fn put_hashers<H: SerializableHasher>(&mut self, hashers: &[H]) {
+        self.put_u64(hashers.len() as u64);
+        for hasher in hashers {
+            hasher.encode(self);
+        }
+    }
+
+    fn pad(&mut self) {
+        while !self.buf.len().is_multiple_of(8) {
+            self.buf.push(0);
+        }
+    }
+}
+
+// reads the fields of a body, failing rather than panicking on anything malformed.
+pub struct Decoder<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(buf: &'a [u8]) -> Decoder<'a> {
+        return Decoder {
+            buf,
+            pos: 0,
+        };
+    }
+
+    // the offset of the next field from the start of the body.
+    pub fn position(&self) -> usize {
+        return self.pos;
+    }
+
+    // every byte of the body has to be used.
+    pub fn finish(&self) -> Result<(), SerializeError> {
+        if self.pos != self.buf.len() {
+            return Err(SerializeError::Corrupt("trailing bytes after the filter"));
+        }
+        return Ok(());
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], SerializeError> {
+        if n > self.buf.len() - self.pos {
+            return Err(SerializeError::Corrupt("field runs past the end of the body"));
+        }
+        let bytes = &self.buf[self.pos..self.pos + n];
+        self.pos += n;
+        return Ok(bytes);
+    }
+
+    fn skip_padding(&mut self) -> Result<(), SerializeError> {
+        let padding = (8 - self.pos % 8) % 8;
+        self.take(padding)?;
+        return Ok(());
+    }
+
+    pub fn get_u64(&mut self) -> Result<u64, SerializeError> {
+        return Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()));
+    }
+
+    pub fn get_u32(&mut self) -> Result<u32, SerializeError> {
+        return u32::try_from(self.get_u64()?).map_err(|_| SerializeError::Corrupt("value out of range"));
+    }
+
+    pub fn get_usize(&mut self) -> Result<usize, SerializeError> {
+        return usize::try_from(self.get_u64()?).map_err(|_| SerializeError::Corrupt("value out of range"));
+    }
+
+    pub fn get_bool(&mut self) -> Result<bool, SerializeError> {
+        return match self.get_u64()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(SerializeError::Corrupt("value out of range")),
+        };
+    }
+
+    // the raw little endian bytes of an array of elements of the given width, without copying them.
+    pub fn get_array_bytes(&mut self, width: usize) -> Result<(usize, &'a [u8]), SerializeError> {
+        let len = self.get_usize()?;
+        let n = len.checked_mul(width).ok_or(SerializeError::Corrupt("array length out of range"))?;
+        let bytes = self.take(n)?;
+        self.skip_padding()?;
+        return Ok((len, bytes));
+    }
+
+    pub fn get_u8s(&mut self) -> Result<Vec<u8>, SerializeError> {
+        let (_, bytes) = self.get_array_bytes(1)?;
+        return Ok(bytes.to_vec());
+    }
+
+    pub fn get_u16s(&mut self) -> Result<Vec<u16>, SerializeError> {
+        let (_, bytes) = self.get_array_bytes(2)?;
+        return Ok(bytes.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect());
+    }
+
+    pub fn get_u32s(&mut self) -> Result<Vec<u32>, SerializeError> {
+        let (_, bytes) = self.get_array_bytes(4)?;
+        return Ok(bytes.chunks_exact(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect());
+    }
+
+    pub fn get_u64s(&mut self) -> Result<Vec<u64>, SerializeError> {
+        let (_, bytes) = self.get_array_bytes(8)?;
+        return Ok(bytes.chunks_exact(8).map(|c| u64::from_le_bytes(c.try_into().unwrap())).collect());
+    }
+
+    // the hash functions, which have to number exactly n if n is given.
+    pub fn get_hashers<H: SerializableHasher>(&mut self, n: Option<usize>) -> Result<Vec<H>, SerializeError> {
+        let count = self.get_usize()?;
+        if let Some(expected) = n {
+            if count != expected {
+                return Err(SerializeError::Corrupt("wrong number of hash functions"));
+            }
+        }
+        // each function takes at least one u64, which bounds the count by what is left.
+        if count > (self.buf.len() - self.pos) / 8 {
+            return Err(SerializeError::Corrupt("field runs past the end of the body"));
+        }
+        let mut hashers = Vec::with_capacity(count);
+        for _ in 0..count {
+            hashers.push(H::decode(self)?);
+        }
+        return Ok(hashers);
+    }
+}
+
+// fail with Corrupt(reason) unless the condition holds.
+pub(crate) fn ensure(condition: bool, reason: &'static str) -> Result<(), SerializeError> {
+    if !condition {
+        return Err(SerializeError::Corrupt(reason));
+    }
+    return Ok(());
+}
+
+// the checks shared by the xor filters and their views, so that member cannot index out of bounds.
+pub(crate) fn check_xor(size: u64, l: u32, fingerprints: usize) -> Result<(), SerializeError> {
+    ensure((size / 3) as u32 != 0, "size is too small")?;
+    ensure(l <= 64, "l out of range")?;
+    ensure(fingerprints as u64 == size, "fingerprints do not match the size")?;
+    return Ok(());
+}
+
+// the same for the binary fuse filters, whose keys land in `ways` consecutive segments.
+pub(crate) fn check_binary_fuse(ways: u64, size: u64, segment_length: u32, l: u32, log_segment: u32,
+                                num_segments: u64, fingerprints: usize) -> Result<(), SerializeError> {
+    ensure(l <= 64, "l out of range")?;
+    ensure(log_segment < 32 && segment_length == 1 << log_segment, "segment length is not 2^log_segment")?;
+    ensure(num_segments >= ways && num_segments <= u32::MAX as u64, "number of segments out of range")?;
+    ensure(num_segments.checked_mul(segment_length as u64).is_some_and(|n| n <= size), "segments overrun the filter")?;
+    ensure(fingerprints as u64 == size, "fingerprints do not match the size")?;
+    return Ok(());
+}
@@ -0,0 +1,377 @@
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+use rand_chacha::ChaCha8Rng;
+
+use crate::utils;
+
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::filter::{self, DynamicFilter, Filter, FilterStats};
+use crate::serialize::{ensure, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
+
+/*
+Split block Bloom filter (Putze, Sanders and Singler, 2007; the layout used by Impala, Kudu and Parquet).
+The filter is an array of 256 bit blocks, each eight 32 bit lanes. A key picks one block, and sets exactly one bit
+in every lane: lane i takes the top 5 bits of the key's 32 bit hash times SALTS[i]. So inserting or querying a key is a
+single 32 byte load, eight multiplies and a compare, which maps onto one AVX2 register.
+The kernel is picked once, when the filter is made, from what the CPU supports at runtime: AVX2, then SSE4.1, then
+plain Rust. They all set and test the same bits, so a filter can be saved on one machine and loaded on another.
+ */
+
+// the salts from the Impala and Parquet implementations. each is odd, so the multiply spreads the key over the lane.
+pub(crate) const SALTS: [u32; 8] = [0x47b6137b, 0x44974d91, 0x8824ad5b, 0xa2b7289d, 0x705495c7, 0x2df1424b, 0x9efc4947,
+    0x5c6bfb31];
+
+// 32 byte aligned, so a block is one aligned AVX2 load.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[repr(C, align(32))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Block(pub(crate) [u32; 8]);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Kernel {
+    Avx2,
+    Sse41,
+    Scalar,
+}
+
+impl Default for Kernel {
+    fn default() -> Kernel {
+        return Kernel::detect();
+    }
+}
+
+impl Kernel {
+    // the fastest kernel this CPU can run.
+    pub(crate) fn detect() -> Kernel {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("avx2") {
+                return Kernel::Avx2;
+            }
+            if is_x86_feature_detected!("sse4.1") {
+                return Kernel::Sse41;
+            }
+        }
+        return Kernel::Scalar;
+    }
+
+    // set the key's bit in every lane of the block.
+    pub(crate) fn insert(self, block: &mut Block, key: u32) {
+        match self {
+            // safe, since detect only picks a kernel the CPU supports.
+            #[cfg(target_arch = "x86_64")]
+            Kernel::Avx2 => unsafe { insert_avx2(block, key) },
+            #[cfg(target_arch = "x86_64")]
+            Kernel::Sse41 => unsafe { insert_sse41(block, key) },
+            _ => insert_scalar(block, key),
+        }
+    }
+
+    // true if the key's bit is set in every lane of the block.
+    pub(crate) fn check(self, block: &Block, key: u32) -> bool {
+        match self {
+            #[cfg(target_arch = "x86_64")]
+            Kernel::Avx2 => return unsafe { check_avx2(block, key) },
+            #[cfg(target_arch = "x86_64")]
+            Kernel::Sse41 => return unsafe { check_sse41(block, key) },
+            _ => return check_scalar(block, key),
+        }
+    }
+}
+
+fn mask_scalar(key: u32) -> [u32; 8] {
+    let mut mask = [0; 8];
+    for i in 0..8 {
+        mask[i] = 1 << (key.wrapping_mul(SALTS[i]) >> 27);
+    }
+    return mask;
+}
+
+fn insert_scalar(block: &mut Block, key: u32) {
+    let mask = mask_scalar(key);
+    for i in 0..8 {
+        block.0[i] |= mask[i];
+    }
+}
+
+fn check_scalar(block: &Block, key: u32) -> bool {
+    let mask = mask_scalar(key);
+    for i in 0..8 {
+        if block.0[i] & mask[i] == 0 {
+            return false;
+        }
+    }
+    return true;
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn mask_avx2(key: u32) -> __m256i {
+    let salts = _mm256_loadu_si256(SALTS.as_ptr() as *const __m256i);
+    let shifts = _mm256_srli_epi32::<27>(_mm256_mullo_epi32(_mm256_set1_epi32(key as i32), salts));
+    return _mm256_sllv_epi32(_mm256_set1_epi32(1), shifts);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn insert_avx2(block: &mut Block, key: u32) {
+    let ptr = block.0.as_mut_ptr() as *mut __m256i;
+    _mm256_store_si256(ptr, _mm256_or_si256(_mm256_load_si256(ptr), mask_avx2(key)));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn check_avx2(block: &Block, key: u32) -> bool {
+    let bits = _mm256_load_si256(block.0.as_ptr() as *const __m256i);
+    return _mm256_testc_si256(bits, mask_avx2(key)) != 0;
+}
+
+// SSE has no per lane shift, so 1 << n is made by putting n into the exponent of a float and converting it back.
+// 2^31 is out of range for the conversion, which gives 0x80000000, the bit wanted anyway.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+unsafe fn mask_sse41(key: u32) -> [__m128i; 2] {
+    let key = _mm_set1_epi32(key as i32);
+    let mut mask = [_mm_setzero_si128(); 2];
+    for half in 0..2 {
+        let salts = _mm_loadu_si128(SALTS.as_ptr().add(half * 4) as *const __m128i);
+        let shifts = _mm_srli_epi32::<27>(_mm_mullo_epi32(key, salts));
+        let exponents = _mm_add_epi32(_mm_slli_epi32::<23>(shifts), _mm_set1_epi32(0x3f800000));
+        mask[half] = _mm_cvttps_epi32(_mm_castsi128_ps(exponents));
+    }
+    return mask;
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+unsafe fn insert_sse41(block: &mut Block, key: u32) {
+    let mask = mask_sse41(key);
+    for half in 0..2 {
+        let ptr = block.0.as_mut_ptr().add(half * 4) as *mut __m128i;
+        _mm_store_si128(ptr, _mm_or_si128(_mm_load_si128(ptr), mask[half]));
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+unsafe fn check_sse41(block: &Block, key: u32) -> bool {
+    let mask = mask_sse41(key);
+    let lo = _mm_load_si128(block.0.as_ptr() as *const __m128i);
+    let hi = _mm_load_si128(block.0.as_ptr().add(4) as *const __m128i);
+    return _mm_testc_si128(lo, mask[0]) & _mm_testc_si128(hi, mask[1]) != 0;
+}
+
+// the false positive rate with bits_per_key bits for every key. the number of keys a in a block is Poisson around
+// 256 / bits_per_key, and each lane then has its bit set with chance 1 - (31/32)^a.
+pub(crate) fn false_positive_rate(bits_per_key: f64) -> f64 {
+    let lambda = 256.0 / bits_per_key;
+    let mut p = (-lambda).exp();
+    let mut rate = 0.0;
+    for a in 0..=(lambda + 20.0 * lambda.sqrt() + 20.0) as i32 {
+        rate += p * (1.0 - (31.0f64 / 32.0).powi(a)).powi(8);
+        p *= lambda / (a + 1) as f64;
+    }
+    return rate;
+}
+
+// the fewest blocks that keep expected_inserts keys under the false positive rate.
+pub(crate) fn num_blocks(expected_inserts: u64, false_positive_rate: f64) -> usize {
+    let (mut lo, mut hi) = (1.0, 256.0);
+    for _ in 0..64 {
+        let mid = (lo + hi) / 2.0;
+        if self::false_positive_rate(mid) > false_positive_rate {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    return ((expected_inserts as f64 * hi / 256.0).ceil() as usize).max(1);
+}
+
+// keys in the blocks, estimated lane by lane, as each key sets one bit in every lane of its block.
+pub(crate) fn estimate_keys(blocks: &[Block]) -> f64 {
+    let mut n = 0.0;
+    for block in blocks {
+        for lane in block.0 {
+            n += filter::swamidass_baldi(lane.count_ones() as u64, 32, 1);
+        }
+    }
+    return n / 8.0;
+}
+
+pub(crate) fn bits_set(blocks: &[Block]) -> u64 {
+    return blocks.iter().map(|block| block.0.iter().map(|lane| lane.count_ones() as u64).sum::<u64>()).sum();
+}
+
+// a key picks a block at random, then needs its bit in each of the eight lanes to be set.
+pub(crate) fn current_false_positive_rate(blocks: &[Block]) -> f64 {
+    let mut rate = 0.0;
+    for block in blocks {
+        rate += block.0.iter().map(|lane| lane.count_ones() as f64 / 32.0).product::<f64>();
+    }
+    return rate / blocks.len() as f64;
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimdBlockedBloomFilter<H = MultiplyShift> {
+    pub(crate) blocks: Vec<Block>,
+    // the first picks the block, the second gives the 32 bit hash the lanes are set from.
+    hash_functions: Vec<H>,
+    seed: u64,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    kernel: Kernel,
+}
+
+impl SimdBlockedBloomFilter {
+    pub fn new(expected_inserts: u64, false_positive_rate: f64) -> SimdBlockedBloomFilter {
+        return Self::with_hasher(expected_inserts, false_positive_rate);
+    }
+}
+
+impl<H: KeyHasher> SimdBlockedBloomFilter<H> {
+    // same as new, but draws the hash functions from H instead of multiply-shift.
+    pub fn with_hasher(expected_inserts: u64, false_positive_rate: f64) -> Self {
+        return Self::with_seed(expected_inserts, false_positive_rate, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    pub fn with_seed(expected_inserts: u64, false_positive_rate: f64, seed: u64) -> Self {
+        let mut rng = utils::seeded_rng(seed);
+        SimdBlockedBloomFilter {
+            blocks: vec![Block::default(); num_blocks(expected_inserts, false_positive_rate)],
+            hash_functions: Self::generate_hash_functions(&mut rng),
+            seed,
+            kernel: Kernel::detect(),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    fn generate_hash_functions(rng: &mut ChaCha8Rng) -> Vec<H> {
+        return vec![H::from_rng(rng), H::from_rng(rng)];
+    }
+
+    fn locate(&self, key: u64) -> (usize, u32) {
+        let block = (self.hash_functions[0].hash_bits(key, 32) as u64 * self.blocks.len() as u64) >> 32;
+        return (block as usize, self.hash_functions[1].hash_bits(key, 32));
+    }
+}
+
+impl<H: KeyHasher> DynamicFilter for SimdBlockedBloomFilter<H> {
+    fn insert(&mut self, key: u64) -> bool {
+        let (block, lanes) = self.locate(key);
+        self.kernel.insert(&mut self.blocks[block], lanes);
+        return true;
+    }
+}
+
+impl<H: KeyHasher> Filter for SimdBlockedBloomFilter<H> {
+    fn member(&self, key: u64) -> bool {
+        let (block, lanes) = self.locate(key);
+        return self.kernel.check(&self.blocks[block], lanes);
+    }
+}
+
+impl<H: SerializableHasher> SerializableFilter for SimdBlockedBloomFilter<H> {
+    const KIND: FilterKind = FilterKind::SimdBlockedBloom;
+    const HASHER: HasherKind = H::KIND;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_hashers(&self.hash_functions);
+        let lanes: Vec<u32> = self.blocks.iter().flat_map(|block| block.0).collect();
+        enc.put_u32s(&lanes);
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let hash_functions = dec.get_hashers(Some(2))?;
+        let lanes = dec.get_u32s()?;
+        ensure(!lanes.is_empty() && lanes.len().is_multiple_of(8), "blocks are not a whole number of 256 bits")?;
+        return Ok(SimdBlockedBloomFilter {
+            blocks: lanes.chunks_exact(8).map(|lane| Block(lane.try_into().unwrap())).collect(),
+            hash_functions,
+            seed,
+            kernel: Kernel::detect(),
+        });
+    }
+}
+
+impl<H: KeyHasher> FilterStats for SimdBlockedBloomFilter<H> {
+    fn len(&self) -> u64 {
+        return estimate_keys(&self.blocks).round() as u64;
+    }
+
+    fn capacity(&self) -> u64 {
+        return filter::half_full(self.slots(), 8);
+    }
+
+    fn slots(&self) -> u64 {
+        return self.blocks.len() as u64 * 256;
+    }
+
+    fn occupied(&self) -> u64 {
+        return bits_set(&self.blocks);
+    }
+
+    fn size_in_bits(&self) -> usize {
+        return self.blocks.len() * 256;
+    }
+
+    fn false_positive_rate(&self) -> f64 {
+        return current_false_positive_rate(&self.blocks);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    // enough blocks that few bits are set in each, so a wrong bit shows.
+    const BLOCKS: usize = 4096;
+
+    // every kernel this CPU can run, the scalar one first.
+    fn available_kernels() -> Vec<Kernel> {
+        let mut kernels = vec![Kernel::Scalar];
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("sse4.1") {
+                kernels.push(Kernel::Sse41);
+            }
+            if is_x86_feature_detected!("avx2") {
+                kernels.push(Kernel::Avx2);
+            }
+        }
+        return kernels;
+    }
+
+    // the kernels have to set and test exactly the same bits, or a saved filter would only load on a similar CPU.
+    #[test]
+    fn kernels_agree() {
+        let mut rng = utils::seeded_rng(0);
+        let keys: Vec<u32> = (0..20_000).map(|_| rng.gen()).collect();
+        let lookups: Vec<u32> = (0..20_000).map(|_| rng.gen()).collect();
+        let mut expected = vec![Block::default(); BLOCKS];
+        for key in &keys {
+            Kernel::Scalar.insert(&mut expected[*key as usize % BLOCKS], *key);
+        }
+        for kernel in available_kernels() {
+            let mut blocks = vec![Block::default(); BLOCKS];
+            for key in &keys {
+                kernel.insert(&mut blocks[*key as usize % BLOCKS], *key);
+            }
+            for (block, expected_block) in blocks.iter().zip(&expected) {
+                assert_eq!(block.0, expected_block.0, "{:?} set different bits", kernel);
+            }
+            for key in keys.iter().chain(&lookups) {
+                let block = &blocks[*key as usize % BLOCKS];
+                assert_eq!(kernel.check(block, *key), Kernel::Scalar.check(block, *key), "{:?} disagrees", kernel);
+            }
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use chrono::{DateTime, Utc};
+use rand_chacha::ChaCha8Rng;
+
+use crate::utils;
+
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::filter::{self, DynamicFilter, Filter, FilterStats};
+use crate::serialize::{ensure, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
+use crate::window::{Clock, Window};
+
+/*
+Stable Bloom filter (Deng and Rafiei, 2006).
+A counting Bloom filter for an unbounded stream that forgets old keys, so that its fill, and its false positive rate,
+settle at a stable level however many keys go in. A key sets its k cells to MAX, and before each insert P cells
+are decremented, so a cell that no key sets again runs down to zero.
+The paper decrements P cells at random, which can forget a key at any time. Here the decrements sweep the cells in
+order at the rate the window needs, so every cell is decremented once a sweep and a cell set to MAX lasts MAX - 1
+whole sweeps. The sweeps are timed so that MAX - 1 of them take a window, which means a key is never a false negative
+in the window, and is forgotten within MAX / (MAX - 1) windows.
+ */
+
+// the value a key sets its cells to. more means keys are forgotten closer to the end of the window, but the sweep has
+// to decrement more cells each insert.
+const MAX: u8 = 7;
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StableBloomFilter<H = MultiplyShift> {
+    count_array: Vec<u8>,
+    hash_functions: Vec<H>,
+    seed: u64,
+    // the next cell the sweep decrements.
+    sweep: usize,
+    expected_inserts: u64,
+    clock: Clock,
+}
+
+impl StableBloomFilter {
+    pub fn new(window: Window, false_positive_rate: f64) -> StableBloomFilter {
+        return Self::with_hasher(window, false_positive_rate);
+    }
+}
+
+impl<H: KeyHasher> StableBloomFilter<H> {
+    // same as new, but draws the hash functions from H instead of multiply-shift.
+    pub fn with_hasher(window: Window, false_positive_rate: f64) -> Self {
+        return Self::with_seed(window, false_positive_rate, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    pub fn with_seed(window: Window, false_positive_rate: f64, seed: u64) -> Self {
+        assert!(false_positive_rate > 0.0 && false_positive_rate < 1.0, "false positive rate must be between 0 and 1");
+        let mut rng = utils::seeded_rng(seed);
+        let expected_inserts = window.expected_inserts().max(1);
+        // a key is held for between 1 and MAX / (MAX - 1) windows, so on average the cells hold the keys of this many.
+        let held = expected_inserts as f64 * (MAX as f64 - 0.5) / (MAX as f64 - 1.0);
+        let size: u64 = ((-1.44 * held).ceil() * false_positive_rate.log2() + 0.5) as u64;
+        let size = size.clamp(1, u32::MAX as u64);
+        let num_hashes = ((-false_positive_rate.log2() + 0.5) as usize).max(1);
+        // one fewer than MAX - 1 sweeps, so that the steps taken between two inserts a window apart never reach a
+        // cell MAX times.
+        let steps_per_window = size * (MAX as u64 - 1) - 1;
+        StableBloomFilter {
+            count_array: vec![0; size as usize],
+            hash_functions: Self::generate_hash_functions(num_hashes, &mut rng),
+            seed,
+            sweep: 0,
+            expected_inserts,
+            clock: Clock::new(window, steps_per_window.max(1)),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    // as insert, at the time now rather than the time on the clock. a filter with a window of inserts ignores now.
+    // times that go backwards are taken to be the latest time seen.
+    pub fn insert_at(&mut self, key: u64, now: DateTime<Utc>) -> bool {
+        let steps = self.clock.insert(now);
+        self.decrement(steps);
+        for i in 0..self.hash_functions.len() {
+            let index = self.index(key, i);
+            self.count_array[index] = MAX;
+        }
+        return true;
+    }
+
+    // forget the keys that have left a timed window. inserts do this anyway, so it is only needed when the stream
+    // goes quiet.
+    pub fn expire(&mut self) {
+        self.expire_at(Utc::now());
+    }
+
+    pub fn expire_at(&mut self, now: DateTime<Utc>) {
+        let steps = self.clock.advance(now);
+        self.decrement(steps);
+    }
+
+    fn generate_hash_functions(n: usize, rng: &mut ChaCha8Rng) -> Vec<H> {
+        let mut hash_functions = Vec::new();
+        for _ in 0..n {
+            hash_functions.push(H::from_rng(rng));
+        }
+        return hash_functions;
+    }
+
+    // the 64 bit hash is scaled to the cells, rather than taken modulo their number, in 128 bits so that it cannot
+    // overflow however many cells there are.
+    fn index(&self, key: u64, i: usize) -> usize {
+        let hash = self.hash_functions[i].hash(key) as u128;
+        return ((hash * self.count_array.len() as u128) >> 64) as usize;
+    }
+
+    // decrement the next steps cells of the sweep. MAX whole sweeps empty the filter, so there is no need for more.
+    fn decrement(&mut self, steps: u64) {
+        let size = self.count_array.len();
+        if steps >= size as u64 * MAX as u64 {
+            self.count_array.fill(0);
+            self.sweep = (self.sweep + (steps % size as u64) as usize) % size;
+            return;
+        }
+        for _ in 0..steps {
+            self.count_array[self.sweep] = self.count_array[self.sweep].saturating_sub(1);
+            self.sweep += 1;
+            if self.sweep == size {
+                self.sweep = 0;
+            }
+        }
+    }
+}
+
+impl<H: KeyHasher> DynamicFilter for StableBloomFilter<H> {
+    fn insert(&mut self, key: u64) -> bool {
+        return self.insert_at(key, Utc::now());
+    }
+}
+
+impl<H: KeyHasher> Filter for StableBloomFilter<H> {
+    fn member(&self, key: u64) -> bool {
+        for i in 0..self.hash_functions.len() {
+            if self.count_array[self.index(key, i)] == 0 {
+                return false;
+            }
+        }
+        return true;
+    }
+}
+
+impl<H: KeyHasher> FilterStats for StableBloomFilter<H> {
+    // estimated from the cells still set, so it counts the keys that have not been forgotten yet.
+    fn len(&self) -> u64 {
+        let k = self.hash_functions.len() as u64;
+        return filter::swamidass_baldi(self.occupied(), self.slots(), k).round() as u64;
+    }
+
+    fn capacity(&self) -> u64 {
+        return self.expected_inserts;
+    }
+
+    fn slots(&self) -> u64 {
+        return self.count_array.len() as u64;
+    }
+
+    fn occupied(&self) -> u64 {
+        return self.count_array.iter().filter(|count| **count != 0).count() as u64;
+    }
+
+    fn size_in_bits(&self) -> usize {
+        return self.count_array.len() * 8;
+    }
+
+    fn false_positive_rate(&self) -> f64 {
+        return self.fill_ratio().powi(self.hash_functions.len() as i32);
+    }
+}
+
+impl<H: SerializableHasher> SerializableFilter for StableBloomFilter<H> {
+    const KIND: FilterKind = FilterKind::StableBloom;
+    const HASHER: HasherKind = H::KIND;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_u64(self.sweep as u64);
+        enc.put_u64(self.expected_inserts);
+        self.clock.encode(enc);
+        enc.put_hashers(&self.hash_functions);
+        enc.put_u8s(&self.count_array);
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let sweep = dec.get_usize()?;
+        let expected_inserts = dec.get_u64()?;
+        let clock = Clock::decode(dec)?;
+        let hash_functions = dec.get_hashers(None)?;
+        let count_array = dec.get_u8s()?;
+        ensure(!hash_functions.is_empty(), "no hash functions")?;
+        ensure(!count_array.is_empty() && count_array.len() as u64 <= u32::MAX as u64, "cell count out of range")?;
+        ensure(sweep < count_array.len(), "sweep out of range")?;
+        ensure(count_array.iter().all(|count| *count <= MAX), "cell above its maximum")?;
+        return Ok(StableBloomFilter {
+            count_array,
+            hash_functions,
+            seed,
+            sweep,
+            expected_inserts,
+            clock,
+        });
+    }
+}
@@ -1,19 +1,30 @@
 use rand::Rng;
 
-pub(crate) struct TabulationHashing {
-    lookups: Vec<Vec<u64>>
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TabulationHashing {
+    pub(crate) lookups: Vec<Vec<u64>>
 }
+impl Default for TabulationHashing {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TabulationHashing {
-    pub(crate) fn new() -> Self {
-        let lookups = Self::generate_lookups();
+    pub fn new() -> Self {
+        return Self::from_rng(&mut rand::thread_rng());
+    }
+
+    pub fn from_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let lookups = Self::generate_lookups(rng);
         TabulationHashing {
             lookups
         }
     }
 
-    fn generate_lookups() -> Vec<Vec<u64>> {
+    fn generate_lookups<R: Rng + ?Sized>(rng: &mut R) -> Vec<Vec<u64>> {
         let mut lookups = vec![];
-        let mut rng = rand::thread_rng();
         for _ in 0..16 {
             let mut lookup = vec![];
             for _ in 0..16 {
@@ -25,30 +36,30 @@ impl TabulationHashing {
         return lookups
     }
 
-    fn hash(&self, x: u8, i: usize) -> u64 {
+    fn lookup(&self, x: u8, i: usize) -> u64 {
         return self.lookups[i][x as usize];
     }
 
-    pub(crate) fn tabulation_hashing(&self, x: u64) -> u64 {
+    pub fn tabulation_hashing(&self, x: u64) -> u64 {
         // obtain hex digits from key and xor lookups together.
         let mut res = 0;
-        res ^= self.hash((x) as u8 & 0x0F, 0);
-        res ^= self.hash((x >> 4) as u8 & 0x0F, 1);
-        res ^= self.hash((x >> 8) as u8 & 0x0F, 2);
-        res ^= self.hash((x >> 12) as u8 & 0x0F, 3);
-        res ^= self.hash((x >> 16) as u8 & 0x0F, 4);
-        res ^= self.hash((x >> 20) as u8 & 0x0F, 5);
-        res ^= self.hash((x >> 24) as u8 & 0x0F, 6);
-        res ^= self.hash((x >> 28) as u8 & 0x0F, 7);
-        res ^= self.hash((x >> 32) as u8 & 0x0F, 8);
-        res ^= self.hash((x >> 36) as u8 & 0x0F, 9);
-        res ^= self.hash((x >> 40) as u8 & 0x0F, 10);
-        res ^= self.hash((x >> 44) as u8 & 0x0F, 11);
-        res ^= self.hash((x >> 48) as u8 & 0x0F, 12);
-        res ^= self.hash((x >> 52) as u8 & 0x0F, 13);
-        res ^= self.hash((x >> 56) as u8 & 0x0F, 14);
-        res ^= self.hash((x >> 60) as u8 & 0x0F, 15);
-        // res ^= self.hash((x >> 64) as u8 & 0x0F, 16);
+        res ^= self.lookup((x) as u8 & 0x0F, 0);
+        res ^= self.lookup((x >> 4) as u8 & 0x0F, 1);
+        res ^= self.lookup((x >> 8) as u8 & 0x0F, 2);
+        res ^= self.lookup((x >> 12) as u8 & 0x0F, 3);
+        res ^= self.lookup((x >> 16) as u8 & 0x0F, 4);
+        res ^= self.lookup((x >> 20) as u8 & 0x0F, 5);
+        res ^= self.lookup((x >> 24) as u8 & 0x0F, 6);
+        res ^= self.lookup((x >> 28) as u8 & 0x0F, 7);
+        res ^= self.lookup((x >> 32) as u8 & 0x0F, 8);
+        res ^= self.lookup((x >> 36) as u8 & 0x0F, 9);
+        res ^= self.lookup((x >> 40) as u8 & 0x0F, 10);
+        res ^= self.lookup((x >> 44) as u8 & 0x0F, 11);
+        res ^= self.lookup((x >> 48) as u8 & 0x0F, 12);
+        res ^= self.lookup((x >> 52) as u8 & 0x0F, 13);
+        res ^= self.lookup((x >> 56) as u8 & 0x0F, 14);
+        res ^= self.lookup((x >> 60) as u8 & 0x0F, 15);
+        // res ^= self.lookup((x >> 64) as u8 & 0x0F, 16);
         // for i in 0..16 {
         //     let digit = (x >> (i * 4)) as u8 & 0x0F;
         //     // println!("{}", digit);
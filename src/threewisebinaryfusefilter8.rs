@@ -1,16 +1,20 @@
-use rand::Rng;
-use std::collections::{HashSet, VecDeque};
-use std::ptr::null;
+use std::collections::VecDeque;
 
 
-#[path = "utils.rs"]
-mod utils;
-use utils::hash;
+use crate::utils;
 use utils::log_base;
 
-pub struct ThreeWiseBinaryFuseFilter8 {
+use rand_chacha::ChaCha8Rng;
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::filter::{Filter, FilterStats, StaticFilter};
+use crate::serialize::{check_binary_fuse, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThreeWiseBinaryFuseFilter8<H = MultiplyShift> {
     fingerprints: Vec<u8>,
-    hashes: Vec<(u64,u64,u64)>,
+    hashes: Vec<H>,
+    seed: u64,
+    #[cfg_attr(feature = "serde", serde(skip))]
     sigma: Vec<(u64, usize)>,
     size: u64,
     segment_length: u32,
@@ -19,10 +23,24 @@ pub struct ThreeWiseBinaryFuseFilter8 {
     num_segments: u64,
 }
 impl ThreeWiseBinaryFuseFilter8 {
-    pub fn new(keys: Vec<u64>) -> ThreeWiseBinaryFuseFilter8 {
+    pub fn new(keys: &[u64]) -> ThreeWiseBinaryFuseFilter8 {
+        return Self::with_hasher(keys);
+    }
+}
+
+impl<H: KeyHasher> ThreeWiseBinaryFuseFilter8<H> {
+    // same as new, but draws the hash functions from H instead of multiply-shift.
+    pub fn with_hasher(keys: &[u64]) -> Self {
+        return Self::with_seed(keys, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    pub fn with_seed(keys: &[u64], seed: u64) -> Self {
+        let mut rng = utils::seeded_rng(seed);
         let mut filter = ThreeWiseBinaryFuseFilter8 {
             fingerprints: vec![],
             hashes: vec![],
+            seed,
             sigma: vec![],
             size: 0,
             segment_length: 0,
@@ -37,33 +55,29 @@ impl ThreeWiseBinaryFuseFilter8 {
         filter.segment_length = 2u32.pow(exp);
         filter.log_segment = log_base(filter.segment_length as f64, 2f64) as u32;
         filter.num_segments = filter.size / filter.segment_length as u64;
-        if (filter.num_segments < 3 ) {
+        if filter.num_segments < 3  {
             filter.size = (filter.segment_length * 3) as u64;
             filter.num_segments = 3;
             filter.l =  log_base(filter.size as f64, 2f64) as u32;
         }
-        filter.construct(keys);
+        filter.construct(keys, &mut rng);
         return filter;
     }
-    pub fn member(&self, k: u64) -> bool {
-        let (h0,h1,h2) = self.segmented_hash(k);
-        let f = self.fingerprint(k);
-        return f == (self.fingerprints[h0 as usize] ^ self.fingerprints[h1 as usize] ^ self.fingerprints[h2 as usize]);
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
     }
-    fn construct(&mut self, keys: Vec<u64>) {
+
+    fn construct(&mut self, keys: &[u64], rng: &mut ChaCha8Rng) {
         let mut finished = false;
         while !finished {
-            let mut rng = rand::thread_rng();
             let mut hash_functions = Vec::new();
 
             for _ in 0..=3 {
-                let a1: u64 = rng.gen_range(1..=u64::MAX );
-                let a2: u64 = rng.gen_range(1..=u64::MAX);
-                let b: u64 = rng.gen_range(1..=u64::MAX);
-                hash_functions.push((a1,a2,b));
+                hash_functions.push(H::from_rng(rng));
             }
             self.hashes = hash_functions;
-            if self.mapping(&keys) {
+            if self.mapping(keys) {
                 finished = true;
                 //println!("mapping succeeded!");
                 self.assign();
@@ -73,7 +87,7 @@ impl ThreeWiseBinaryFuseFilter8 {
             }
         }
     }
-    fn mapping(&mut self, keys: &Vec<u64>) -> bool {
+    fn mapping(&mut self, keys: &[u64]) -> bool {
         let c: u64 = self.size;
         let mut h: Vec<(u64,usize)> = vec![(0,0); c as usize];
         let mut sorted : Vec<Vec<u64>> = vec![vec![]; self.num_segments as usize];
@@ -145,7 +159,7 @@ impl ThreeWiseBinaryFuseFilter8 {
     }
 
     pub(crate) fn fingerprint(&self, key: u64) -> u8 {
-        return hash(key,self.l, self.hashes[0].0, self.hashes[0].1, self.hashes[0].2) as u8;
+        return self.hashes[0].hash_bits(key, self.l) as u8;
     }
 
     // select a segment via a hash function. hash three times in [segment_length]. return 3 indexes in 3 consecutive segments.
@@ -154,21 +168,123 @@ impl ThreeWiseBinaryFuseFilter8 {
         // hash in segment range. using the three hash functions.
         let s_length = self.segment_length;
         // hash this to the correct range. then complete.
-        let segment_id = hash(key,self.l, self.hashes[3].0, self.hashes[3].1, self.hashes[3].2)
+        let segment_id = self.hashes[3].hash_bits(key, self.l)
             % (self.num_segments-2) as u32;
-        let h0 = (hash(key,self.log_segment, self.hashes[0].0, self.hashes[0].1, self.hashes[0].2) ) +
+        let h0 = (self.hashes[0].hash_bits(key, self.log_segment) ) +
             (segment_id * s_length);
-        let h1 = (hash(key,self.log_segment, self.hashes[1].0, self.hashes[1].1, self.hashes[1].2)) +
+        let h1 = (self.hashes[1].hash_bits(key, self.log_segment)) +
             ((segment_id+1) * s_length);
-        let h2 = (hash(key,self.log_segment, self.hashes[2].0, self.hashes[2].1, self.hashes[2].2)) +
+        let h2 = (self.hashes[2].hash_bits(key, self.log_segment)) +
             ((segment_id+2) * s_length);
 
         return (h0,h1,h2);
     }
 
-    fn get_segment(&self, key: u64) -> (u32) {
-        return hash(key,self.l, self.hashes[3].0, self.hashes[3].1, self.hashes[3].2) % (self.num_segments-2) as u32;
+    fn get_segment(&self, key: u64) -> u32  {
+        return self.hashes[3].hash_bits(key, self.l) % (self.num_segments-2) as u32;
+    }
+
+
+}
+
+impl<H: KeyHasher> Filter for ThreeWiseBinaryFuseFilter8<H> {
+    fn member(&self, k: u64) -> bool {
+        let (h0,h1,h2) = self.segmented_hash(k);
+        let f = self.fingerprint(k);
+        return f == (self.fingerprints[h0 as usize] ^ self.fingerprints[h1 as usize] ^ self.fingerprints[h2 as usize]);
+    }
+
+    // hash a batch of keys and prefetch their three fingerprints, then check them.
+    fn member_batch(&self, keys: &[u64], out: &mut [bool]) {
+        assert_eq!(keys.len(), out.len(), "member_batch needs one output per key");
+        let mut slots = [(0u32, 0u32, 0u32); utils::BATCH];
+        for (keys, out) in keys.chunks(utils::BATCH).zip(out.chunks_mut(utils::BATCH)) {
+            for i in 0..keys.len() {
+                slots[i] = self.segmented_hash(keys[i]);
+                utils::prefetch(&self.fingerprints[slots[i].0 as usize]);
+                utils::prefetch(&self.fingerprints[slots[i].1 as usize]);
+                utils::prefetch(&self.fingerprints[slots[i].2 as usize]);
+            }
+            for i in 0..keys.len() {
+                let (h0, h1, h2) = slots[i];
+                out[i] = self.fingerprint(keys[i]) ==
+                    (self.fingerprints[h0 as usize] ^ self.fingerprints[h1 as usize] ^ self.fingerprints[h2 as usize]);
+            }
+        }
+    }
+}
+
+impl<H: KeyHasher> StaticFilter for ThreeWiseBinaryFuseFilter8<H> {
+    fn from_keys(keys: &[u64]) -> Self {
+        return Self::with_hasher(keys);
+    }
+}
+
+impl<H: SerializableHasher> SerializableFilter for ThreeWiseBinaryFuseFilter8<H> {
+    const KIND: FilterKind = FilterKind::ThreeWiseBinaryFuse8;
+    const HASHER: HasherKind = H::KIND;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_u64(self.size);
+        enc.put_u64(self.segment_length as u64);
+        enc.put_u64(self.l as u64);
+        enc.put_u64(self.log_segment as u64);
+        enc.put_u64(self.num_segments);
+        enc.put_hashers(&self.hashes);
+        enc.put_u8s(&self.fingerprints);
     }
 
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let size = dec.get_u64()?;
+        let segment_length = dec.get_u32()?;
+        let l = dec.get_u32()?;
+        let log_segment = dec.get_u32()?;
+        let num_segments = dec.get_u64()?;
+        let hashes = dec.get_hashers(Some(4))?;
+        let fingerprints = dec.get_u8s()?;
+        check_binary_fuse(3, size, segment_length, l, log_segment, num_segments, fingerprints.len())?;
+        return Ok(ThreeWiseBinaryFuseFilter8 {
+            fingerprints,
+            hashes,
+            seed,
+            sigma: vec![],
+            size,
+            segment_length,
+            l,
+            log_segment,
+            num_segments,
+        });
+    }
+}
 
-}
\ No newline at end of file
+impl<H: KeyHasher> FilterStats for ThreeWiseBinaryFuseFilter8<H> {
+    // the table was sized to 1.125n + 32 slots for n keys, so this is the n it was built from, give or take
+    // rounding. a filter too small for the minimum number of segments was given more slots, and overcounts.
+    fn len(&self) -> u64 {
+        return (self.size.saturating_sub(32) as f64 / 1.125).round() as u64;
+    }
+
+    fn capacity(&self) -> u64 {
+        return self.len();
+    }
+
+    fn slots(&self) -> u64 {
+        return self.size;
+    }
+
+    // building assigns every key a slot of its own.
+    fn occupied(&self) -> u64 {
+        return self.len();
+    }
+
+    fn size_in_bits(&self) -> usize {
+        return self.fingerprints.len() * 8;
+    }
+
+    // fingerprints are l bit hashes, cut to 8 bits.
+    fn false_positive_rate(&self) -> f64 {
+        return 0.5f64.powi(self.l.min(8) as i32);
+    }
+}
@@ -0,0 +1,121 @@
+use chrono::{DateTime, Utc};
+
+use crate::serialize::{ensure, Decoder, Encoder, SerializeError};
+
+/*
+How long the forgetful filters (age partitioned and stable Bloom) remember a key for.
+A key is always a member for the whole window after it was inserted, and is forgotten some time after that.
+A window can be a number of inserts, or a span of time, read from the clock or given with each insert.
+The filter moves forward in steps, e.g. one generation of an age partitioned filter. Clock turns the inserts or time
+that have passed into how many steps the filter should have taken, spread evenly over the window.
+ */
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Window {
+    // remember a key for at least this many inserts after its own.
+    Inserts(u64),
+    // remember a key for at least this long. the filter is sized for expected_inserts keys arriving in that time.
+    Time { duration: chrono::Duration, expected_inserts: u64 },
+}
+
+impl Window {
+    // the keys inserted over one window.
+    pub(crate) fn expected_inserts(&self) -> u64 {
+        match self {
+            Window::Inserts(inserts) => return *inserts,
+            Window::Time { expected_inserts, .. } => return *expected_inserts,
+        }
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Clock {
+    // the window in inserts, or in microseconds if it is timed.
+    window: u64,
+    timed: bool,
+    // microseconds since the unix epoch at the first insert, for a timed clock.
+    start: Option<i64>,
+    // inserts, or microseconds since start, so far. never goes backwards.
+    ticks: u64,
+    steps: u64,
+    steps_per_window: u64,
+}
+
+impl Clock {
+    pub(crate) fn new(window: Window, steps_per_window: u64) -> Clock {
+        let (window, timed) = match window {
+            Window::Inserts(inserts) => (inserts, false),
+            Window::Time { duration, .. } => (duration.num_microseconds().unwrap_or(i64::MAX) as u64, true),
+        };
+        assert!(window > 0, "the window must be at least one insert or microsecond long");
+        assert!(steps_per_window > 0, "a window needs at least one step");
+        return Clock {
+            window,
+            timed,
+            start: None,
+            ticks: 0,
+            steps: 0,
+            steps_per_window,
+        };
+    }
+
+    // an insert at the time now, which a clock counting inserts ignores. returns the steps the filter has to take
+    // before it adds the key.
+    pub(crate) fn insert(&mut self, now: DateTime<Utc>) -> u64 {
+        if self.timed {
+            return self.advance(now);
+        }
+        self.ticks += 1;
+        return self.catch_up();
+    }
+
+    // the time is now, without an insert. returns the steps the filter has to take.
+    pub(crate) fn advance(&mut self, now: DateTime<Utc>) -> u64 {
+        if !self.timed {
+            return 0;
+        }
+        let now = now.timestamp_micros();
+        let start = *self.start.get_or_insert(now);
+        self.ticks = self.ticks.max(now.saturating_sub(start).max(0) as u64);
+        return self.catch_up();
+    }
+
+    // between two ticks at most a window apart, at most steps_per_window steps are taken.
+    fn catch_up(&mut self) -> u64 {
+        let target = (self.ticks as u128 * self.steps_per_window as u128 / self.window as u128) as u64;
+        let steps = target - self.steps;
+        self.steps = target;
+        return steps;
+    }
+
+    pub(crate) fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.window);
+        enc.put_u64(self.timed as u64);
+        enc.put_u64(self.start.is_some() as u64);
+        enc.put_u64(self.start.unwrap_or(0) as u64);
+        enc.put_u64(self.ticks);
+        enc.put_u64(self.steps);
+        enc.put_u64(self.steps_per_window);
+    }
+
+    pub(crate) fn decode(dec: &mut Decoder) -> Result<Clock, SerializeError> {
+        let window = dec.get_u64()?;
+        let timed = dec.get_bool()?;
+        let started = dec.get_bool()?;
+        let start = dec.get_u64()? as i64;
+        let ticks = dec.get_u64()?;
+        let steps = dec.get_u64()?;
+        let steps_per_window = dec.get_u64()?;
+        ensure(window > 0 && steps_per_window > 0, "empty window")?;
+        ensure(steps == (ticks as u128 * steps_per_window as u128 / window as u128) as u64, "clock is not caught up")?;
+        return Ok(Clock {
+            window,
+            timed,
+            start: if started { Some(start) } else { None },
+            ticks,
+            steps,
+            steps_per_window,
+        });
+    }
+}
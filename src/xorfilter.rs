@@ -1,21 +1,40 @@
-use rand::Rng;
-use std::collections::{HashSet, VecDeque};
-#[path = "utils.rs"]
-mod utils;
-use utils::hash;
+use std::collections::VecDeque;
+use crate::utils;
 
-pub struct XorFilter {
+use rand_chacha::ChaCha8Rng;
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::filter::{Filter, FilterStats, StaticFilter};
+use crate::serialize::{check_xor, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct XorFilter32<H = MultiplyShift> {
     fingerprints: Vec<u32>,
-    hashes: Vec<(u64,u64,u64)>,
+    hashes: Vec<H>,
+    seed: u64,
+    #[cfg_attr(feature = "serde", serde(skip))]
     sigma: Vec<(u64, usize)>,
     size: u64,
     l: u32
 }
-impl XorFilter {
-    pub fn new(keys: Vec<u64>) -> XorFilter {
-        let mut filter = XorFilter {
+impl XorFilter32 {
+    pub fn new(keys: &[u64]) -> XorFilter32 {
+        return Self::with_hasher(keys);
+    }
+}
+
+impl<H: KeyHasher> XorFilter32<H> {
+    // same as new, but draws the hash functions from H instead of multiply-shift.
+    pub fn with_hasher(keys: &[u64]) -> Self {
+        return Self::with_seed(keys, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    pub fn with_seed(keys: &[u64], seed: u64) -> Self {
+        let mut rng = utils::seeded_rng(seed);
+        let mut filter = XorFilter32 {
             fingerprints: vec![],
             hashes: vec![],
+            seed,
             sigma: vec![],
             size: 0,
             l: 0,
@@ -26,37 +45,31 @@ impl XorFilter {
         //     filter.size = 10000;
         // }
         filter.l = utils::log_base(filter.size as f64, 2f64) as u32;
-        filter.construct(keys);
+        filter.construct(keys, &mut rng);
         return filter;
     }
-    pub fn member(&self, k: u64) -> bool {
-        let h0 = self.hash0(k);
-        let h1 = self.hash1(k);
-        let h2 = self.hash2(k);
-        let f = self.fingerprint(k);
-        return f == (self.fingerprints[h0 as usize] ^ self.fingerprints[h1 as usize] ^ self.fingerprints[h2 as usize]);
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
     }
-    fn construct(&mut self, keys: Vec<u64>) {
+
+    fn construct(&mut self, keys: &[u64], rng: &mut ChaCha8Rng) {
         let mut finished = false;
         while !finished {
-            let mut rng = rand::thread_rng();
             let mut hash_functions = Vec::new();
 
             for _ in 0..=2 {
-                let a1: u64 = rng.gen_range(1..=u64::MAX );
-                let a2: u64 = rng.gen_range(1..=u64::MAX);
-                let b: u64 = rng.gen_range(1..=u64::MAX);
-                hash_functions.push((a1,a2,b));
+                hash_functions.push(H::from_rng(rng));
             }
             self.hashes = hash_functions;
-            if self.mapping(&keys) {
+            if self.mapping(keys) {
                 finished = true;
                 //println!("mapping succeeded!");
                 self.assign();
             }
         }
     }
-    fn mapping(&mut self, keys: &Vec<u64>) -> bool {
+    fn mapping(&mut self, keys: &[u64]) -> bool {
         let c: u64 = self.size;
         let mut h: Vec<(u64,usize)> = vec![(0,0); c as usize];
         for i in 0..keys.len() {
@@ -124,27 +137,103 @@ impl XorFilter {
     }
 
     pub(crate) fn fingerprint(&self, key: u64) -> u32 {
-        return hash(key,self.l, self.hashes[0].0, self.hashes[0].1, self.hashes[0].2) as u32;
+        return self.hashes[0].hash_bits(key, self.l);
     }
     fn hash0(&self, key: u64) -> u32 {
         let bound = self.size / 3;
-        let res = hash(key,self.l, self.hashes[0].0, self.hashes[0].1, self.hashes[0].2) % bound as u32;
+        let res = self.hashes[0].hash_bits(key, self.l) % bound as u32;
         //println!("'{}','{}'",0, res);
         return res;
 
     }
     fn hash1(&self, key: u64) -> u32 {
         let bound = self.size / 3;
-        let mut res = hash(key,self.l, self.hashes[1].0, self.hashes[1].1, self.hashes[1].2) % bound as u32;
+        let mut res = self.hashes[1].hash_bits(key, self.l) % bound as u32;
         res = (bound + res as u64) as u32;
         //println!("'{}','{}'",1, res);
         return res
     }
     fn hash2(&self, key: u64) -> u32 {
         let bound = self.size / 3;
-        let mut res = (hash(key, self.l, self.hashes[2].0, self.hashes[2].1, self.hashes[2].2)) % bound as u32;
+        let mut res = (self.hashes[2].hash_bits(key, self.l)) % bound as u32;
         res = ((2 * bound) + res as u64) as u32;
         //println!("'{}','{}'",2, res);
         return res;
     }
-}
\ No newline at end of file
+}
+
+impl<H: KeyHasher> Filter for XorFilter32<H> {
+    fn member(&self, k: u64) -> bool {
+        let h0 = self.hash0(k);
+        let h1 = self.hash1(k);
+        let h2 = self.hash2(k);
+        let f = self.fingerprint(k);
+        return f == (self.fingerprints[h0 as usize] ^ self.fingerprints[h1 as usize] ^ self.fingerprints[h2 as usize]);
+    }
+}
+
+impl<H: KeyHasher> StaticFilter for XorFilter32<H> {
+    fn from_keys(keys: &[u64]) -> Self {
+        return Self::with_hasher(keys);
+    }
+}
+
+impl<H: SerializableHasher> SerializableFilter for XorFilter32<H> {
+    const KIND: FilterKind = FilterKind::Xor32;
+    const HASHER: HasherKind = H::KIND;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_u64(self.size);
+        enc.put_u64(self.l as u64);
+        enc.put_hashers(&self.hashes);
+        enc.put_u32s(&self.fingerprints);
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let size = dec.get_u64()?;
+        let l = dec.get_u32()?;
+        let hashes = dec.get_hashers(Some(3))?;
+        let fingerprints = dec.get_u32s()?;
+        check_xor(size, l, fingerprints.len())?;
+        return Ok(XorFilter32 {
+            fingerprints,
+            hashes,
+            seed,
+            sigma: vec![],
+            size,
+            l,
+        });
+    }
+}
+
+impl<H: KeyHasher> FilterStats for XorFilter32<H> {
+    // the table was sized to 1.23n + 32 slots for n keys, so this is the n it was built from, give or take
+    // rounding.
+    fn len(&self) -> u64 {
+        return (self.size.saturating_sub(32) as f64 / 1.23).round() as u64;
+    }
+
+    fn capacity(&self) -> u64 {
+        return self.len();
+    }
+
+    fn slots(&self) -> u64 {
+        return self.size;
+    }
+
+    // building assigns every key a slot of its own.
+    fn occupied(&self) -> u64 {
+        return self.len();
+    }
+
+    fn size_in_bits(&self) -> usize {
+        return self.fingerprints.len() * 32;
+    }
+
+    // fingerprints are l bit hashes, cut to 32 bits.
+    fn false_positive_rate(&self) -> f64 {
+        return 0.5f64.powi(self.l.min(32) as i32);
+    }
+}
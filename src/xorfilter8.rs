@@ -0,0 +1,256 @@
+use std::collections::VecDeque;
+use crate::utils;
+
+use rand_chacha::ChaCha8Rng;
+use crate::hasher::{KeyHasher, MultiplyShift};
+use crate::filter::{Filter, FilterStats, StaticFilter};
+use crate::serialize::{check_xor, Decoder, Encoder, FilterKind, HasherKind, SerializableFilter, SerializableHasher, SerializeError};
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct XorFilter8<H = MultiplyShift> {
+    fingerprints: Vec<u8>,
+    hashes: Vec<H>,
+    seed: u64,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    sigma: Vec<(u64, usize)>,
+    size: u64,
+    l: u32
+}
+impl XorFilter8 {
+    pub fn new(keys: &[u64]) -> XorFilter8 {
+        return Self::with_hasher(keys);
+    }
+}
+
+impl<H: KeyHasher> XorFilter8<H> {
+    // same as new, but draws the hash functions from H instead of multiply-shift.
+    pub fn with_hasher(keys: &[u64]) -> Self {
+        return Self::with_seed(keys, rand::random());
+    }
+
+    // the same seed gives the same hash functions, and so the same filter, on any machine.
+    pub fn with_seed(keys: &[u64], seed: u64) -> Self {
+        let mut rng = utils::seeded_rng(seed);
+        let mut filter = XorFilter8 {
+            fingerprints: vec![],
+            hashes: vec![],
+            seed,
+            sigma: vec![],
+            size: 0,
+            l: 0,
+        };
+
+        filter.size = ((1.23 * keys.len() as f64).floor() + 32.0) as u64;
+
+        filter.l = utils::log_base(filter.size as f64, 2f64) as u32;
+        filter.construct(keys, &mut rng);
+        return filter;
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    fn construct(&mut self, keys: &[u64], rng: &mut ChaCha8Rng) {
+        let mut finished = false;
+        while !finished {
+            let mut hash_functions = Vec::new();
+
+            for _ in 0..=2 {
+                hash_functions.push(H::from_rng(rng));
+            }
+            self.hashes = hash_functions;
+            if self.mapping(keys) {
+                finished = true;
+                //println!("mapping succeeded!");
+                self.assign();
+            }
+        }
+    }
+    fn mapping(&mut self, keys: &[u64]) -> bool {
+        let c: u64 = self.size;
+        let mut h: Vec<(u64,usize)> = vec![(0,0); c as usize];
+        for i in 0..keys.len() {
+            let x = keys[i];
+            let h0 = self.hash0(x);
+            let h1 = self.hash1(x);
+            let h2 = self.hash2(x);
+            h[h0 as usize] = ((x ^ h[h0 as usize].0), h[h0 as usize].1 + 1);
+            h[h1 as usize] = ((x ^ h[h1 as usize].0), h[h1 as usize].1 + 1);
+            h[h2 as usize] = ((x ^ h[h2 as usize].0), h[h2 as usize].1 + 1);
+        }
+        let mut q = VecDeque::new();
+        let mut sigma = Vec::new();
+        for i in 0..h.len() {
+            if h[i].1 == 1 {
+                q.push_back(i);
+            }
+        }
+        while !q.is_empty() {
+            let i = q.pop_front().unwrap();
+            if h[i].1 == 1 {
+                let x = h[i].0;
+                // needs to be a stack.
+                sigma.push((x, i));
+                let h0 = self.hash0(x);
+                let h1 = self.hash1(x);
+                let h2 = self.hash2(x);
+                // remove x from h[h_j]
+                h[h0 as usize] = ((x ^ h[h0 as usize].0), h[h0 as usize].1 - 1);
+                h[h1 as usize] = ((x ^ h[h1 as usize].0), h[h1 as usize].1 - 1);
+                h[h2 as usize] = ((x ^ h[h2 as usize].0), h[h2 as usize].1 - 1);
+                if h[h0 as usize].1 == 1 {
+                    q.push_back(h0 as usize);
+                }
+                if h[h1 as usize].1 == 1 {
+                    q.push_back(h1 as usize);
+                }
+                if h[h2 as usize].1 == 1 {
+                    q.push_back(h2 as usize);
+                }
+            }
+        }
+        if sigma.len() == keys.len() {
+            //println!("'{}'", sigma.len());
+            self.sigma = sigma;
+            return true;
+        }
+        else {
+            //println!("'{:?}'", sigma);
+            //println!("'{}'", sigma.len());
+            return false;
+        }
+    }
+    fn assign(&mut self) {
+        let c: u64 = self.size;
+        let mut b = vec![0; c as usize ];
+        for j in (0..self.sigma.len()).rev() {
+            let (x,i) = self.sigma[j];
+            b[i] = self.fingerprint(x) ^ (b[self.hash0(x) as usize] ^ b[self.hash1(x) as usize] ^ b[self.hash2(x) as usize]);
+        }
+        self.fingerprints = b;
+
+        // clear sigma. it is no longer needed
+        self.sigma = Vec::new();
+    }
+
+    pub(crate) fn fingerprint(&self, key: u64) -> u8 {
+        return self.hashes[0].hash_bits(key, self.l) as u8;
+    }
+    fn hash0(&self, key: u64) -> u32 {
+        let bound = self.size / 3;
+        let res = self.hashes[0].hash_bits(key, self.l) % bound as u32;
+        //println!("'{}','{}'",0, res);
+        return res;
+
+    }
+    fn hash1(&self, key: u64) -> u32 {
+        let bound = self.size / 3;
+        let mut res = self.hashes[1].hash_bits(key, self.l) % bound as u32;
+        res = (bound + res as u64) as u32;
+        //println!("'{}','{}'",1, res);
+        return res
+    }
+    fn hash2(&self, key: u64) -> u32 {
+        let bound = self.size / 3;
+        let mut res = (self.hashes[2].hash_bits(key, self.l)) % bound as u32;
+        res = ((2 * bound) + res as u64) as u32;
+        //println!("'{}','{}'",2, res);
+        return res;
+    }
+}
+
+impl<H: KeyHasher> Filter for XorFilter8<H> {
+    fn member(&self, k: u64) -> bool {
+        let h0 = self.hash0(k);
+        let h1 = self.hash1(k);
+        let h2 = self.hash2(k);
+        let f = self.fingerprint(k);
+        return f == (self.fingerprints[h0 as usize] ^ self.fingerprints[h1 as usize] ^ self.fingerprints[h2 as usize]);
+    }
+
+    // hash a batch of keys and prefetch their three fingerprints, then check them.
+    fn member_batch(&self, keys: &[u64], out: &mut [bool]) {
+        assert_eq!(keys.len(), out.len(), "member_batch needs one output per key");
+        let mut slots = [(0u32, 0u32, 0u32); utils::BATCH];
+        for (keys, out) in keys.chunks(utils::BATCH).zip(out.chunks_mut(utils::BATCH)) {
+            for i in 0..keys.len() {
+                slots[i] = (self.hash0(keys[i]), self.hash1(keys[i]), self.hash2(keys[i]));
+                utils::prefetch(&self.fingerprints[slots[i].0 as usize]);
+                utils::prefetch(&self.fingerprints[slots[i].1 as usize]);
+                utils::prefetch(&self.fingerprints[slots[i].2 as usize]);
+            }
+            for i in 0..keys.len() {
+                let (h0, h1, h2) = slots[i];
+                out[i] = self.fingerprint(keys[i]) ==
+                    (self.fingerprints[h0 as usize] ^ self.fingerprints[h1 as usize] ^ self.fingerprints[h2 as usize]);
+            }
+        }
+    }
+}
+
+impl<H: KeyHasher> StaticFilter for XorFilter8<H> {
+    fn from_keys(keys: &[u64]) -> Self {
+        return Self::with_hasher(keys);
+    }
+}
+
+impl<H: SerializableHasher> SerializableFilter for XorFilter8<H> {
+    const KIND: FilterKind = FilterKind::Xor8;
+    const HASHER: HasherKind = H::KIND;
+
+    fn encode(&self, enc: &mut Encoder) {
+        enc.put_u64(self.seed);
+        enc.put_u64(self.size);
+        enc.put_u64(self.l as u64);
+        enc.put_hashers(&self.hashes);
+        enc.put_u8s(&self.fingerprints);
+    }
+
+    fn decode(dec: &mut Decoder) -> Result<Self, SerializeError> {
+        let seed = dec.get_u64()?;
+        let size = dec.get_u64()?;
+        let l = dec.get_u32()?;
+        let hashes = dec.get_hashers(Some(3))?;
+        let fingerprints = dec.get_u8s()?;
+        check_xor(size, l, fingerprints.len())?;
+        return Ok(XorFilter8 {
+            fingerprints,
+            hashes,
+            seed,
+            sigma: vec![],
+            size,
+            l,
+        });
+    }
+}
+
+impl<H: KeyHasher> FilterStats for XorFilter8<H> {
+    // the table was sized to 1.23n + 32 slots for n keys, so this is the n it was built from, give or take
+    // rounding.
+    fn len(&self) -> u64 {
+        return (self.size.saturating_sub(32) as f64 / 1.23).round() as u64;
+    }
+
+    fn capacity(&self) -> u64 {
+        return self.len();
+    }
+
+    fn slots(&self) -> u64 {
+        return self.size;
+    }
+
+    // building assigns every key a slot of its own.
+    fn occupied(&self) -> u64 {
+        return self.len();
+    }
+
+    fn size_in_bits(&self) -> usize {
+        return self.fingerprints.len() * 8;
+    }
+
+    // fingerprints are l bit hashes, cut to 8 bits.
+    fn false_positive_rate(&self) -> f64 {
+        return 0.5f64.powi(self.l.min(8) as i32);
+    }
+}